@@ -51,4 +51,18 @@ keybind!(StepForwardKeybind, "Step Forward", NONE, Period);
 keybind!(PrevFrameKeybind, "Previous Frame", COMMAND, Comma);
 keybind!(NextFrameKeybind, "Next Frame", COMMAND, Period);
 
+keybind!(SelectAllKeybind, "Select All", COMMAND, A);
+keybind!(SelectLayerKeybind, "Select All in Layer", ALT, A);
+keybind!(SelectSameColorKeybind, "Select Same Color", ALT, C);
+keybind!(SelectFilledKeybind, "Select Filled", ALT, F);
+keybind!(SelectUnfilledKeybind, "Select Unfilled", ALT, U);
+keybind!(InvertSelectionKeybind, "Invert Selection", COMMAND, I);
+keybind!(GrowSelectionKeybind, "Grow Selection", ALT, G);
+
+keybind!(BringToFrontKeybind, "Bring to Front", SHIFT, CloseBracket);
+keybind!(BringForwardKeybind, "Bring Forward", COMMAND, CloseBracket);
+keybind!(SendBackwardKeybind, "Send Backward", COMMAND, OpenBracket);
+keybind!(SendToBackKeybind, "Send to Back", SHIFT, OpenBracket);
+
 keybind!(CenterSceneKeybind, "Center Scene", COMMAND, G);
+keybind!(ToggleSnappingKeybind, "Toggle Snapping", NONE, S);
@@ -1,9 +1,9 @@
 
 use std::{fs, path::PathBuf, sync::{Arc, Mutex}};
 
-use crate::{audio::AudioController, export::export_options::ExportOptionsDialog, panels, project::{graphic::Graphic, obj::{obj_list::ObjListTrait, ObjPtr}}, renderer::scene::SceneRenderer};
+use crate::{audio::AudioController, export::export_options::ExportOptionsDialog, panels, project::{graphic::Graphic, layer::Layer, obj::{obj_event::ObjEventReceiver, obj_list::ObjListTrait, ObjPtr}, sound_instance::SoundInstance}, renderer::scene::SceneRenderer, util::polygon::BooleanOp};
 
-use self::{clipboard::Clipboard, dialog::{DialogManager, DialogsToOpen}, dropped_files::handle_dropped_files, keybind::{Keybind, RedoKeybind, UndoKeybind}, prefs::{prefs_dialog::PrefsDialog, UserPrefs}, splash_screen::SplashScreen, state::EditorState, toasts::Toasts};
+use self::{clipboard::Clipboard, dialog::{DialogManager, DialogsToOpen}, dropped_files::handle_dropped_files, keybind::{BringForwardKeybind, BringToFrontKeybind, GrowSelectionKeybind, InvertSelectionKeybind, Keybind, RedoKeybind, SelectAllKeybind, SelectFilledKeybind, SelectLayerKeybind, SelectSameColorKeybind, SelectUnfilledKeybind, SendBackwardKeybind, SendToBackKeybind, ToggleSnappingKeybind, UndoKeybind}, prefs::{prefs_dialog::PrefsDialog, UserPrefs}, project_problems::ProjectProblemsDialog, splash_screen::SplashScreen, state::EditorState, stroke_commands::ZOrder, toasts::Toasts};
 
 pub mod selection;
 pub mod clipboard;
@@ -15,6 +15,15 @@ pub mod prefs;
 pub mod dropped_files;
 pub mod toasts;
 pub mod keybind;
+pub mod project_problems;
+pub mod stroke_commands;
+pub mod snapping;
+pub mod symmetry;
+pub mod select_commands;
+pub mod symbol_commands;
+
+#[cfg(test)]
+mod tests;
 
 pub struct Editor {
     state: Arc<Mutex<EditorState>>,
@@ -27,8 +36,13 @@ pub struct Editor {
     project_open: bool,
 
     audio: Option<AudioController>,
+    graphic_events: Option<ObjEventReceiver<Graphic>>,
+    layer_events: Option<ObjEventReceiver<Layer>>,
+    sound_instance_events: Option<ObjEventReceiver<SoundInstance>>,
     prev_open_graphic: ObjPtr<Graphic>,
-    prev_playing: bool
+    prev_playing: bool,
+    #[cfg(debug_assertions)]
+    prev_diagnostics: Vec<crate::project::validate::Diagnostic>
 }
 
 pub struct EditorSystems<'a> {
@@ -82,8 +96,13 @@ impl Editor {
             project_open: false,
 
             audio,
+            graphic_events: None,
+            layer_events: None,
+            sound_instance_events: None,
             prev_open_graphic: ObjPtr::null(),
-            prev_playing: false
+            prev_playing: false,
+            #[cfg(debug_assertions)]
+            prev_diagnostics: Vec::new()
         };
         
         res
@@ -148,8 +167,21 @@ impl Editor {
 
         self.dialog.render(ctx, state, &mut systems);
 
+        // Catch broken invariants as soon as the action that caused them happens, without reopening the dialog for problems that were already reported
+        #[cfg(debug_assertions)]
+        if state.actions.take_changed() {
+            let diagnostics = state.project.validate();
+            if !diagnostics.is_empty() && diagnostics != self.prev_diagnostics {
+                systems.dialog.open_dialog(ProjectProblemsDialog::new());
+            }
+            self.prev_diagnostics = diagnostics;
+        }
+
         let _ = std::fs::write(self.config_path.join("dock.json"), serde_json::json!(self.panels).to_string());
 
+        for msg in state.error_messages.drain(..) {
+            systems.toasts.error_toast(msg);
+        }
         systems.toasts.render(ctx);
 
         if self.project_open {
@@ -158,16 +190,19 @@ impl Editor {
             });
         }
 
-        if state.project.graphics.mutated() || state.project.layers.mutated() || state.project.sound_instances.mutated()
+        state.project.garbage_collect_objs();
+
+        // Garbage collection happens first so that dropped objects are also picked up
+        let graphics_changed = list_changed(&mut self.graphic_events, &mut state.project.graphics);
+        let layers_changed = list_changed(&mut self.layer_events, &mut state.project.layers);
+        let sound_instances_changed = list_changed(&mut self.sound_instance_events, &mut state.project.sound_instances);
+        if graphics_changed || layers_changed || sound_instances_changed
            || self.prev_open_graphic != state.open_graphic || self.prev_playing != state.playing {
-            // println!("{}")
             set_audio_data(state, &mut self.audio); 
         }
         self.prev_open_graphic = state.open_graphic; 
         self.prev_playing = state.playing;
 
-        state.project.garbage_collect_objs();
-
         if let Some(gfx) = state.project.graphics.get(state.open_graphic) {
             let gfx_len_in_samples = ((gfx.len as f32) * state.frame_len() * state.sample_rate()) as i64; 
             if state.time < 0 {
@@ -227,6 +262,70 @@ impl Editor {
                     egui::Button::new("Redo").shortcut_text(ui.ctx().format_shortcut(&self.prefs.get::<RedoKeybind>()))).clicked() {
                     state.actions.redo(&mut state.project);
                 }
+                ui.separator();
+                ui.menu_button("Select", |ui| {
+                    let commands: [(&str, egui::KeyboardShortcut, fn(&mut EditorState)); 7] = [
+                        ("All", self.prefs.get::<SelectAllKeybind>(), select_commands::select_all),
+                        ("All in Layer", self.prefs.get::<SelectLayerKeybind>(), select_commands::select_all_in_layer),
+                        ("Same Color", self.prefs.get::<SelectSameColorKeybind>(), select_commands::select_same_color),
+                        ("Filled", self.prefs.get::<SelectFilledKeybind>(), |state| select_commands::select_filled(state, true)),
+                        ("Unfilled", self.prefs.get::<SelectUnfilledKeybind>(), |state| select_commands::select_filled(state, false)),
+                        ("Invert", self.prefs.get::<InvertSelectionKeybind>(), select_commands::invert_selection),
+                        ("Grow", self.prefs.get::<GrowSelectionKeybind>(), select_commands::grow_selection),
+                    ];
+                    for (name, shortcut, command) in commands {
+                        if ui.add(egui::Button::new(name).shortcut_text(ui.ctx().format_shortcut(&shortcut))).clicked() {
+                            command(state);
+                            ui.close_menu();
+                        }
+                    }
+                });
+                ui.separator();
+                if ui.button("Check Project").clicked() {
+                    let mut dialogs_to_open = DialogsToOpen::new();
+                    dialogs_to_open.open_dialog(ProjectProblemsDialog::new());
+                    self.dialog.open_dialogs(dialogs_to_open);
+                    ui.close_menu();
+                }
+            });
+            ui.menu_button("Stroke", |ui| {
+                let scene_selected = state.selection.is_scene();
+                for op in [BooleanOp::Union, BooleanOp::Difference, BooleanOp::Intersection, BooleanOp::Exclude] {
+                    if ui.add_enabled(scene_selected, egui::Button::new(op.name())).clicked() {
+                        stroke_commands::boolean_selected_strokes(state, op);
+                        ui.close_menu();
+                    }
+                }
+                ui.separator();
+                if ui.add_enabled(scene_selected, egui::Button::new("Outline Stroke")).clicked() {
+                    stroke_commands::outline_selected_strokes(state);
+                    ui.close_menu();
+                }
+                if ui.add_enabled(scene_selected, egui::Button::new("Convert to Symbol")).clicked() {
+                    symbol_commands::convert_selection_to_symbol(state);
+                    ui.close_menu();
+                }
+                ui.separator();
+                let z_orders = [
+                    (ZOrder::ToFront, self.prefs.get::<BringToFrontKeybind>()),
+                    (ZOrder::Forward, self.prefs.get::<BringForwardKeybind>()),
+                    (ZOrder::Backward, self.prefs.get::<SendBackwardKeybind>()),
+                    (ZOrder::ToBack, self.prefs.get::<SendToBackKeybind>())
+                ];
+                for (z_order, shortcut) in z_orders {
+                    if ui.add_enabled(scene_selected, egui::Button::new(z_order.name()).shortcut_text(ui.ctx().format_shortcut(&shortcut))).clicked() {
+                        stroke_commands::reorder_selected_strokes(state, z_order);
+                        ui.close_menu();
+                    }
+                }
+                if ui.add_enabled(scene_selected, egui::Button::new("Move to Layer Above")).clicked() {
+                    stroke_commands::move_selected_strokes_to_layer(state, true);
+                    ui.close_menu();
+                }
+                if ui.add_enabled(scene_selected, egui::Button::new("Move to Layer Below")).clicked() {
+                    stroke_commands::move_selected_strokes_to_layer(state, false);
+                    ui.close_menu();
+                }
             });
             ui.menu_button("View", |ui| {
                 ui.menu_button("Add Panel", |ui| {
@@ -245,7 +344,43 @@ impl Editor {
                     if ui.button("Colors").clicked() {
                         self.panels.add_panel(panels::Panel::Color(panels::colors::ColorPanel::new()));
                     }
-                })
+                });
+                ui.separator();
+                ui.checkbox(&mut state.camera_view, "Camera View");
+                ui.separator();
+                ui.checkbox(&mut state.snapping.show_grid, "Show Grid");
+                ui.horizontal(|ui| {
+                    ui.label("Grid size:");
+                    ui.add(egui::DragValue::new(&mut state.snapping.grid_size).clamp_range(1.0..=1000.0).speed(1.0));
+                });
+                ui.menu_button("Guides", |ui| {
+                    ui.checkbox(&mut state.snapping.show_guides, "Show Guides");
+                    ui.checkbox(&mut state.snapping.lock_guides, "Lock Guides");
+                    let graphic_open = state.project.graphics.get(state.open_graphic).is_some();
+                    if ui.add_enabled(graphic_open, egui::Button::new("Add Horizontal Guide")).clicked() {
+                        snapping::add_guide(state, false);
+                        ui.close_menu();
+                    }
+                    if ui.add_enabled(graphic_open, egui::Button::new("Add Vertical Guide")).clicked() {
+                        snapping::add_guide(state, true);
+                        ui.close_menu();
+                    }
+                    if ui.add_enabled(graphic_open, egui::Button::new("Clear Guides")).clicked() {
+                        snapping::set_guides(state, Vec::new());
+                        ui.close_menu();
+                    }
+                    ui.label("Drag guides to move them, right-click to remove them.");
+                });
+                ui.menu_button("Snapping", |ui| {
+                    ui.checkbox(&mut state.snapping.enabled, "Snap");
+                    ui.add_enabled_ui(state.snapping.enabled, |ui| {
+                        ui.checkbox(&mut state.snapping.snap_grid, "Grid");
+                        ui.checkbox(&mut state.snapping.snap_guides, "Guides");
+                        ui.checkbox(&mut state.snapping.snap_endpoints, "Stroke Ends");
+                        ui.checkbox(&mut state.snapping.snap_curves, "Curves");
+                        ui.checkbox(&mut state.snapping.snap_frame, "Frame Edges");
+                    });
+                });
             });
         });
     }
@@ -261,6 +396,42 @@ impl Editor {
             state.reset_tool();
             state.actions.redo(&mut state.project);
         }
+        if ToggleSnappingKeybind::consume(ui, &mut self.prefs) {
+            state.snapping.enabled = !state.snapping.enabled;
+        }
+        if SelectAllKeybind::consume(ui, &mut self.prefs) {
+            select_commands::select_all(state);
+        }
+        if SelectLayerKeybind::consume(ui, &mut self.prefs) {
+            select_commands::select_all_in_layer(state);
+        }
+        if SelectSameColorKeybind::consume(ui, &mut self.prefs) {
+            select_commands::select_same_color(state);
+        }
+        if SelectFilledKeybind::consume(ui, &mut self.prefs) {
+            select_commands::select_filled(state, true);
+        }
+        if SelectUnfilledKeybind::consume(ui, &mut self.prefs) {
+            select_commands::select_filled(state, false);
+        }
+        if InvertSelectionKeybind::consume(ui, &mut self.prefs) {
+            select_commands::invert_selection(state);
+        }
+        if GrowSelectionKeybind::consume(ui, &mut self.prefs) {
+            select_commands::grow_selection(state);
+        }
+        if BringToFrontKeybind::consume(ui, &mut self.prefs) {
+            stroke_commands::reorder_selected_strokes(state, ZOrder::ToFront);
+        }
+        if BringForwardKeybind::consume(ui, &mut self.prefs) {
+            stroke_commands::reorder_selected_strokes(state, ZOrder::Forward);
+        }
+        if SendBackwardKeybind::consume(ui, &mut self.prefs) {
+            stroke_commands::reorder_selected_strokes(state, ZOrder::Backward);
+        }
+        if SendToBackKeybind::consume(ui, &mut self.prefs) {
+            stroke_commands::reorder_selected_strokes(state, ZOrder::ToBack);
+        }
         
 
         state.just_pasted = false;
@@ -287,6 +458,10 @@ impl Editor {
 
 }
 
+fn list_changed<L: ObjListTrait>(receiver: &mut Option<ObjEventReceiver<L::ObjType>>, list: &mut L) -> bool {
+    ObjEventReceiver::poll(receiver, list).map(|events| !events.is_empty()).unwrap_or(true)
+}
+
 fn set_audio_data(state: &EditorState, audio_controller: &mut Option<AudioController>) {
     if let Some(audio) = audio_controller {
         let audio_state = audio.state.clone();
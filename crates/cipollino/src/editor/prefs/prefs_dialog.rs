@@ -1,7 +1,7 @@
 
 use unique_type_id::UniqueTypeId;
 
-use crate::{editor::{dialog::Dialog, keybind::{CenterSceneKeybind, DeleteKeybind, Keybind, NewFrameKeybind, NextFrameKeybind, PlayKeybind, PrevFrameKeybind, RedoKeybind, StepBackKeybind, StepForwardKeybind, UndoKeybind}, state::EditorState, EditorSystems}, tools::{bucket::BucketToolKeybind, color_picker::ColorPickerToolKeybind, line::LineToolKeybind, pencil::PencilToolKeybind, scissors::ScissorsToolKeybind, select::SelectToolKeybind}};
+use crate::{editor::{dialog::Dialog, keybind::{BringForwardKeybind, BringToFrontKeybind, CenterSceneKeybind, DeleteKeybind, GrowSelectionKeybind, InvertSelectionKeybind, Keybind, NewFrameKeybind, NextFrameKeybind, PlayKeybind, PrevFrameKeybind, RedoKeybind, SelectAllKeybind, SelectFilledKeybind, SelectLayerKeybind, SelectSameColorKeybind, SelectUnfilledKeybind, SendBackwardKeybind, SendToBackKeybind, StepBackKeybind, StepForwardKeybind, ToggleSnappingKeybind, UndoKeybind}, state::EditorState, EditorSystems}, tools::{bucket::BucketToolKeybind, camera::CameraToolKeybind, color_picker::ColorPickerToolKeybind, eraser::EraserToolKeybind, line::LineToolKeybind, node::NodeToolKeybind, pencil::PencilToolKeybind, scissors::ScissorsToolKeybind, select::SelectToolKeybind, shape::{ellipse::EllipseToolKeybind, polygon::PolygonToolKeybind, rectangle::RectangleToolKeybind}}};
 
 #[derive(UniqueTypeId)]
 pub struct PrefsDialog {
@@ -58,12 +58,31 @@ impl Dialog for PrefsDialog {
             self.render_keybind_setting::<UndoKeybind>(ui, systems, &key_down);
             self.render_keybind_setting::<RedoKeybind>(ui, systems, &key_down);
 
+            self.render_keybind_setting::<SelectAllKeybind>(ui, systems, &key_down);
+            self.render_keybind_setting::<SelectLayerKeybind>(ui, systems, &key_down);
+            self.render_keybind_setting::<SelectSameColorKeybind>(ui, systems, &key_down);
+            self.render_keybind_setting::<SelectFilledKeybind>(ui, systems, &key_down);
+            self.render_keybind_setting::<SelectUnfilledKeybind>(ui, systems, &key_down);
+            self.render_keybind_setting::<InvertSelectionKeybind>(ui, systems, &key_down);
+            self.render_keybind_setting::<GrowSelectionKeybind>(ui, systems, &key_down);
+
+            self.render_keybind_setting::<BringToFrontKeybind>(ui, systems, &key_down);
+            self.render_keybind_setting::<BringForwardKeybind>(ui, systems, &key_down);
+            self.render_keybind_setting::<SendBackwardKeybind>(ui, systems, &key_down);
+            self.render_keybind_setting::<SendToBackKeybind>(ui, systems, &key_down);
+
             self.render_keybind_setting::<SelectToolKeybind>(ui, systems, &key_down);
+            self.render_keybind_setting::<NodeToolKeybind>(ui, systems, &key_down);
             self.render_keybind_setting::<PencilToolKeybind>(ui, systems, &key_down);
+            self.render_keybind_setting::<EraserToolKeybind>(ui, systems, &key_down);
             self.render_keybind_setting::<BucketToolKeybind>(ui, systems, &key_down);
             self.render_keybind_setting::<ColorPickerToolKeybind>(ui, systems, &key_down);
             self.render_keybind_setting::<LineToolKeybind>(ui, systems, &key_down);
+            self.render_keybind_setting::<RectangleToolKeybind>(ui, systems, &key_down);
+            self.render_keybind_setting::<EllipseToolKeybind>(ui, systems, &key_down);
+            self.render_keybind_setting::<PolygonToolKeybind>(ui, systems, &key_down);
             self.render_keybind_setting::<ScissorsToolKeybind>(ui, systems, &key_down);
+            self.render_keybind_setting::<CameraToolKeybind>(ui, systems, &key_down);
 
             self.render_keybind_setting::<PlayKeybind>(ui, systems, &key_down);
             self.render_keybind_setting::<NewFrameKeybind>(ui, systems, &key_down);
@@ -73,6 +92,7 @@ impl Dialog for PrefsDialog {
             self.render_keybind_setting::<NextFrameKeybind>(ui, systems, &key_down);
 
             self.render_keybind_setting::<CenterSceneKeybind>(ui, systems, &key_down);
+            self.render_keybind_setting::<ToggleSnappingKeybind>(ui, systems, &key_down);
         });
         false
     }
@@ -0,0 +1,74 @@
+
+use unique_type_id::UniqueTypeId;
+
+use crate::project::{action::Action, validate::Diagnostic};
+
+use super::{dialog::Dialog, state::EditorState, EditorSystems};
+
+#[derive(UniqueTypeId)]
+pub struct ProjectProblemsDialog {
+    // Validating checks the disk for every sound, so it's only redone when the project is changed or on request
+    diagnostics: Option<(u64, Vec<Diagnostic>)>
+}
+
+impl ProjectProblemsDialog {
+
+    pub fn new() -> Self {
+        Self {
+            diagnostics: None
+        }
+    }
+
+}
+
+impl Dialog for ProjectProblemsDialog {
+
+    fn render(&mut self, ui: &mut egui::Ui, state: &mut EditorState, _systems: &mut EditorSystems) -> bool {
+        let version = state.actions.version();
+        if ui.button("Refresh").clicked() || self.diagnostics.as_ref().map(|(checked, _)| *checked != version).unwrap_or(true) {
+            self.diagnostics = Some((version, state.project.validate()));
+        }
+        let diagnostics = self.diagnostics.as_ref().map(|(_, diagnostics)| diagnostics.clone()).unwrap_or_default();
+        if diagnostics.is_empty() {
+            ui.label("No problems found.");
+            return ui.button("Close").clicked();
+        }
+
+        let mut fixes = Vec::new();
+        egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+            egui::Grid::new(ui.next_auto_id()).striped(true).show(ui, |ui| {
+                for diagnostic in &diagnostics {
+                    ui.label(diagnostic.message(&state.project));
+                    if ui.button("Fix").on_hover_text(diagnostic.fix_description()).clicked() {
+                        fixes.push(*diagnostic);
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+        if ui.button("Fix All").clicked() {
+            fixes = diagnostics.clone();
+        }
+
+        let mut action = Action::new();
+        for fix in fixes {
+            if let Some(act) = fix.fix(&mut state.project, state.open_graphic) {
+                action.add(act);
+            }
+        }
+        if !action.actions.is_empty() {
+            state.actions.add(action);
+        }
+
+        false
+    }
+
+    fn title(&self, _state: &EditorState) -> String {
+        "Project Problems".to_owned()
+    }
+
+    fn unique_dialog() -> bool {
+        true
+    }
+
+}
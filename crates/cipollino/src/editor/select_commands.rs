@@ -0,0 +1,123 @@
+
+use glam::Vec2;
+
+use crate::{project::{layer::Layer, obj::{obj_list::ObjListTrait, ObjPtr}, stroke::{iter_bezier_segments, Stroke}, Project}, util::{geo::LineSegment, polygon::{point_in_polygon, polygon_bounds}}};
+
+use super::{selection::Selection, state::EditorState};
+
+// The stroke's centre lines along with how far the stroke extends out from them
+fn stroke_outline(stroke: &Stroke) -> (Vec<Vec<Vec2>>, f32) {
+    let lines = stroke.points.iter().map(|chain| {
+        let mut pts = Vec::new();
+        for segment in iter_bezier_segments(chain) {
+            pts.extend_from_slice(segment.to_discrete::<10>().as_slice());
+        }
+        pts
+    }).collect();
+    (lines, if stroke.filled { 0.0 } else { stroke.r })
+}
+
+pub fn strokes_touch(a: &Stroke, b: &Stroke) -> bool {
+    let (a_lines, a_r) = stroke_outline(a);
+    let (b_lines, b_r) = stroke_outline(b);
+    let (Some((a_min, a_max)), Some((b_min, b_max))) = (polygon_bounds(&a_lines), polygon_bounds(&b_lines)) else { return false; };
+    let reach = a_r + b_r;
+    if a_min.x > b_max.x + reach || b_min.x > a_max.x + reach || a_min.y > b_max.y + reach || b_min.y > a_max.y + reach {
+        return false;
+    }
+
+    for a_line in &a_lines {
+        for a_seg in a_line.windows(2) {
+            let a_seg = LineSegment::new(a_seg[0], a_seg[1]);
+            for b_line in &b_lines {
+                for b_seg in b_line.windows(2) {
+                    if a_seg.distance(&LineSegment::new(b_seg[0], b_seg[1])) <= reach {
+                        return true;
+                    }
+                }
+            }
+        }
+    }
+
+    // One stroke might lie entirely inside a filled one
+    let inside = |lines: &Vec<Vec<Vec2>>, filled: &Stroke| {
+        filled.filled && lines.iter().flatten().next().map(|pt| point_in_polygon(&filled.polygon(), *pt)).unwrap_or(false)
+    };
+    inside(&a_lines, b) || inside(&b_lines, a)
+}
+
+// The candidate strokes touching any of the given strokes
+pub fn touching_strokes(project: &Project, strokes: &Vec<ObjPtr<Stroke>>, candidates: &Vec<ObjPtr<Stroke>>) -> Vec<ObjPtr<Stroke>> {
+    candidates.iter().copied().filter(|candidate| {
+        let Some(candidate_stroke) = project.strokes.get(*candidate) else { return false; };
+        strokes.iter().any(|stroke| {
+            *stroke != *candidate && project.strokes.get(*stroke).map(|stroke| strokes_touch(stroke, candidate_stroke)).unwrap_or(false)
+        })
+    }).collect()
+}
+
+// The candidate strokes sharing a colour with any of the given strokes. Palette colours match every stroke using them, whatever their current value.
+pub fn same_color_strokes(project: &Project, strokes: &Vec<ObjPtr<Stroke>>, candidates: &Vec<ObjPtr<Stroke>>) -> Vec<ObjPtr<Stroke>> {
+    let colors: Vec<_> = strokes.iter().filter_map(|stroke| project.strokes.get(*stroke)).map(|stroke| stroke.color).collect();
+    candidates.iter().copied().filter(|candidate| {
+        project.strokes.get(*candidate).map(|candidate| colors.iter().any(|color| color.same_as(&candidate.color))).unwrap_or(false)
+    }).collect()
+}
+
+fn set_selection(state: &mut EditorState, strokes: Vec<ObjPtr<Stroke>>) {
+    state.selection = if strokes.is_empty() { Selection::None } else { Selection::Scene(strokes) };
+    state.reset_tool();
+}
+
+pub fn select_all(state: &mut EditorState) {
+    let strokes = state.visible_strokes_in_layer_space(true);
+    set_selection(state, strokes);
+}
+
+pub fn select_all_in_layer(state: &mut EditorState) {
+    if Layer::locked(&state.project, state.active_layer) {
+        return;
+    }
+    let strokes = state.layer_strokes(state.active_layer);
+    set_selection(state, strokes);
+}
+
+// Compares against the selection, or the current colour when nothing is selected
+pub fn select_same_color(state: &mut EditorState) {
+    let candidates = state.visible_strokes_in_layer_space(true);
+    let selected = state.selection.scene_strokes();
+    let strokes = if selected.is_empty() {
+        candidates.into_iter().filter(|stroke| {
+            state.project.strokes.get(*stroke).map(|stroke| stroke.color.same_as(&state.color)).unwrap_or(false)
+        }).collect()
+    } else {
+        same_color_strokes(&state.project, &selected, &candidates)
+    };
+    set_selection(state, strokes);
+}
+
+// Narrows down the selection, or picks from every visible stroke when nothing is selected
+pub fn select_filled(state: &mut EditorState, filled: bool) {
+    let selected = state.selection.scene_strokes();
+    let candidates = if selected.is_empty() { state.visible_strokes_in_layer_space(true) } else { selected };
+    let strokes = candidates.into_iter().filter(|stroke| {
+        state.project.strokes.get(*stroke).map(|stroke| stroke.filled == filled).unwrap_or(false)
+    }).collect();
+    set_selection(state, strokes);
+}
+
+pub fn invert_selection(state: &mut EditorState) {
+    let selected = state.selection.scene_strokes();
+    let strokes = state.visible_strokes_in_layer_space(true).into_iter().filter(|stroke| !selected.contains(stroke)).collect();
+    set_selection(state, strokes);
+}
+
+pub fn grow_selection(state: &mut EditorState) {
+    let mut strokes = state.selection.scene_strokes();
+    if strokes.is_empty() {
+        return;
+    }
+    let candidates = state.visible_strokes_in_layer_space(true).into_iter().filter(|stroke| !strokes.contains(stroke)).collect();
+    strokes.extend(touching_strokes(&state.project, &strokes, &candidates));
+    set_selection(state, strokes);
+}
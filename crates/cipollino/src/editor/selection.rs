@@ -36,6 +36,14 @@ impl Selection {
         }
     }
 
+    pub fn scene_strokes(&self) -> Vec<ObjPtr<Stroke>> {
+        if let Self::Scene(strokes) = self {
+            strokes.clone()
+        } else {
+            Vec::new()
+        }
+    }
+
     pub fn stroke_selected(&self, stroke: ObjPtr<Stroke>) -> bool {
         if let Self::Scene(strokes) = self {
             strokes.contains(&stroke)
@@ -0,0 +1,174 @@
+
+use glam::{vec2, vec3, Mat4, Vec2};
+
+use crate::project::{action::Action, graphic::{Graphic, Guide}, obj::{obj_list::ObjListTrait, ObjPtr}, stroke::Stroke, Project};
+
+use super::state::EditorState;
+
+// How close to a target a point needs to be to snap to it, relative to the camera size
+pub const SNAP_DIST: f32 = 0.02;
+
+pub struct Snapping {
+    pub enabled: bool,
+
+    pub show_grid: bool,
+    pub grid_size: f32,
+    pub show_guides: bool,
+    pub lock_guides: bool,
+
+    pub snap_grid: bool,
+    pub snap_guides: bool,
+    pub snap_endpoints: bool,
+    pub snap_curves: bool,
+    pub snap_frame: bool,
+
+    // The target last snapped to, shown in the scene until the mouse is released
+    pub indicator: Option<Vec2>
+}
+
+fn transform_pt(trans: Mat4, pt: Vec2) -> Vec2 {
+    let pt = trans.transform_point3(vec3(pt.x, pt.y, 0.0));
+    vec2(pt.x, pt.y)
+}
+
+fn closest(pts: &Vec<Vec2>, pos: Vec2, max_dist: f32) -> Option<Vec2> {
+    let mut best = None;
+    let mut best_dist = max_dist;
+    for pt in pts {
+        let dist = (*pt - pos).length();
+        if dist < best_dist {
+            best_dist = dist;
+            best = Some(*pt);
+        }
+    }
+    best
+}
+
+impl Snapping {
+
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            show_grid: false,
+            grid_size: 50.0,
+            show_guides: true,
+            lock_guides: false,
+            snap_grid: false,
+            snap_guides: true,
+            snap_endpoints: true,
+            snap_curves: false,
+            snap_frame: true,
+            indicator: None
+        }
+    }
+
+    // Finds the target closest to pos. Points like grid points, corners and stroke ends win over lines like guides, curves and frame edges.
+    // pos is in the space given by the space matrix, and the strokes have to be on layers in that space too. The grid, guides and frame stay in the scene's space
+    pub fn find_target(&self, project: &Project, gfx: ObjPtr<Graphic>, strokes: &Vec<ObjPtr<Stroke>>, space: Mat4, pos: Vec2, max_dist: f32) -> Option<Vec2> {
+        if !self.enabled {
+            return None;
+        }
+
+        let local_pos = pos;
+        let pos = transform_pt(space, pos);
+        let mut points = Vec::new();
+        let mut line_points = Vec::new();
+        if self.snap_grid && self.grid_size > 0.0 {
+            points.push((pos / self.grid_size).round() * self.grid_size);
+        }
+        if let Some(gfx) = project.graphics.get(gfx) {
+            if self.snap_guides {
+                for guide in &gfx.guides {
+                    line_points.push(guide.closest_point(pos));
+                }
+                for vertical in gfx.guides.iter().filter(|guide| guide.vertical) {
+                    for horizontal in gfx.guides.iter().filter(|guide| !guide.vertical) {
+                        points.push(vec2(vertical.pos, horizontal.pos));
+                    }
+                }
+            }
+            if self.snap_frame && gfx.clip {
+                let half = vec2(gfx.w as f32, gfx.h as f32) * 0.5;
+                points.push(Vec2::ZERO);
+                for corner in [half, vec2(-half.x, half.y), -half, vec2(half.x, -half.y)] {
+                    points.push(corner);
+                }
+                let clamped = pos.clamp(-half, half);
+                line_points.push(vec2(-half.x, clamped.y));
+                line_points.push(vec2(half.x, clamped.y));
+                line_points.push(vec2(clamped.x, -half.y));
+                line_points.push(vec2(clamped.x, half.y));
+            }
+        }
+
+        let inv_space = space.inverse();
+        let mut points: Vec<Vec2> = points.into_iter().map(|pt| transform_pt(inv_space, pt)).collect();
+        let mut line_points: Vec<Vec2> = line_points.into_iter().map(|pt| transform_pt(inv_space, pt)).collect();
+        let pos = local_pos;
+        for stroke in strokes {
+            let Some(stroke) = project.strokes.get(*stroke) else { continue; };
+            if self.snap_endpoints {
+                for chain in &stroke.points {
+                    if let (Some(first), Some(last)) = (chain.first(), chain.last()) {
+                        points.push(first.pt);
+                        points.push(last.pt);
+                    }
+                }
+            }
+            if self.snap_curves {
+                for segment in stroke.iter_bezier_segments() {
+                    line_points.push(segment.sample(segment.nearest_t(pos)));
+                }
+            }
+        }
+
+        closest(&points, pos, max_dist).or_else(|| closest(&line_points, pos, max_dist))
+    }
+
+    // Finds the smallest offset that snaps any of the given points to a target
+    pub fn find_offset(&self, project: &Project, gfx: ObjPtr<Graphic>, strokes: &Vec<ObjPtr<Stroke>>, space: Mat4, pts: &Vec<Vec2>, max_dist: f32) -> Option<(Vec2, Vec2)> {
+        let mut best = None;
+        let mut best_dist = max_dist;
+        for pt in pts {
+            if let Some(target) = self.find_target(project, gfx, strokes, space, *pt, best_dist) {
+                best_dist = (target - *pt).length();
+                best = Some((target - *pt, target));
+            }
+        }
+        best
+    }
+
+}
+
+fn snap_strokes(state: &EditorState, exclude: &Vec<ObjPtr<Stroke>>) -> Vec<ObjPtr<Stroke>> {
+    state.visible_strokes_in_layer_space(false).into_iter().filter(|stroke| !exclude.contains(stroke)).collect()
+}
+
+// Snaps a point placed by a tool in the active layer's space, ignoring the given strokes
+pub fn snap_point(state: &mut EditorState, pos: Vec2, cam_size: f32, exclude: &Vec<ObjPtr<Stroke>>) -> Vec2 {
+    let strokes = snap_strokes(state, exclude);
+    let target = state.snapping.find_target(&state.project, state.open_graphic, &strokes, state.layer_matrix(), pos, SNAP_DIST * cam_size);
+    state.snapping.indicator = target;
+    target.unwrap_or(pos)
+}
+
+// Snaps a group of points moved together, returning the offset to apply to all of them
+pub fn snap_offset(state: &mut EditorState, pts: &Vec<Vec2>, cam_size: f32, exclude: &Vec<ObjPtr<Stroke>>) -> Vec2 {
+    let strokes = snap_strokes(state, exclude);
+    let res = state.snapping.find_offset(&state.project, state.open_graphic, &strokes, state.layer_matrix(), pts, SNAP_DIST * cam_size);
+    state.snapping.indicator = res.map(|(_, target)| target);
+    res.map(|(offset, _)| offset).unwrap_or(Vec2::ZERO)
+}
+
+pub fn add_guide(state: &mut EditorState, vertical: bool) {
+    let Some(gfx) = state.project.graphics.get(state.open_graphic) else { return; };
+    let mut guides = gfx.guides.clone();
+    guides.push(Guide { vertical, pos: 0.0 });
+    set_guides(state, guides);
+}
+
+pub fn set_guides(state: &mut EditorState, guides: Vec<Guide>) {
+    if let Some(act) = Graphic::set_guides(&mut state.project, state.open_graphic, guides) {
+        state.actions.add(Action::from_list(vec![act]));
+    }
+}
@@ -1,9 +1,9 @@
 
 use std::{path::PathBuf, sync::{Arc, RwLock}};
 
-use crate::{project::{action::ActionManager, graphic::Graphic, layer::{Layer, LayerKind}, obj::{obj_list::ObjListTrait, ObjPtr}, palette::Palette, stroke::{Stroke, StrokeColor}, Project}, tools::{bucket::Bucket, color_picker::ColorPicker, line::Line, pencil::Pencil, scissors::Scissors, select::Select, Tool}};
+use crate::{project::{action::ActionManager, frame::Frame, graphic::Graphic, layer::{Layer, LayerKind}, layer_transform::LayerTransform, obj::{obj_list::ObjListTrait, ObjPtr}, palette::Palette, stroke::{Stroke, StrokeCap, StrokeColor}, Project}, tools::{bucket::Bucket, camera::CameraTool, color_picker::ColorPicker, eraser::Eraser, line::Line, node::NodeTool, pencil::Pencil, scissors::Scissors, select::Select, shape::{ellipse::Ellipse, polygon::Polygon, rectangle::Rectangle, ShapeTool}, Tool}};
 
-use super::{clipboard, selection::{self, Selection}, toasts::Toasts};
+use super::{clipboard, selection::{self, Selection}, snapping::Snapping, symmetry::Symmetry, toasts::Toasts};
 
 pub struct EditorState {
     pub project: Project, 
@@ -31,14 +31,22 @@ pub struct EditorState {
     // Display
     pub onion_before: i32,
     pub onion_after: i32,
+    pub snapping: Snapping,
+    // Look at the open graphic through its camera, if it has one
+    pub camera_view: bool,
 
     // Tool Options
     pub color: StrokeColor,
     pub stroke_r: f32,
     pub stroke_filled: bool,
+    pub stroke_taper_start: f32,
+    pub stroke_taper_end: f32,
+    pub stroke_cap: StrokeCap,
+    pub symmetry: Symmetry,
 
     // Misc
-    pub just_pasted: bool // Tracks if user pasted(Cmd+V) this frame
+    pub just_pasted: bool, // Tracks if user pasted(Cmd+V) this frame
+    pub error_messages: Vec<String> // Shown as toasts at the end of the frame
 }
 
 impl EditorState {
@@ -51,11 +59,17 @@ impl EditorState {
         let color_picker = Arc::new(RwLock::new(ColorPicker::new()));
         let line = Arc::new(RwLock::new(Line::new()));
         let scissors = Arc::new(RwLock::new(Scissors::new()));
+        let eraser = Arc::new(RwLock::new(Eraser::new()));
+        let node = Arc::new(RwLock::new(NodeTool::new()));
+        let rectangle = Arc::new(RwLock::new(ShapeTool::new(Rectangle::new())));
+        let ellipse = Arc::new(RwLock::new(ShapeTool::new(Ellipse::new())));
+        let polygon = Arc::new(RwLock::new(ShapeTool::new(Polygon::new())));
+        let camera = Arc::new(RwLock::new(CameraTool::new()));
         Self {
             project: project, 
 
             actions: ActionManager::new(),
-            tools: vec![select.clone(), pencil, bucket, color_picker, line, scissors],
+            tools: vec![select.clone(), node, pencil, eraser, bucket, color_picker, line, rectangle, ellipse, polygon, scissors, camera],
             curr_tool: select,
 
             open_graphic: ObjPtr::null(),
@@ -70,12 +84,19 @@ impl EditorState {
 
             onion_before: 0,
             onion_after: 0,
+            snapping: Snapping::new(),
+            camera_view: false,
 
             color: StrokeColor::Color(glam::vec4(0.0, 0.0, 0.0, 1.0)),
             stroke_r: 5.0,
             stroke_filled: false,
+            stroke_taper_start: 0.0,
+            stroke_taper_end: 0.0,
+            stroke_cap: StrokeCap::Round,
+            symmetry: Symmetry::new(),
 
-            just_pasted: false
+            just_pasted: false,
+            error_messages: Vec::new()
         }
     }
 
@@ -100,6 +121,10 @@ impl EditorState {
         }
         if layer.kind == LayerKind::Animation {
             if let Some(frame) = layer.get_frame_at(&self.project, time) {
+                // A tween's in-between drawing stands in for the frame's own strokes
+                if Frame::tween_at(&self.project, frame.make_ptr(), time).is_some() {
+                    return;
+                }
                 let frame = frame.get(&self.project);
                 for stroke in &frame.strokes {
                     strokes.push(stroke.make_ptr());
@@ -122,6 +147,30 @@ impl EditorState {
         res
     }
 
+    // Visible strokes on layers placed the same way as the active one, so that tools can work on them in its space
+    pub fn visible_strokes_in_layer_space(&self, ignore_locked: bool) -> Vec<ObjPtr<Stroke>> {
+        let layer_mat = self.layer_matrix();
+        self.visible_strokes(ignore_locked).into_iter().filter(|stroke| {
+            self.project.strokes.get(*stroke)
+                .and_then(|stroke| self.project.frames.get(stroke.frame))
+                .map(|frame| LayerTransform::world_matrix(&self.project, frame.layer, self.frame()) == layer_mat)
+                .unwrap_or(false)
+        }).collect()
+    }
+
+    // Takes points from the active layer's space, where tools work, to the scene's
+    pub fn layer_matrix(&self) -> glam::Mat4 {
+        LayerTransform::world_matrix(&self.project, self.active_layer, self.frame())
+    }
+
+    pub fn layer_strokes(&self, layer: ObjPtr<Layer>) -> Vec<ObjPtr<Stroke>> {
+        let mut res = Vec::new();
+        if let Some(layer_obj) = self.project.layers.get(layer) {
+            self.visible_strokes_in_layer(layer, layer_obj, self.frame(), &mut res, true);
+        }
+        res
+    }
+
     pub fn pause(&mut self) {
         self.selection = Selection::None;
         self.playing = false;
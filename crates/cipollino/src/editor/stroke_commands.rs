@@ -0,0 +1,418 @@
+
+use glam::{vec2, vec3, Mat4, Vec2};
+
+use crate::{project::{action::{Action, ObjAction}, easing::Easing, frame::Frame, layer::{Layer, LayerKind}, obj::{child_obj::{ChildObj, HasRootAsset}, obj_list::ObjListTrait, ObjBox, ObjPtr}, stroke::{iter_bezier_segments, polygon_to_points, Stroke}, Project}, renderer::scene::meshgen::unfilled_outline, tools::active_frame_proj_layer_frame, util::polygon::{polygon_nonzero, BooleanOp}};
+
+use super::{selection::Selection, state::EditorState};
+
+pub fn boolean_selected_strokes(state: &mut EditorState, op: BooleanOp) {
+    let Selection::Scene(strokes) = &state.selection else { return; };
+    let strokes = strokes.clone();
+    if let Some((new_stroke, acts)) = Stroke::boolean(&mut state.project, &strokes, op) {
+        state.actions.add(Action::from_list(acts));
+        state.selection = match new_stroke {
+            Some(stroke) => Selection::Scene(vec![stroke]),
+            None => Selection::None
+        };
+        state.reset_tool();
+    } else {
+        state.error_messages.push(format!("{} needs at least two selected filled strokes from the same frame.", op.name()));
+    }
+}
+
+// Replaces an unfilled stroke with a filled one following its outline, keeping its place in the frame
+pub fn outline_stroke(project: &mut Project, stroke_ptr: ObjPtr<Stroke>) -> Option<(ObjPtr<Stroke>, Vec<ObjAction>)> {
+    let stroke = project.strokes.get(stroke_ptr)?.clone();
+    if stroke.filled {
+        return None;
+    }
+
+    // The outline crosses itself wherever the stroke overlaps itself or its other chains, which would leave holes with the even-odd rule
+    let outline = polygon_nonzero(&unfilled_outline(&stroke));
+    let points = polygon_to_points(&outline, 0.1);
+    if points.is_empty() {
+        return None;
+    }
+
+    let idx = Stroke::get_list_in_parent(project, stroke.frame)?.iter().position(|other| other.make_ptr() == stroke_ptr)?;
+    let mut acts = vec![Stroke::delete(project, stroke_ptr)?];
+    let n_strokes = Stroke::get_list_in_parent(project, stroke.frame)?.len();
+    let idx = if idx < n_strokes { idx as i32 } else { -1 };
+    let (new_stroke, act) = Stroke::add_at_idx(project, stroke.frame, Stroke {
+        filled: true,
+        taper_start: 0.0,
+        taper_end: 0.0,
+        points,
+        ..stroke
+    }, idx)?;
+    acts.push(act);
+    Some((new_stroke, acts))
+}
+
+pub fn outline_selected_strokes(state: &mut EditorState) {
+    let Selection::Scene(strokes) = &state.selection else { return; };
+    let strokes = strokes.clone();
+    let mut acts = Vec::new();
+    let mut new_selection = Vec::new();
+    for stroke in strokes {
+        match outline_stroke(&mut state.project, stroke) {
+            Some((new_stroke, stroke_acts)) => {
+                acts.extend(stroke_acts);
+                new_selection.push(new_stroke);
+            },
+            None => new_selection.push(stroke)
+        }
+    }
+    if acts.is_empty() {
+        state.error_messages.push("Outline Stroke needs at least one selected unfilled stroke.".to_owned());
+        return;
+    }
+    state.actions.add(Action::from_list(acts));
+    state.selection = Selection::Scene(new_selection);
+    state.reset_tool();
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+    Top,
+    Middle,
+    Bottom
+}
+
+impl Align {
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Align::Left => "Left",
+            Align::Center => "Center",
+            Align::Right => "Right",
+            Align::Top => "Top",
+            Align::Middle => "Middle",
+            Align::Bottom => "Bottom",
+        }
+    }
+
+}
+
+fn stroke_bounds(project: &Project, strokes: &Vec<ObjPtr<Stroke>>) -> Vec<(ObjPtr<Stroke>, Vec2, Vec2)> {
+    strokes.iter().filter_map(|ptr| {
+        let (min, max) = project.strokes.get(*ptr)?.bounding_box()?;
+        Some((*ptr, min, max))
+    }).collect()
+}
+
+fn translate_strokes(project: &mut Project, moves: Vec<(ObjPtr<Stroke>, Vec2)>) -> Vec<ObjAction> {
+    moves.into_iter().filter(|(_, delta)| *delta != Vec2::ZERO).filter_map(|(ptr, delta)| {
+        Stroke::transform(project, ptr, Mat4::from_translation(vec3(delta.x, delta.y, 0.0)))
+    }).collect()
+}
+
+// Lines the strokes up against the edge or centre of their combined bounding box
+pub fn align_strokes(project: &mut Project, strokes: &Vec<ObjPtr<Stroke>>, align: Align) -> Vec<ObjAction> {
+    let bounds = stroke_bounds(project, strokes);
+    let Some((min, max)) = bounds.iter().map(|(_, min, max)| (*min, *max)).reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b))) else { return Vec::new(); };
+    let moves = bounds.into_iter().map(|(ptr, stroke_min, stroke_max)| {
+        let center = (stroke_min + stroke_max) * 0.5;
+        let delta = match align {
+            Align::Left => vec2(min.x - stroke_min.x, 0.0),
+            Align::Center => vec2((min.x + max.x) * 0.5 - center.x, 0.0),
+            Align::Right => vec2(max.x - stroke_max.x, 0.0),
+            Align::Top => vec2(0.0, max.y - stroke_max.y),
+            Align::Middle => vec2(0.0, (min.y + max.y) * 0.5 - center.y),
+            Align::Bottom => vec2(0.0, min.y - stroke_min.y),
+        };
+        (ptr, delta)
+    }).collect();
+    translate_strokes(project, moves)
+}
+
+// Spaces the strokes' centres evenly between the two outermost ones
+pub fn distribute_strokes(project: &mut Project, strokes: &Vec<ObjPtr<Stroke>>, vertical: bool) -> Vec<ObjAction> {
+    let axis = |pt: Vec2| if vertical { pt.y } else { pt.x };
+    let mut centers: Vec<_> = stroke_bounds(project, strokes).into_iter().map(|(ptr, min, max)| (ptr, axis((min + max) * 0.5))).collect();
+    if centers.len() < 3 {
+        return Vec::new();
+    }
+    centers.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+    let first = centers[0].1;
+    let step = (centers[centers.len() - 1].1 - first) / ((centers.len() - 1) as f32);
+    let moves = centers.into_iter().enumerate().map(|(i, (ptr, center))| {
+        let delta = first + step * (i as f32) - center;
+        (ptr, if vertical { vec2(0.0, delta) } else { vec2(delta, 0.0) })
+    }).collect();
+    translate_strokes(project, moves)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ZOrder {
+    ToFront,
+    Forward,
+    Backward,
+    ToBack
+}
+
+impl ZOrder {
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ZOrder::ToFront => "Bring to Front",
+            ZOrder::Forward => "Bring Forward",
+            ZOrder::Backward => "Send Backward",
+            ZOrder::ToBack => "Send to Back",
+        }
+    }
+
+}
+
+// Strokes later in their frame are drawn on top. Selected strokes keep their order relative to each other.
+fn reordered(order: Vec<ObjPtr<Stroke>>, selected: &Vec<ObjPtr<Stroke>>, z_order: ZOrder) -> Vec<ObjPtr<Stroke>> {
+    let (mut picked, mut others): (Vec<_>, Vec<_>) = order.iter().partition(|stroke| selected.contains(stroke));
+    match z_order {
+        ZOrder::ToFront => {
+            others.append(&mut picked);
+            others
+        },
+        ZOrder::ToBack => {
+            picked.append(&mut others);
+            picked
+        },
+        ZOrder::Forward => {
+            let mut order = order;
+            for i in (0..order.len().saturating_sub(1)).rev() {
+                if selected.contains(&order[i]) && !selected.contains(&order[i + 1]) {
+                    order.swap(i, i + 1);
+                }
+            }
+            order
+        },
+        ZOrder::Backward => {
+            let mut order = order;
+            for i in 1..order.len() {
+                if selected.contains(&order[i]) && !selected.contains(&order[i - 1]) {
+                    order.swap(i, i - 1);
+                }
+            }
+            order
+        }
+    }
+}
+
+pub fn reorder_strokes(project: &mut Project, strokes: &Vec<ObjPtr<Stroke>>, z_order: ZOrder) -> Vec<ObjAction> {
+    let mut frames = Vec::new();
+    for stroke in strokes {
+        if let Some(stroke) = project.strokes.get(*stroke) {
+            if !frames.contains(&stroke.frame) {
+                frames.push(stroke.frame);
+            }
+        }
+    }
+
+    let mut acts = Vec::new();
+    for frame in frames {
+        let Some(siblings) = Stroke::get_list_in_parent(project, frame) else { continue; };
+        let order = siblings.iter().map(|stroke| stroke.make_ptr()).collect();
+        // Every stroke before idx is already in place, so each one only ever moves back
+        for (idx, stroke) in reordered(order, strokes, z_order).into_iter().enumerate() {
+            let curr_idx = Stroke::get_list_in_parent(project, frame).and_then(|siblings| siblings.iter().position(|other| other.make_ptr() == stroke));
+            if curr_idx.map(|curr_idx| curr_idx != idx).unwrap_or(false) {
+                acts.extend(Stroke::set_index(project, stroke, idx));
+            }
+        }
+    }
+    acts
+}
+
+pub fn reorder_selected_strokes(state: &mut EditorState, z_order: ZOrder) {
+    let strokes = state.selection.scene_strokes();
+    let acts = reorder_strokes(&mut state.project, &strokes, z_order);
+    if !acts.is_empty() {
+        state.actions.add(Action::from_list(acts));
+    }
+}
+
+// Animation layers from top to bottom, the way they are listed in the timeline
+fn animation_layers(project: &Project, layers: &Vec<ObjBox<Layer>>, res: &mut Vec<ObjPtr<Layer>>) {
+    for layer in layers {
+        let layer_obj = layer.get(project);
+        match layer_obj.kind {
+            LayerKind::Animation => res.push(layer.make_ptr()),
+            LayerKind::Group => animation_layers(project, &layer_obj.layers, res),
+            LayerKind::Audio | LayerKind::Camera => {}
+        }
+    }
+}
+
+// The next unlocked layer above or below the given one in its clip
+fn neighbour_layer(project: &Project, layer: ObjPtr<Layer>, above: bool) -> Option<ObjPtr<Layer>> {
+    let gfx = project.graphics.get(Layer::get_root_asset(project, layer)?)?;
+    let mut layers = Vec::new();
+    animation_layers(project, &gfx.layers, &mut layers);
+    let idx = layers.iter().position(|other| *other == layer)?;
+    let target = if above {
+        layers[..idx].iter().rev().find(|layer| !Layer::locked(project, **layer))
+    } else {
+        layers[(idx + 1)..].iter().find(|layer| !Layer::locked(project, **layer))
+    };
+    target.copied()
+}
+
+/*
+    Moves strokes onto the frame shown at the given time on the next unlocked layer above or below their own, adding a frame there if needed.
+    Strokes from different layers each move relative to their own layer. Nothing is moved if any of them has nowhere to go.
+*/
+pub fn move_strokes_to_layer(project: &mut Project, strokes: &Vec<ObjPtr<Stroke>>, time: i32, above: bool) -> Option<Vec<ObjAction>> {
+    let mut moves: Vec<(ObjPtr<Layer>, Vec<ObjPtr<Stroke>>)> = Vec::new();
+    for stroke_ptr in strokes {
+        let layer = project.frames.get(project.strokes.get(*stroke_ptr)?.frame)?.layer;
+        let target = neighbour_layer(project, layer, above)?;
+        match moves.iter_mut().find(|(other, _)| *other == target) {
+            Some((_, strokes)) => strokes.push(*stroke_ptr),
+            None => moves.push((target, vec![*stroke_ptr]))
+        }
+    }
+    if moves.is_empty() {
+        return None;
+    }
+
+    let mut acts = Vec::new();
+    for (target, strokes) in moves {
+        let Some((frame, frame_act)) = active_frame_proj_layer_frame(project, target, time) else {
+            Action::from_list(acts).undo(project);
+            return None;
+        };
+        acts.extend(frame_act);
+        for stroke in strokes {
+            acts.extend(Stroke::transfer(project, stroke, frame));
+        }
+    }
+    Some(acts)
+}
+
+pub fn move_selected_strokes_to_layer(state: &mut EditorState, above: bool) {
+    let strokes = state.selection.scene_strokes();
+    let frame = state.frame();
+    match move_strokes_to_layer(&mut state.project, &strokes, frame, above) {
+        Some(acts) => {
+            state.actions.add(Action::from_list(acts));
+            state.reset_tool();
+        },
+        None if shows_tween_in_between(&state.project, &strokes, frame, above) => state.error_messages.push(format!("The layer {} shows an in-between drawing of a tween here. Bake the tween or add a frame there first.", if above { "above" } else { "below" })),
+        None => state.error_messages.push(format!("There is no unlocked layer {} to move the strokes to.", if above { "above" } else { "below" }))
+    }
+}
+
+// Whether a layer the strokes would move to shows an in-between drawing of a tween at the given time
+fn shows_tween_in_between(project: &Project, strokes: &Vec<ObjPtr<Stroke>>, time: i32, above: bool) -> bool {
+    strokes.iter().any(|stroke| target_frame_in_between(project, *stroke, time, above).is_some())
+}
+
+fn target_frame_in_between(project: &Project, stroke: ObjPtr<Stroke>, time: i32, above: bool) -> Option<Vec<Stroke>> {
+    let layer = project.frames.get(project.strokes.get(stroke)?.frame)?.layer;
+    let target = neighbour_layer(project, layer, above)?;
+    let key = project.layers.get(target)?.get_frame_at(project, time)?;
+    Frame::tween_at(project, key.make_ptr(), time)
+}
+
+// Generating more frames than this along a path is refused
+pub const MAX_MOTION_PATH_FRAMES: i32 = 1000;
+
+#[derive(Clone, Copy)]
+pub struct MotionPath {
+    // How many frames the strokes take to reach the end of the path
+    pub frames: i32,
+    // Rotates the strokes to follow the direction of the path
+    pub orient: bool,
+    pub easing: Easing
+}
+
+impl Default for MotionPath {
+
+    fn default() -> Self {
+        Self {
+            frames: 12,
+            orient: false,
+            easing: Easing::Linear
+        }
+    }
+
+}
+
+// The first chain of a path stroke as a polyline, along with the distance along the path to each point
+fn flatten_path(path: &Stroke) -> Option<(Vec<Vec2>, Vec<f32>)> {
+    let chain = path.points.first()?;
+    let mut pts = vec![chain.first()?.pt];
+    for segment in iter_bezier_segments(chain) {
+        pts.extend(segment.to_discrete::<16>().into_iter().skip(1));
+    }
+    let mut dists = vec![0.0];
+    for i in 1..pts.len() {
+        dists.push(dists[i - 1] + (pts[i] - pts[i - 1]).length());
+    }
+    if *dists.last()? < 0.0001 {
+        return None;
+    }
+    Some((pts, dists))
+}
+
+// The point and direction a fraction of the way along a flattened path
+fn sample_path(pts: &Vec<Vec2>, dists: &Vec<f32>, t: f32) -> (Vec2, Vec2) {
+    let dist = t.clamp(0.0, 1.0) * dists[dists.len() - 1];
+    let i = dists.partition_point(|d| *d < dist).clamp(1, pts.len() - 1);
+    let seg_len = dists[i] - dists[i - 1];
+    let seg_t = if seg_len > 0.0 { (dist - dists[i - 1]) / seg_len } else { 0.0 };
+    (pts[i - 1].lerp(pts[i], seg_t), (pts[i] - pts[i - 1]).normalize_or_zero())
+}
+
+// Copies the strokes onto the frames after the one they're on, moving them along the path so they reach its end on the last frame
+pub fn strokes_along_path(project: &mut Project, strokes: &Vec<ObjPtr<Stroke>>, path: ObjPtr<Stroke>, motion: MotionPath) -> Option<Vec<ObjAction>> {
+    let flat_path = flatten_path(project.strokes.get(path)?)?;
+    let copies: Vec<Stroke> = strokes.iter().filter(|stroke| **stroke != path).filter_map(|stroke| project.strokes.get(*stroke).cloned()).collect();
+    // The keys go on the layer of the strokes being moved, which the path doesn't have to share
+    let frame = project.frames.get(copies.first()?.frame)?;
+    let (layer, time) = (frame.layer, frame.time);
+    if motion.frames < 1 || motion.frames > MAX_MOTION_PATH_FRAMES || time.checked_add(motion.frames).is_none() {
+        return None;
+    }
+
+    let mut acts = Vec::new();
+    if copy_along_path(project, layer, time, &copies, &flat_path, motion, &mut acts).is_none() {
+        Action::from_list(acts).undo(project);
+        return None;
+    }
+    Some(acts)
+}
+
+fn copy_along_path(project: &mut Project, layer: ObjPtr<Layer>, time: i32, copies: &Vec<Stroke>, (pts, dists): &(Vec<Vec2>, Vec<f32>), motion: MotionPath, acts: &mut Vec<ObjAction>) -> Option<()> {
+    let (start, start_dir) = sample_path(pts, dists, 0.0);
+    for i in 1..=motion.frames {
+        let (pos, dir) = sample_path(pts, dists, motion.easing.apply((i as f32) / (motion.frames as f32)));
+        let angle = if motion.orient { start_dir.angle_between(dir) } else { 0.0 };
+        let angle = if angle.is_finite() { angle } else { 0.0 };
+        let trans = Mat4::from_translation(vec3(pos.x, pos.y, 0.0)) * Mat4::from_rotation_z(angle) * Mat4::from_translation(vec3(-start.x, -start.y, 0.0));
+
+        let existing_frame = project.layers.get(layer)?.get_frame_exactly_at(project, time + i).map(|frame| frame.make_ptr());
+        let frame = if let Some(frame) = existing_frame {
+            frame
+        } else {
+            let (frame, act) = Frame::add(project, layer, Frame {
+                layer,
+                time: time + i,
+                ..Frame::default()
+            })?;
+            acts.push(act);
+            frame
+        };
+        for stroke in copies {
+            let (copy, act) = Stroke::add(project, frame, Stroke {
+                frame,
+                ..stroke.clone()
+            })?;
+            acts.push(act);
+            acts.push(Stroke::transform(project, copy, trans)?);
+        }
+    }
+    Some(())
+}
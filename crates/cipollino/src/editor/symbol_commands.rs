@@ -0,0 +1,69 @@
+use crate::{project::{action::{Action, ObjAction}, frame::Frame, graphic::Graphic, layer::{Layer, LayerParent}, obj::{asset::Asset, child_obj::{ChildObj, HasRootAsset}, obj_list::ObjListTrait, ObjPtr}, stroke::Stroke, symbol::SymbolInstance, Project}, tools::active_frame};
+
+use super::{selection::Selection, state::EditorState};
+
+// Places an instance of the graphic in the frame, unless the graphic already shows the frame's graphic
+pub fn place_symbol(project: &mut Project, frame: ObjPtr<Frame>, graphic: ObjPtr<Graphic>) -> Option<(ObjPtr<SymbolInstance>, ObjAction)> {
+    let parent_graphic = Frame::get_root_asset(project, frame)?;
+    if Graphic::contains_symbol(project, graphic, parent_graphic) {
+        return None;
+    }
+    SymbolInstance::add(project, frame, SymbolInstance {
+        graphic,
+        ..SymbolInstance::default()
+    })
+}
+
+pub fn place_symbol_in_scene(state: &mut EditorState, graphic: ObjPtr<Graphic>) {
+    if state.project.graphics.get(graphic).is_none() {
+        return;
+    }
+    let Some((frame, mut acts)) = active_frame(state) else { return; };
+    match place_symbol(&mut state.project, frame, graphic) {
+        Some((_, act)) => {
+            acts.push(act);
+            state.actions.add(Action::from_list(acts));
+        },
+        None => {
+            for act in acts.iter().rev() {
+                act.undo(&mut state.project);
+            }
+            state.error_messages.push("A graphic can't be placed inside of itself.".to_owned());
+        }
+    }
+}
+
+// Moves the strokes into a new graphic in the same folder, replacing them with an instance of it
+pub fn convert_to_symbol(project: &mut Project, strokes: &Vec<ObjPtr<Stroke>>) -> Option<(ObjPtr<SymbolInstance>, Vec<ObjAction>)> {
+    let frame = project.strokes.get(*strokes.first()?)?.frame;
+    let parent_graphic = Frame::get_root_asset(project, frame)?;
+    let folder = project.graphics.get(parent_graphic)?.folder();
+
+    let (graphic, mut acts) = Graphic::asset_add(project, folder, Graphic {
+        name: "Symbol".to_owned(),
+        clip: false,
+        ..Graphic::default()
+    })?;
+    let (layer, act) = Layer::add(project, LayerParent::Graphic(graphic), Layer {
+        name: "Layer".to_owned(),
+        ..Layer::default()
+    })?;
+    acts.push(act);
+    let (symbol_frame, act) = Frame::add(project, layer, Frame::default())?;
+    acts.push(act);
+    for stroke in strokes {
+        acts.extend(Stroke::transfer(project, *stroke, symbol_frame));
+    }
+    let (symbol, act) = place_symbol(project, frame, graphic)?;
+    acts.push(act);
+    Some((symbol, acts))
+}
+
+pub fn convert_selection_to_symbol(state: &mut EditorState) {
+    let strokes = state.selection.scene_strokes();
+    if let Some((_, acts)) = convert_to_symbol(&mut state.project, &strokes) {
+        state.actions.add(Action::from_list(acts));
+        state.selection = Selection::None;
+        state.reset_tool();
+    }
+}
@@ -0,0 +1,128 @@
+
+use glam::{vec2, vec3, Mat4, Vec2};
+
+use crate::project::{action::ObjAction, frame::Frame, obj::{child_obj::ChildObj, ObjPtr}, stroke::Stroke, Project};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SymmetryMode {
+    Off,
+    // Mirrored across a horizontal line through the centre
+    Horizontal,
+    // Mirrored across a vertical line through the centre
+    Vertical,
+    // Rotated copies around the centre
+    Radial
+}
+
+impl SymmetryMode {
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SymmetryMode::Off => "Off",
+            SymmetryMode::Horizontal => "Horizontal",
+            SymmetryMode::Vertical => "Vertical",
+            SymmetryMode::Radial => "Radial",
+        }
+    }
+
+}
+
+pub struct Symmetry {
+    pub mode: SymmetryMode,
+    pub radial_count: u32,
+    pub center: Vec2
+}
+
+fn transform_pt(pt: Vec2, trans: Mat4) -> Vec2 {
+    let pt = trans.transform_point3(vec3(pt.x, pt.y, 0.0));
+    vec2(pt.x, pt.y)
+}
+
+impl Symmetry {
+
+    pub fn new() -> Self {
+        Self {
+            mode: SymmetryMode::Off,
+            radial_count: 6,
+            center: Vec2::ZERO
+        }
+    }
+
+    // The transforms taking what's drawn to each of its copies. The centre and axes are in the scene's space, while what's drawn is in the given layer space
+    pub fn copy_transforms(&self, space: Mat4) -> Vec<Mat4> {
+        let to_center = Mat4::from_translation(vec3(self.center.x, self.center.y, 0.0));
+        let from_center = Mat4::from_translation(vec3(-self.center.x, -self.center.y, 0.0));
+        let inv_space = space.inverse();
+        let around_center = |mat: Mat4| inv_space * to_center * mat * from_center * space;
+        match self.mode {
+            SymmetryMode::Off => Vec::new(),
+            SymmetryMode::Horizontal => vec![around_center(Mat4::from_scale(vec3(1.0, -1.0, 1.0)))],
+            SymmetryMode::Vertical => vec![around_center(Mat4::from_scale(vec3(-1.0, 1.0, 1.0)))],
+            SymmetryMode::Radial => (1..self.radial_count.max(1)).map(|i| {
+                around_center(Mat4::from_rotation_z(std::f32::consts::TAU * (i as f32) / (self.radial_count as f32)))
+            }).collect()
+        }
+    }
+
+    pub fn copy_points(&self, pt: Vec2, space: Mat4) -> Vec<Vec2> {
+        self.copy_transforms(space).into_iter().map(|trans| transform_pt(pt, trans)).collect()
+    }
+
+    pub fn copy_strokes(&self, stroke: &Stroke, space: Mat4) -> Vec<Stroke> {
+        self.copy_transforms(space).into_iter().map(|trans| {
+            let mut copy = stroke.clone();
+            for chain in &mut copy.points {
+                for pt in chain {
+                    pt.a = transform_pt(pt.a, trans);
+                    pt.pt = transform_pt(pt.pt, trans);
+                    pt.b = transform_pt(pt.b, trans);
+                }
+            }
+            copy
+        }).collect()
+    }
+
+}
+
+// The copies of a stroke being drawn, replaced whenever the original changes
+pub struct SymmetryCopies {
+    strokes: Vec<ObjPtr<Stroke>>,
+    acts: Vec<ObjAction>
+}
+
+impl SymmetryCopies {
+
+    pub fn new() -> Self {
+        Self {
+            strokes: Vec::new(),
+            acts: Vec::new()
+        }
+    }
+
+    pub fn update(&mut self, project: &mut Project, symmetry: &Symmetry, space: Mat4, frame: ObjPtr<Frame>, stroke: &Stroke) {
+        self.undo(project);
+        for copy in symmetry.copy_strokes(stroke, space) {
+            if let Some((copy, act)) = Stroke::add(project, frame, copy) {
+                self.strokes.push(copy);
+                self.acts.push(act);
+            }
+        }
+    }
+
+    pub fn strokes(&self) -> Vec<ObjPtr<Stroke>> {
+        self.strokes.clone()
+    }
+
+    pub fn undo(&mut self, project: &mut Project) {
+        self.strokes.clear();
+        for act in self.acts.drain(..).rev() {
+            act.undo(project);
+        }
+    }
+
+    pub fn take_acts(&mut self) -> Vec<ObjAction> {
+        self.strokes.clear();
+        std::mem::replace(&mut self.acts, Vec::new())
+    }
+
+}
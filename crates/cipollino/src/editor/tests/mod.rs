@@ -0,0 +1,5 @@
+
+mod stroke_commands;
+mod snapping;
+mod symmetry;
+mod select_commands;
@@ -0,0 +1,40 @@
+
+use glam::{vec2, vec4, Vec2};
+
+use crate::{editor::select_commands::{same_color_strokes, strokes_touch, touching_strokes}, project::{obj::{child_obj::ChildObj, obj_list::ObjListTrait, ObjPtr}, stroke::{Stroke, StrokeColor}}};
+
+use crate::project::tests::{line_stroke, TestProject};
+
+#[test]
+fn select_touching_and_same_color() {
+    let mut test = TestProject::new();
+    let frame = test.frame();
+
+    let red = StrokeColor::Color(vec4(1.0, 0.0, 0.0, 1.0));
+    let palette_a = StrokeColor::Palette { color: ObjPtr::from_key(1), backup: vec4(0.0, 0.0, 1.0, 1.0) };
+    let palette_b = StrokeColor::Palette { color: ObjPtr::from_key(2), backup: vec4(0.0, 0.0, 1.0, 1.0) };
+    let mut add = |p0: Vec2, p1: Vec2, color: StrokeColor| {
+        let mut stroke = line_stroke(p0, p1);
+        stroke.r = 1.0;
+        stroke.color = color;
+        Stroke::add(&mut test.project, frame, stroke).unwrap().0
+    };
+    let a = add(vec2(0.0, 0.0), vec2(10.0, 0.0), red);
+    let b = add(vec2(5.0, 1.5), vec2(5.0, 10.0), palette_a);
+    let c = add(vec2(20.0, 0.0), vec2(30.0, 0.0), red);
+    let d = add(vec2(5.0, 15.0), vec2(5.0, 20.0), palette_a);
+    let e = add(vec2(40.0, 0.0), vec2(50.0, 0.0), palette_b);
+    let all = vec![a, b, c, d, e];
+
+    // Strokes touch once their widths overlap
+    let stroke = |ptr| test.project.strokes.get(ptr).unwrap();
+    assert!(strokes_touch(stroke(a), stroke(b)));
+    assert!(!strokes_touch(stroke(a), stroke(c)));
+    assert_eq!(touching_strokes(&test.project, &vec![a], &all), vec![b]);
+    assert_eq!(touching_strokes(&test.project, &vec![b], &all), vec![a]);
+
+    // Palette colours match by entry, even when their values are the same
+    assert_eq!(same_color_strokes(&test.project, &vec![a], &all), vec![a, c]);
+    assert_eq!(same_color_strokes(&test.project, &vec![b], &all), vec![b, d]);
+    assert_eq!(same_color_strokes(&test.project, &vec![a, e], &all), vec![a, c, e]);
+}
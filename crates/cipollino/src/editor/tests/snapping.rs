@@ -0,0 +1,44 @@
+
+use glam::{vec2, Mat4};
+
+use crate::{editor::snapping::Snapping, project::{graphic::{Graphic, Guide}, obj::child_obj::ChildObj, stroke::Stroke}};
+
+use crate::project::tests::{line_stroke, TestProject};
+
+#[test]
+fn snapping_targets() {
+    let mut test = TestProject::new();
+    Graphic::set_guides(&mut test.project, test.gfx, vec![Guide { vertical: true, pos: 100.0 }]).unwrap();
+    let frame = test.frame();
+    let stroke = Stroke::add(&mut test.project, frame, line_stroke(vec2(300.0, 0.0), vec2(300.0, 200.0))).unwrap().0;
+    let strokes = vec![stroke];
+    let mut snapping = Snapping::new();
+
+    // Guides and the clip frame's edges
+    assert_eq!(snapping.find_target(&test.project, test.gfx, &strokes, Mat4::IDENTITY, vec2(103.0, 40.0), 5.0), Some(vec2(100.0, 40.0)));
+    assert_eq!(snapping.find_target(&test.project, test.gfx, &strokes, Mat4::IDENTITY, vec2(50.0, 538.0), 5.0), Some(vec2(50.0, 540.0)));
+    assert_eq!(snapping.find_target(&test.project, test.gfx, &strokes, Mat4::IDENTITY, vec2(50.0, 40.0), 5.0), None);
+
+    // Stroke ends win over the curve they're on
+    snapping.snap_curves = true;
+    assert_eq!(snapping.find_target(&test.project, test.gfx, &strokes, Mat4::IDENTITY, vec2(302.0, 198.0), 5.0), Some(vec2(300.0, 200.0)));
+    let on_curve = snapping.find_target(&test.project, test.gfx, &strokes, Mat4::IDENTITY, vec2(303.0, 100.0), 5.0).unwrap();
+    assert!((on_curve.x - 300.0).abs() < 0.01 && (on_curve.y - 100.0).abs() < 3.0);
+
+    snapping.snap_grid = true;
+    snapping.grid_size = 25.0;
+    assert_eq!(snapping.find_target(&test.project, test.gfx, &strokes, Mat4::IDENTITY, vec2(52.0, 48.0), 5.0), Some(vec2(50.0, 50.0)));
+
+    // Moving several points snaps whichever is closest to a target
+    let (offset, target) = snapping.find_offset(&test.project, test.gfx, &strokes, Mat4::IDENTITY, &vec![vec2(61.0, 61.0), vec2(99.0, 72.0)], 5.0).unwrap();
+    assert_eq!(offset, vec2(1.0, 3.0));
+    assert_eq!(target, vec2(100.0, 75.0));
+
+    // Points in a moved layer snap to guides where they appear in the scene
+    snapping.snap_grid = false;
+    let space = Mat4::from_translation(glam::vec3(40.0, 0.0, 0.0));
+    assert_eq!(snapping.find_target(&test.project, test.gfx, &strokes, space, vec2(63.0, 40.0), 5.0), Some(vec2(60.0, 40.0)));
+
+    snapping.enabled = false;
+    assert_eq!(snapping.find_target(&test.project, test.gfx, &strokes, Mat4::IDENTITY, vec2(52.0, 48.0), 5.0), None);
+}
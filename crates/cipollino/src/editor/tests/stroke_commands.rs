@@ -0,0 +1,188 @@
+
+use glam::vec2;
+
+use crate::{editor::stroke_commands::{align_strokes, distribute_strokes, move_strokes_to_layer, outline_stroke, reorder_strokes, strokes_along_path, Align, MotionPath, ZOrder, MAX_MOTION_PATH_FRAMES}, project::{easing::Easing, frame::Frame, layer::{Layer, LayerParent}, obj::{child_obj::ChildObj, obj_list::ObjListTrait, ObjPtr}, stroke::{Stroke, StrokePoint}, Project}, util::polygon::point_in_polygon};
+
+use crate::project::tests::{in_parent, line_stroke, TestProject};
+
+#[test]
+fn stroke_z_order() {
+    let mut test = TestProject::new();
+    let frame = test.frame();
+    let strokes: Vec<_> = (0..4).map(|_| Stroke::add(&mut test.project, frame, line_stroke(vec2(0.0, 0.0), vec2(10.0, 0.0))).unwrap().0).collect();
+    let order = |project: &Project| -> Vec<ObjPtr<Stroke>> { project.frames.get(frame).unwrap().strokes.iter().map(|stroke| stroke.make_ptr()).collect() };
+    let [a, b, c, d] = [strokes[0], strokes[1], strokes[2], strokes[3]];
+
+    let acts = reorder_strokes(&mut test.project, &vec![a, c], ZOrder::ToFront);
+    test.check_undo_redo(acts, |project, applied| {
+        assert_eq!(order(project), if applied { vec![b, d, a, c] } else { vec![a, b, c, d] });
+    });
+    let acts = reorder_strokes(&mut test.project, &vec![b, d], ZOrder::ToFront);
+    test.check_undo_redo(acts, |project, applied| {
+        assert_eq!(order(project), if applied { vec![a, c, b, d] } else { vec![b, d, a, c] });
+    });
+
+    // Stepping stops at the ends, keeping the selected strokes in order
+    let acts = reorder_strokes(&mut test.project, &vec![c, d], ZOrder::Forward);
+    test.check_undo_redo(acts, |project, applied| {
+        assert_eq!(order(project), if applied { vec![a, b, c, d] } else { vec![a, c, b, d] });
+    });
+    let acts = reorder_strokes(&mut test.project, &vec![a, c], ZOrder::Backward);
+    test.check_undo_redo(acts, |project, applied| {
+        assert_eq!(order(project), if applied { vec![a, c, b, d] } else { vec![a, b, c, d] });
+    });
+    assert!(reorder_strokes(&mut test.project, &vec![a, c], ZOrder::ToBack).is_empty());
+}
+
+#[test]
+fn move_strokes_between_layers() {
+    let mut test = TestProject::new();
+    let gfx = LayerParent::Graphic(test.gfx);
+    let (below, _) = Layer::add(&mut test.project, gfx, Layer::default()).unwrap();
+    let (frame, _) = Frame::add(&mut test.project, test.layer, Frame { time: 5, ..Frame::default() }).unwrap();
+    let stroke = Stroke::add(&mut test.project, frame, line_stroke(vec2(0.0, 0.0), vec2(10.0, 0.0))).unwrap().0;
+
+    assert!(move_strokes_to_layer(&mut test.project, &vec![stroke], 5, true).is_none());
+
+    // The layer below has no frame yet, so one is added for the strokes
+    let acts = move_strokes_to_layer(&mut test.project, &vec![stroke], 7, false).unwrap();
+    test.check_undo_redo(acts, |project, applied| {
+        let below_frame = project.layers.get(below).unwrap().get_frame_at(project, 7).map(|frame| frame.make_ptr());
+        assert_eq!(below_frame.is_some(), applied);
+        assert_eq!(in_parent(project, frame, stroke), !applied);
+        if let Some(below_frame) = below_frame {
+            assert_eq!(project.frames.get(below_frame).unwrap().time, 7);
+            assert!(in_parent(project, below_frame, stroke));
+        }
+    });
+
+    // Strokes from different layers each move to the layer next to their own
+    let (bottom, _) = Layer::add(&mut test.project, gfx, Layer::default()).unwrap();
+    let (below_frame, _) = Frame::add(&mut test.project, below, Frame { time: 5, ..Frame::default() }).unwrap();
+    let lower = Stroke::add(&mut test.project, below_frame, line_stroke(vec2(0.0, 0.0), vec2(10.0, 0.0))).unwrap().0;
+    let upper = Stroke::add(&mut test.project, frame, line_stroke(vec2(0.0, 0.0), vec2(10.0, 0.0))).unwrap().0;
+    assert!(move_strokes_to_layer(&mut test.project, &vec![upper, lower], 5, true).is_none());
+    assert!(in_parent(&test.project, below_frame, lower));
+    let acts = move_strokes_to_layer(&mut test.project, &vec![upper, lower], 5, false).unwrap();
+    test.check_undo_redo(acts, |project, applied| {
+        assert_eq!(in_parent(project, below_frame, upper), applied);
+        assert_eq!(in_parent(project, below_frame, lower), !applied);
+        let bottom_frame = project.layers.get(bottom).unwrap().get_frame_at(project, 5).map(|frame| frame.make_ptr());
+        assert_eq!(bottom_frame.map(|frame| in_parent(project, frame, lower)), if applied { Some(true) } else { None });
+    });
+}
+
+#[test]
+fn outlining_looping_strokes() {
+    let mut test = TestProject::new();
+    let frame = test.frame();
+    // Goes right, up-left, then straight down through the first segment
+    let pts = [vec2(0.0, 0.0), vec2(10.0, 0.0), vec2(5.0, 5.0), vec2(5.0, -5.0)];
+    let (stroke, _) = Stroke::add(&mut test.project, frame, Stroke {
+        r: 1.0,
+        points: vec![pts.iter().map(|pt| StrokePoint { a: *pt, pt: *pt, b: *pt, pressure: 1.0 }).collect()],
+        ..Stroke::default()
+    }).unwrap();
+
+    let (outlined, acts) = outline_stroke(&mut test.project, stroke).unwrap();
+    test.check_undo_redo(acts, |project, applied| {
+        let strokes: Vec<_> = project.frames.get(frame).unwrap().strokes.iter().map(|stroke| stroke.make_ptr()).collect();
+        assert_eq!(strokes, vec![if applied { outlined } else { stroke }]);
+    });
+    let polygon = test.project.strokes.get(outlined).unwrap().polygon();
+    // Where the stroke crosses itself stays filled, but the loop it encloses doesn't
+    assert!(point_in_polygon(&polygon, vec2(5.0, 0.0)));
+    assert!(point_in_polygon(&polygon, vec2(2.0, 0.5)));
+    assert!(!point_in_polygon(&polygon, vec2(6.6, 1.6)));
+    assert!(!point_in_polygon(&polygon, vec2(2.0, 2.0)));
+}
+
+#[test]
+fn align_and_distribute() {
+    let mut test = TestProject::new();
+    let frame = test.frame();
+    let strokes = vec![
+        Stroke::add(&mut test.project, frame, line_stroke(vec2(0.0, 0.0), vec2(10.0, 10.0))).unwrap().0,
+        Stroke::add(&mut test.project, frame, line_stroke(vec2(30.0, 5.0), vec2(34.0, 7.0))).unwrap().0,
+        Stroke::add(&mut test.project, frame, line_stroke(vec2(100.0, -5.0), vec2(110.0, 0.0))).unwrap().0
+    ];
+    let bounds = |project: &Project, idx: usize| project.strokes.get(strokes[idx]).unwrap().bounding_box().unwrap();
+
+    let acts = align_strokes(&mut test.project, &strokes, Align::Top);
+    assert_eq!(acts.len(), 2);
+    test.check_undo_redo(acts, |project, applied| {
+        assert_eq!(bounds(project, 1).1.y, if applied { 10.0 } else { 7.0 });
+        assert_eq!(bounds(project, 2).1.y, if applied { 10.0 } else { 0.0 });
+        assert_eq!(bounds(project, 2).0.x, 100.0);
+    });
+
+    let acts = distribute_strokes(&mut test.project, &strokes, false);
+    assert_eq!(acts.len(), 1);
+    let (min, max) = bounds(&test.project, 1);
+    assert!(((min.x + max.x) * 0.5 - 55.0).abs() < 0.0001);
+
+    // Nothing to spread out between two strokes
+    assert!(distribute_strokes(&mut test.project, &strokes[..2].to_vec(), true).is_empty());
+}
+
+#[test]
+fn motion_paths() {
+    let mut test = TestProject::new();
+    let frame = test.frame();
+    let stroke = Stroke::add(&mut test.project, frame, line_stroke(vec2(0.0, 0.0), vec2(10.0, 0.0))).unwrap().0;
+    // Goes right, then turns to go up
+    let mut corner = line_stroke(vec2(0.0, 0.0), vec2(100.0, 0.0));
+    corner.points[0][1].b = vec2(100.0, 0.0);
+    corner.points[0].push(StrokePoint { a: vec2(100.0, 0.0), pt: vec2(100.0, 100.0), b: vec2(100.0, 100.0), pressure: 1.0 });
+    let path = Stroke::add(&mut test.project, frame, corner).unwrap().0;
+    let motion = MotionPath {
+        frames: 4,
+        orient: false,
+        easing: Easing::Linear
+    };
+
+    let layer = test.layer;
+    let copy_at = |project: &Project, time: i32| {
+        let frame = project.layers.get(layer).unwrap().get_frame_exactly_at(project, time)?;
+        let strokes = &frame.get(project).strokes;
+        assert_eq!(strokes.len(), 1);
+        project.strokes.get(strokes[0].make_ptr()).cloned()
+    };
+    let acts = strokes_along_path(&mut test.project, &vec![stroke, path], path, motion).unwrap();
+    test.check_undo_redo(acts, |project, applied| {
+        assert_eq!(project.layers.get(layer).unwrap().frames.len(), if applied { 5 } else { 1 });
+        if applied {
+            let (min, _) = copy_at(project, 2).unwrap().bounding_box().unwrap();
+            assert!((min - vec2(100.0, 0.0)).length() < 0.5);
+            let end = copy_at(project, 4).unwrap().points[0].clone();
+            assert!((end[0].pt - vec2(100.0, 100.0)).length() < 0.001);
+            assert!((end[1].pt - vec2(110.0, 100.0)).length() < 0.001);
+        }
+    });
+
+    // Following the direction of the path turns the strokes at the corner
+    let (frame, _) = Frame::add(&mut test.project, test.layer, Frame { time: 10, ..Frame::default() }).unwrap();
+    let stroke = Stroke::add(&mut test.project, frame, line_stroke(vec2(0.0, 0.0), vec2(10.0, 0.0))).unwrap().0;
+    strokes_along_path(&mut test.project, &vec![stroke], path, MotionPath { orient: true, ..motion }).unwrap();
+    let end = copy_at(&test.project, 14).unwrap().points[0].clone();
+    assert!((end[1].pt - vec2(100.0, 110.0)).length() < 0.001);
+
+    // The keys go on the moved strokes' layer even when the path is on another one and selected first
+    let (other, _) = Layer::add(&mut test.project, LayerParent::Graphic(test.gfx), Layer::default()).unwrap();
+    let (other_frame, _) = Frame::add(&mut test.project, other, Frame { time: 20, ..Frame::default() }).unwrap();
+    let moved = Stroke::add(&mut test.project, other_frame, line_stroke(vec2(0.0, 0.0), vec2(10.0, 0.0))).unwrap().0;
+    strokes_along_path(&mut test.project, &vec![path, moved], path, motion).unwrap();
+    assert_eq!(test.project.layers.get(other).unwrap().frames.len(), 5);
+    assert!(copy_at(&test.project, 24).is_none());
+
+    // A path with no length can't be followed
+    let dot = Stroke::add(&mut test.project, frame, line_stroke(vec2(5.0, 5.0), vec2(5.0, 5.0))).unwrap().0;
+    assert!(strokes_along_path(&mut test.project, &vec![stroke], dot, motion).is_none());
+
+    // Too many frames, or frames past the end of time, aren't generated
+    assert!(strokes_along_path(&mut test.project, &vec![stroke], path, MotionPath { frames: MAX_MOTION_PATH_FRAMES + 1, ..motion }).is_none());
+    let (last, _) = Frame::add(&mut test.project, test.layer, Frame { time: i32::MAX - 2, ..Frame::default() }).unwrap();
+    let stroke = Stroke::add(&mut test.project, last, line_stroke(vec2(0.0, 0.0), vec2(10.0, 0.0))).unwrap().0;
+    assert!(strokes_along_path(&mut test.project, &vec![stroke], path, motion).is_none());
+    assert!(test.project.layers.get(layer).unwrap().get_frame_exactly_at(&test.project, i32::MAX - 1).is_none());
+}
@@ -0,0 +1,47 @@
+
+use glam::{vec2, Mat4};
+
+use crate::{editor::symmetry::{Symmetry, SymmetryCopies, SymmetryMode}, project::obj::obj_list::ObjListTrait};
+
+use crate::project::tests::{line_stroke, TestProject};
+
+#[test]
+fn symmetry_copies() {
+    let mut symmetry = Symmetry::new();
+    assert!(symmetry.copy_points(vec2(3.0, 4.0), Mat4::IDENTITY).is_empty());
+
+    symmetry.center = vec2(10.0, 0.0);
+    symmetry.mode = SymmetryMode::Vertical;
+    assert_eq!(symmetry.copy_points(vec2(13.0, 4.0), Mat4::IDENTITY), vec![vec2(7.0, 4.0)]);
+    // A moved layer is mirrored around where the axis appears in it
+    assert_eq!(symmetry.copy_points(vec2(8.0, 4.0), Mat4::from_translation(glam::vec3(5.0, 0.0, 0.0))), vec![vec2(2.0, 4.0)]);
+    symmetry.mode = SymmetryMode::Horizontal;
+    assert_eq!(symmetry.copy_points(vec2(13.0, 4.0), Mat4::IDENTITY), vec![vec2(13.0, -4.0)]);
+
+    symmetry.mode = SymmetryMode::Radial;
+    symmetry.radial_count = 4;
+    let pts = symmetry.copy_points(vec2(12.0, 0.0), Mat4::IDENTITY);
+    assert_eq!(pts.len(), 3);
+    for (pt, expected) in pts.iter().zip([vec2(10.0, 2.0), vec2(8.0, 0.0), vec2(10.0, -2.0)]) {
+        assert!((*pt - expected).length() < 0.0001);
+    }
+
+    // Copies keep the stroke's style and mirror its handles along with its points
+    let mut test = TestProject::new();
+    let frame = test.frame();
+    let mut copies = SymmetryCopies::new();
+    let stroke = line_stroke(vec2(12.0, 0.0), vec2(12.0, 6.0));
+    copies.update(&mut test.project, &symmetry, Mat4::IDENTITY, frame, &stroke);
+    copies.update(&mut test.project, &symmetry, Mat4::IDENTITY, frame, &stroke);
+    assert_eq!(test.project.frames.get(frame).unwrap().strokes.len(), 3);
+    let copy = test.project.strokes.get(copies.strokes()[1]).unwrap();
+    assert_eq!(copy.r, stroke.r);
+    assert!((copy.points[0][1].pt - vec2(8.0, -6.0)).length() < 0.0001);
+    assert!((copy.points[0][1].b - vec2(8.0, -8.0)).length() < 0.0001);
+
+    let acts = copies.take_acts();
+    assert_eq!(acts.len(), 3);
+    test.check_undo_redo(acts, |project, applied| {
+        assert_eq!(project.frames.get(frame).unwrap().strokes.len(), if applied { 3 } else { 0 });
+    });
+}
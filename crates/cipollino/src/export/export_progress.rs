@@ -3,7 +3,7 @@ use std::{io::Write, path::PathBuf, process::{Command, Stdio}, thread::{self, Jo
 
 use unique_type_id::UniqueTypeId;
 
-use crate::{audio::{generate::MAX_AUDIO_CHANNELS, state::AudioState}, editor::{dialog::Dialog, state::EditorState, EditorSystems}, project::{graphic::Graphic, obj::{obj_list::ObjListTrait, ObjPtr}}, renderer::fb::Framebuffer, util::ffmpeg::FFMPEG_PATH};
+use crate::{audio::{generate::MAX_AUDIO_CHANNELS, state::AudioState}, editor::{dialog::Dialog, state::EditorState, EditorSystems}, project::{camera::Camera, graphic::Graphic, obj::{obj_list::ObjListTrait, ObjPtr}}, renderer::fb::Framebuffer, util::ffmpeg::FFMPEG_PATH};
 
 use super::video_writer::VideoWriter;
 
@@ -150,7 +150,9 @@ impl Dialog for ExportProgressDialog {
                 let h = gfx.h;
                 let gfx_len = gfx.len as i32;
 
-                systems.renderer.render(fb, None, w * aa_scl, h * aa_scl, glam::Vec2::ZERO, h as f32 / 2.0, &mut state.project, self.gfx, *curr_frame, 0, 0, systems.gl);
+                // Clips without a camera layer are rendered through the default camera
+                let camera = Camera::of_graphic(&state.project, self.gfx, *curr_frame).unwrap_or_default();
+                systems.renderer.render(fb, None, w * aa_scl, h * aa_scl, camera.position, camera.cam_size(h), camera.rotation, &mut state.project, self.gfx, *curr_frame, 0, 0, systems.gl);
                 aa_fb.resize(w, h, systems.gl);
                 unsafe {
                     systems.gl.bind_framebuffer(glow::FRAMEBUFFER, Some(aa_fb.fbo));
@@ -16,6 +16,8 @@ pub mod audio;
 
 fn main() -> Result<(), eframe::Error> {
 
+    env_logger::init();
+
     #[cfg(not(debug_assertions))]
     set_current_dir(current_exe().unwrap().parent().unwrap()).unwrap();
 
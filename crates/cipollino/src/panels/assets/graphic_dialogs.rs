@@ -34,6 +34,7 @@ impl Dialog for NewGraphicDialog {
             if ui.button("Create").clicked() {
                 if let Some((_, acts)) = Graphic::asset_add(&mut state.project, root_folder, Graphic {
                     layers: Vec::new(),
+                    guides: Vec::new(),
                     name: self.create_graphic_data.name.clone(),
                     ..self.create_graphic_data
                 }) {
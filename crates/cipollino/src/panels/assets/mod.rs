@@ -1,7 +1,7 @@
 
 use std::cmp;
 
-use crate::{editor::{state::EditorState, EditorSystems}, project::{action::Action, resource::{audio::AudioFile, ResPtr, ResourceType}, folder::Folder, graphic::Graphic, obj::{asset::Asset, obj_list::ObjListTrait, ObjBox, ObjPtr}, palette::Palette, Project}, util::ui::dnd::{dnd_drop_zone_reset_colors, dnd_drop_zone_setup_colors, draggable_label, draggable_widget}};
+use crate::{editor::{state::EditorState, symbol_commands::place_symbol_in_scene, EditorSystems}, project::{action::Action, resource::{audio::AudioFile, ResPtr, ResourceType}, folder::Folder, graphic::Graphic, obj::{asset::Asset, obj_list::ObjListTrait, ObjBox, ObjPtr}, palette::Palette, Project}, util::ui::dnd::{dnd_drop_zone_reset_colors, dnd_drop_zone_setup_colors, draggable_label, draggable_widget}};
 use crate::project::AssetPtr;
 
 use self::graphic_dialogs::{GraphicPropertiesDialog, NewGraphicDialog};
@@ -246,6 +246,7 @@ impl AssetsPanel {
         let folder = state.project.folders.get(folder_ptr)?;
         for gfx in Self::sort_asset_list(&state.project, &folder.graphics).iter().map(|obj_box| obj_box.make_ptr()).collect::<Vec<ObjPtr<Graphic>>>() {
             let mut open_properties = false;
+            let mut place = false;
             let mut load = false;
             self.render_asset(ui, state, systems, gfx, folder_ptr, open, delete, rename, |ui| {
                 if ui.button("Properties").clicked() {
@@ -253,6 +254,11 @@ impl AssetsPanel {
                     open_properties = true;
                     ui.close_menu();
                 }
+                if ui.button("Place in Scene").clicked() {
+                    load = true;
+                    place = true;
+                    ui.close_menu();
+                }
             }); 
 
             if load {
@@ -261,6 +267,9 @@ impl AssetsPanel {
             if open_properties {
                 systems.dialog.open_dialog(GraphicPropertiesDialog::new(gfx)); 
             }
+            if place {
+                place_symbol_in_scene(state, gfx);
+            }
         }
 
         let folder = state.project.folders.get(folder_ptr)?;
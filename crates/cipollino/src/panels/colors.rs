@@ -51,7 +51,7 @@ impl ColorPanel {
                     }
 
                     if ui.button(egui_phosphor::regular::EYEDROPPER_SAMPLE).clicked() {
-                        state.color = StrokeColor::Palette(color_box.make_ptr(), color.color);
+                        state.color = StrokeColor::Palette { color: color_box.make_ptr(), backup: color.color };
                     }
                 });
                 
@@ -6,11 +6,15 @@ use glow::HasContext;
 pub mod overlay;
 
 use crate::{
-    editor::{clipboard::Clipboard, keybind::{CenterSceneKeybind, DeleteKeybind, Keybind}, selection::Selection, state::EditorState, EditorSystems}, project::{action::Action, graphic::Graphic, obj::{child_obj::ChildObj, obj_list::ObjListTrait, ObjPtr}, stroke::{Stroke, StrokeColor}}, renderer::fb::Framebuffer, util::ui::{color::color_picker, keybind::consume_shortcut}
+    editor::{clipboard::Clipboard, keybind::{CenterSceneKeybind, DeleteKeybind, Keybind}, selection::Selection, snapping::{set_guides, snap_point}, state::EditorState, symmetry::SymmetryMode, EditorSystems}, project::{action::{Action, ObjAction}, camera::Camera, graphic::{Graphic, Guide}, obj::{child_obj::ChildObj, obj_list::ObjListTrait, ObjPtr}, stroke::{Stroke, StrokeColor}}, renderer::fb::Framebuffer, util::ui::{color::color_picker, keybind::consume_shortcut}
 };
 
+use self::overlay::SYMMETRY_CENTER_R;
 use super::super::tools::active_frame_proj_layer_frame;
 
+// How close to a guide the mouse needs to be to drag it, relative to the camera size
+const GUIDE_GRAB_DIST: f32 = 0.01;
+
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct ScenePanel {
     #[serde(skip)]
@@ -24,13 +28,35 @@ pub struct ScenePanel {
     pan_lock_point: Vec2,
     #[serde(skip)]
     prev_mouse_down: bool,
+    // Pen pressure or touch force of the current drag, if the device reports it
+    #[serde(skip)]
+    pub pressure: Option<f32>,
+    // Modifier keys held while using the current tool
+    #[serde(skip)]
+    pub modifiers: egui::Modifiers,
+    // Index of the guide being dragged, along with the guides from before the drag
+    #[serde(skip)]
+    guide_drag: Option<(usize, Vec<Guide>)>,
+    #[serde(skip)]
+    guide_drag_act: Option<ObjAction>,
+    #[serde(skip)]
+    symmetry_center_drag: bool,
 
     #[serde(skip)]
     pub cam_pos: glam::Vec2,
     #[serde(skip)]
     pub cam_size: f32,
+    // Measured in degrees, only used when looking through the clip's camera
     #[serde(skip)]
-    pub cam_aspect : f32
+    pub cam_rot: f32,
+    // Whether the view follows the clip's camera this frame
+    #[serde(skip)]
+    pub camera_view: bool,
+    #[serde(skip)]
+    pub cam_aspect : f32,
+    // The active layer's transform. Tools work in the layer's own space
+    #[serde(skip)]
+    pub layer_mat: glam::Mat4
 }
 
 impl Default for ScenePanel {
@@ -48,9 +74,17 @@ impl ScenePanel {
             color_key_map: Vec::new(),
             pan_lock_point: Vec2::ZERO,
             prev_mouse_down: false,
+            pressure: None,
+            modifiers: egui::Modifiers::NONE,
+            guide_drag: None,
+            guide_drag_act: None,
+            symmetry_center_drag: false,
             cam_pos: glam::vec2(0.0, 0.0),
             cam_size: 600.0,
-            cam_aspect: 1.0
+            cam_rot: 0.0,
+            camera_view: false,
+            cam_aspect: 1.0,
+            layer_mat: glam::Mat4::IDENTITY
         }
     }
 
@@ -92,6 +126,12 @@ impl ScenePanel {
             }
         }
 
+        // Deleting whatever the tool has selected, like the node tool's points
+        let tool = state.curr_tool.clone();
+        if tool.read().unwrap().has_selection() && DeleteKeybind::consume(ui, systems.prefs) {
+            tool.write().unwrap().delete_selected(state);
+        }
+
         // Deleting strokes
         if let Selection::Scene(strokes) = &mut state.selection {
             if DeleteKeybind::consume(ui, systems.prefs) {
@@ -206,21 +246,49 @@ impl ScenePanel {
             self.cam_size = (state.project.graphics.get(gfx).unwrap().h as f32) * 0.6;
         }
 
+        // Looking through the clip's camera
+        let camera = if state.camera_view { Camera::of_graphic(&state.project, gfx, state.frame()) } else { None };
+        self.camera_view = camera.is_some();
+        if let Some(camera) = camera {
+            self.cam_pos = camera.position;
+            self.cam_size = camera.cam_size(state.project.graphics.get(gfx).unwrap().h);
+            self.cam_rot = camera.rotation;
+        } else {
+            self.cam_rot = 0.0;
+        }
+
+        self.layer_mat = state.layer_matrix();
+
         if let Some(mouse_pos) = response.hover_pos() {
             let mouse_pos = self.cam_size * (mouse_pos - rect.center()) / (rect.height() * 0.5);
-            let mouse_pos = glam::vec2(mouse_pos.x, -mouse_pos.y) + self.cam_pos;
+            let mouse_pos = Vec2::from_angle(self.cam_rot.to_radians()).rotate(glam::vec2(mouse_pos.x, -mouse_pos.y)) + self.cam_pos;
+            let tool_pos = self.layer_mat.inverse().transform_point3(mouse_pos.extend(0.0)).truncate();
             self.cam_aspect = rect.aspect_ratio();
 
-            let zoom_fac =
-                (1.05 as f32).powf(-ui.input(|i| i.smooth_scroll_delta.y.clamp(-4.0, 4.0) * 0.7));
-            let next_cam_size = (self.cam_size * zoom_fac).clamp(10.0, 5000.0);
-            let zoom_fac = next_cam_size / self.cam_size;
-            self.cam_pos -= (mouse_pos - self.cam_pos) * (zoom_fac - 1.0);
-            self.cam_size = next_cam_size;
+            if !self.camera_view {
+                let zoom_fac =
+                    (1.05 as f32).powf(-ui.input(|i| i.smooth_scroll_delta.y.clamp(-4.0, 4.0) * 0.7));
+                let next_cam_size = (self.cam_size * zoom_fac).clamp(10.0, 5000.0);
+                let zoom_fac = next_cam_size / self.cam_size;
+                self.cam_pos -= (mouse_pos - self.cam_pos) * (zoom_fac - 1.0);
+                self.cam_size = next_cam_size;
+            }
 
             let mouse_down = response.is_pointer_button_down_on() || response.clicked();
+            if mouse_down {
+                ui.input(|i| {
+                    for event in &i.events {
+                        if let egui::Event::Touch { force: Some(force), .. } = event {
+                            self.pressure = Some(*force);
+                        }
+                    }
+                });
+            } else {
+                self.pressure = None;
+            }
+            self.modifiers = ui.input(|i| i.modifiers);
 
-            if ui.input(|i| i.modifiers.command) {
+            if ui.input(|i| i.modifiers.command) && !self.camera_view {
                 let cursor = if mouse_down {
                     if !self.prev_mouse_down {
                         self.pan_lock_point = mouse_pos;
@@ -233,28 +301,50 @@ impl ScenePanel {
                     egui::CursorIcon::Grab
                 };
                 ui.output_mut(|o| o.cursor_icon = cursor);
+            } else if self.symmetry_center_drag || (mouse_down && !self.prev_mouse_down && self.over_symmetry_center(state, mouse_pos)) {
+                self.symmetry_center_drag = mouse_down;
+                if mouse_down {
+                    let center = snap_point(state, tool_pos, self.cam_size, &Vec::new());
+                    state.symmetry.center = self.to_scene(center);
+                }
+                ui.output_mut(|o| o.cursor_icon = egui::CursorIcon::Move);
+            } else if self.guide_drag.is_some() || (mouse_down && !self.prev_mouse_down && self.start_guide_drag(state, gfx, mouse_pos)) {
+                self.drag_guide(state, gfx, mouse_pos, mouse_down);
+                ui.output_mut(|o| o.cursor_icon = egui::CursorIcon::Move);
             } else {
 
+                // Deleting guides
+                if response.secondary_clicked() {
+                    if let Some(idx) = self.find_guide(state, gfx, mouse_pos) {
+                        let mut guides = state.project.graphics.get(gfx).unwrap().guides.clone();
+                        guides.remove(idx);
+                        set_guides(state, guides);
+                    }
+                }
+
                 // Tool interaction
                 let tool = state.curr_tool.clone();
                 if mouse_down && !self.prev_mouse_down {
-                    tool.write().unwrap().mouse_click(mouse_pos, state, ui, self, systems.gl);
+                    tool.write().unwrap().mouse_click(tool_pos, state, ui, self, systems.gl);
                 }
                 if mouse_down && self.prev_mouse_down {
-                    tool.write().unwrap().mouse_down(mouse_pos, state, self);
+                    tool.write().unwrap().mouse_down(tool_pos, state, self);
                 }
                 if !mouse_down && self.prev_mouse_down {
-                    tool.write().unwrap().mouse_release(mouse_pos, state, ui, self, systems.gl);
+                    tool.write().unwrap().mouse_release(tool_pos, state, ui, self, systems.gl);
                 }
                 if response.hovered() {
                     ui.ctx().output_mut(|o| {
                         let tool = state.curr_tool.clone();
-                        o.cursor_icon = tool.write().unwrap().mouse_cursor(mouse_pos, state, self, systems.gl);
+                        o.cursor_icon = tool.write().unwrap().mouse_cursor(tool_pos, state, self, systems.gl);
                     });
                 }
 
             }
 
+            if !mouse_down {
+                state.snapping.indicator = None;
+            }
             self.prev_mouse_down = mouse_down;
         }
 
@@ -276,6 +366,7 @@ impl ScenePanel {
             (rect.height() as u32) * 2,
             self.cam_pos,
             self.cam_size,
+            self.cam_rot,
             &mut state.project,
             gfx,
             frame,
@@ -294,17 +385,64 @@ impl ScenePanel {
 
     }
 
+    fn over_symmetry_center(&self, state: &EditorState, pos: Vec2) -> bool {
+        state.symmetry.mode != SymmetryMode::Off && (state.symmetry.center - pos).length() < 2.0 * SYMMETRY_CENTER_R * self.cam_size
+    }
+
+    fn find_guide(&self, state: &EditorState, gfx: ObjPtr<Graphic>, pos: Vec2) -> Option<usize> {
+        if !state.snapping.show_guides || state.snapping.lock_guides {
+            return None;
+        }
+        let gfx = state.project.graphics.get(gfx)?;
+        let grab_dist = GUIDE_GRAB_DIST * self.cam_size;
+        gfx.guides.iter().position(|guide| (guide.closest_point(pos) - pos).length() < grab_dist)
+    }
+
+    fn start_guide_drag(&mut self, state: &EditorState, gfx: ObjPtr<Graphic>, pos: Vec2) -> bool {
+        let Some(idx) = self.find_guide(state, gfx, pos) else { return false; };
+        self.guide_drag = Some((idx, state.project.graphics.get(gfx).unwrap().guides.clone()));
+        true
+    }
+
+    fn drag_guide(&mut self, state: &mut EditorState, gfx: ObjPtr<Graphic>, pos: Vec2, mouse_down: bool) {
+        if !mouse_down {
+            self.guide_drag = None;
+            if let Some(act) = self.guide_drag_act.take() {
+                state.actions.add(Action::from_list(vec![act]));
+            }
+            return;
+        }
+        let Some((idx, orig_guides)) = &self.guide_drag else { return; };
+        let mut guides = orig_guides.clone();
+        let guide = &mut guides[*idx];
+        // Guides stick to grid lines
+        let pos = if state.snapping.enabled && state.snapping.snap_grid && state.snapping.grid_size > 0.0 {
+            (pos / state.snapping.grid_size).round() * state.snapping.grid_size
+        } else {
+            pos
+        };
+        guide.pos = if guide.vertical { pos.x } else { pos.y };
+        if let Some(act) = self.guide_drag_act.take() {
+            act.undo(&mut state.project);
+        }
+        self.guide_drag_act = Graphic::set_guides(&mut state.project, gfx, guides);
+    }
+
+    // Takes a point from the active layer's space, where tools work, to the scene's
+    pub fn to_scene(&self, pos: Vec2) -> Vec2 {
+        self.layer_mat.transform_point3(pos.extend(0.0)).truncate()
+    }
+
+    // Finds the stroke drawn at a point in the active layer's space
     pub fn sample_pick(&mut self, pos: Vec2, gl: &Arc<glow::Context>) -> Option<ObjPtr<Stroke>> {
         if let Some(fb_pick) = self.fb_pick.lock().unwrap().as_ref() {
+            let pos = self.to_scene(pos);
 
             let h_cam_size = self.cam_size * (fb_pick.w as f32) / (fb_pick.h as f32);
-            let left = self.cam_pos.x - h_cam_size;
-            let right = self.cam_pos.x + h_cam_size;
-            let top = self.cam_pos.y + self.cam_size;
-            let bottom = self.cam_pos.y - self.cam_size;
+            let pos = Vec2::from_angle(-self.cam_rot.to_radians()).rotate(pos - self.cam_pos);
 
-            let x = (pos.x - left) / (right - left);
-            let y = (pos.y - bottom) / (top - bottom);
+            let x = (pos.x + h_cam_size) / (2.0 * h_cam_size);
+            let y = (pos.y + self.cam_size) / (2.0 * self.cam_size);
 
             let px = (x * (fb_pick.w as f32)) as i32;
             let py = (y * (fb_pick.h as f32)) as i32;
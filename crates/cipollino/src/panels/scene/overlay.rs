@@ -1,10 +1,17 @@
 
 use glam::Vec2;
 
-use crate::{editor::{selection::Selection, state::EditorState, EditorSystems}, project::{graphic::Graphic, obj::{obj_list::ObjListTrait, ObjPtr}}};
+use crate::{editor::{selection::Selection, state::EditorState, symmetry::SymmetryMode, EditorSystems}, project::{camera::Camera, graphic::Graphic, obj::{obj_list::ObjListTrait, ObjPtr}}};
 use super::ScenePanel;
 use glow::HasContext;
 
+// Above this many grid lines across the view, the grid spacing is doubled
+const MAX_GRID_LINES: f32 = 80.0;
+const GUIDE_COLOR: glam::Vec4 = glam::vec4(1.0, 0.2, 0.8, 0.8);
+const SYMMETRY_COLOR: glam::Vec4 = glam::vec4(0.3, 0.8, 0.3, 0.8);
+// Size of the symmetry centre's handle, relative to the camera size
+pub const SYMMETRY_CENTER_R: f32 = 0.008;
+
 pub struct OverlayRenderer<'a, 'b> {
     renderer: &'a mut EditorSystems<'b>,
     proj_view: glam::Mat4,
@@ -22,9 +29,13 @@ impl<'a, 'b> OverlayRenderer<'a, 'b> {
     }
 
     pub fn line(&mut self, p0: glam::Vec2, p1: glam::Vec2, color: glam::Vec4) {
+        self.line_with_width(p0, p1, color, 0.004 * self.cam_size);
+    }
+
+    pub fn line_with_width(&mut self, p0: glam::Vec2, p1: glam::Vec2, color: glam::Vec4, width: f32) {
         let center = (p0 + p1) / 2.0;
         let len = (p1 - p0).length();
-        let scale = glam::vec3(len, 0.004 * self.cam_size, 1.0);
+        let scale = glam::vec3(len, width, 1.0);
         let angle = glam::vec2(1.0, 0.0).angle_between(p1 - p0);
         let model = glam::Mat4::from_translation(glam::vec3(center.x, center.y, 0.0)) * glam::Mat4::from_axis_angle(glam::vec3(0.0, 0.0, 1.0), angle) * glam::Mat4::from_scale(scale);
         let trans = self.proj_view * model; 
@@ -52,18 +63,78 @@ impl ScenePanel {
             systems.gl.disable(glow::DEPTH_TEST);
         }
 
+        let gfx_ptr = gfx;
         if let Some(gfx) = state.project.graphics.get(gfx) { 
 
-            // Clip shadow
+            // Clip shadow, following the clip's camera if it has one
             if gfx.clip {
                 systems.renderer.clip_shadow_shader.enable(systems.gl);
-                let trans = proj_view * glam::Mat4::from_scale(glam::vec3(gfx.w as f32, gfx.h as f32, 1.0)); 
+                let camera = Camera::of_graphic(&state.project, gfx_ptr, state.frame()).unwrap_or_default();
+                let trans = proj_view * camera.model() * glam::Mat4::from_scale(glam::vec3(gfx.w as f32, gfx.h as f32, 1.0)); 
                 systems.renderer.clip_shadow_shader.set_mat4("uTrans", &trans, systems.gl);
                 systems.renderer.clip_shadow_mesh.render(systems.gl);
             }
 
             let mut overlay = OverlayRenderer::new(systems, proj_view, self.cam_size);
 
+            // Grid and guides
+            let view_half = glam::vec2(self.cam_size * self.cam_aspect, self.cam_size);
+            // A rotated view can see past its unrotated bounds
+            let view_half = if self.cam_rot != 0.0 { glam::Vec2::splat(view_half.length()) } else { view_half };
+            let view_min = self.cam_pos - view_half;
+            let view_max = self.cam_pos + view_half;
+            if state.snapping.show_grid && state.snapping.grid_size > 0.0 {
+                // Zoomed out, only every few grid lines are shown
+                let mut spacing = state.snapping.grid_size;
+                while view_half.y * 2.0 / spacing > MAX_GRID_LINES {
+                    spacing *= 2.0;
+                }
+                let color = glam::vec4(0.5, 0.5, 0.5, 0.4);
+                let width = 0.002 * self.cam_size;
+                let mut x = (view_min.x / spacing).ceil() * spacing;
+                while x < view_max.x {
+                    overlay.line_with_width(glam::vec2(x, view_min.y), glam::vec2(x, view_max.y), color, width);
+                    x += spacing;
+                }
+                let mut y = (view_min.y / spacing).ceil() * spacing;
+                while y < view_max.y {
+                    overlay.line_with_width(glam::vec2(view_min.x, y), glam::vec2(view_max.x, y), color, width);
+                    y += spacing;
+                }
+            }
+            if state.snapping.show_guides {
+                for guide in &gfx.guides {
+                    let (p0, p1) = if guide.vertical {
+                        (glam::vec2(guide.pos, view_min.y), glam::vec2(guide.pos, view_max.y))
+                    } else {
+                        (glam::vec2(view_min.x, guide.pos), glam::vec2(view_max.x, guide.pos))
+                    };
+                    overlay.line_with_width(p0, p1, GUIDE_COLOR, 0.003 * self.cam_size);
+                }
+            }
+
+            // Symmetry axes
+            if state.symmetry.mode != SymmetryMode::Off {
+                let center = state.symmetry.center;
+                let reach = view_half.length() + (self.cam_pos - center).length();
+                let width = 0.003 * self.cam_size;
+                match state.symmetry.mode {
+                    SymmetryMode::Horizontal => overlay.line_with_width(center - glam::vec2(reach, 0.0), center + glam::vec2(reach, 0.0), SYMMETRY_COLOR, width),
+                    SymmetryMode::Vertical => overlay.line_with_width(center - glam::vec2(0.0, reach), center + glam::vec2(0.0, reach), SYMMETRY_COLOR, width),
+                    SymmetryMode::Radial => {
+                        let n = state.symmetry.radial_count.max(1);
+                        for i in 0..n {
+                            let angle = std::f32::consts::FRAC_PI_2 + std::f32::consts::TAU * (i as f32) / (n as f32);
+                            overlay.line_with_width(center, center + glam::vec2(angle.cos(), angle.sin()) * reach, SYMMETRY_COLOR, width);
+                        }
+                    },
+                    SymmetryMode::Off => {}
+                }
+                overlay.circle(center, SYMMETRY_COLOR, SYMMETRY_CENTER_R * self.cam_size);
+            }
+
+            // Tools and the selection are drawn in the active layer's space
+            let mut overlay = OverlayRenderer::new(systems, proj_view * self.layer_mat, self.cam_size);
             state.curr_tool.clone().write().unwrap().draw_overlay(&mut overlay, state);
 
             if let Selection::Scene(strokes) = &state.selection {
@@ -78,6 +149,10 @@ impl ScenePanel {
                     }
                 }
             }
+
+            if let Some(target) = state.snapping.indicator {
+                overlay.circle(target, glam::vec4(1.0, 0.5, 0.0, 1.0), 0.006 * self.cam_size);
+            }
         }
     }
 
@@ -15,8 +15,11 @@ pub fn timeline_controls(timeline: &mut TimelinePanel, ui: &mut egui::Ui, state:
             kind: LayerKind::Animation, 
             alpha: 1.0,
             blending: BlendingMode::Normal,
+            fill_reference: false,
             frames: Vec::new(),
             sound_instances: Vec::new(),
+            camera_keys: Vec::new(),
+            transform_keys: Vec::new(),
             layers: Vec::new()
         }, 0) {
             state.actions.add(Action::from_single(act));
@@ -33,14 +36,29 @@ pub fn timeline_controls(timeline: &mut TimelinePanel, ui: &mut egui::Ui, state:
             kind: LayerKind::Group, 
             alpha: 1.0,
             blending: BlendingMode::Normal,
+            fill_reference: false,
             frames: Vec::new(),
             sound_instances: Vec::new(),
+            camera_keys: Vec::new(),
+            transform_keys: Vec::new(),
             layers: Vec::new()
         }, 0) {
             state.actions.add(Action::from_single(act));
             state.active_layer = layer;
         }
     }
+    // Each clip only uses one camera
+    let has_camera = state.project.graphics.get(state.open_graphic).unwrap().layers.iter().any(|layer| layer.get(&state.project).kind == LayerKind::Camera);
+    if ui.add_enabled(!has_camera, egui::Button::new(egui_phosphor::regular::VIDEO_CAMERA)).clicked() {
+        if let Some((_, act)) = Layer::add_at_idx(&mut state.project, LayerParent::Graphic(state.open_graphic), Layer {
+            parent: LayerParent::Graphic(state.open_graphic),
+            name: "Camera".to_owned(),
+            kind: LayerKind::Camera,
+            ..Layer::default()
+        }, 0) {
+            state.actions.add(Action::from_single(act));
+        }
+    }
 
     if ui.button(egui_phosphor::regular::REWIND).clicked() {
         state.time = 0;
@@ -1,7 +1,7 @@
 
 use egui::{vec2, Vec2};
 
-use crate::{editor::{selection::Selection, state::EditorState}, project::{action::{Action, ObjAction}, resource::{audio::AudioFile, ResPtr}, folder::Folder, frame::Frame, layer::Layer, obj::{child_obj::ChildObj, obj_list::ObjListTrait, ObjPtr}, sound_instance::SoundInstance, AssetPtr, Project}};
+use crate::{editor::{selection::Selection, state::EditorState}, project::{action::{Action, ObjAction}, easing::Easing, resource::{audio::AudioFile, ResPtr}, folder::Folder, frame::Frame, layer::Layer, obj::{child_obj::ChildObj, obj_list::ObjListTrait, ObjPtr}, sound_instance::SoundInstance, AssetPtr, Project}};
 
 use super::{FrameGridRow, FrameGridRowKind, TimelinePanel};
 
@@ -90,6 +90,16 @@ impl FrameGridRow {
             } 
         }
 
+        // Tween lines
+        for frame in &layer.frames {
+            if let Some(target) = Frame::tween_target(&state.project, frame.make_ptr()).and_then(|target| state.project.frames.get(target)) {
+                let y = rect.top() + 0.5 * frame_h;
+                let x0 = rect.left() + (frame.get(&state.project).time as f32 + 0.5) * frame_w;
+                let x1 = rect.left() + (target.time as f32 + 0.5) * frame_w;
+                ui.painter().hline(egui::Rangef::new(x0, x1), y, egui::Stroke::new(1.5, ui.visuals().weak_text_color()));
+            }
+        }
+
         // Frame dots
         for frame in &layer.frames {
             let dot_pos = rect.left_top() + Vec2::new((frame.get(&state.project).time as f32 + 0.5) * frame_w, 0.5 * frame_h);
@@ -199,16 +209,118 @@ impl FrameGridRow {
         None
     }
 
+    // Key diamonds, clicking one jumps to it
+    fn frame_area_keys(ui: &mut egui::Ui, frame_w: f32, frame_h: f32, state: &mut EditorState, rect: egui::Rect, response: &egui::Response, times: Vec<i32>) {
+        let mut jump_to = None;
+        for time in times {
+            let key_pos = rect.left_top() + Vec2::new((time as f32 + 0.5) * frame_w, 0.5 * frame_h);
+            let r = frame_w * 0.35;
+            ui.painter().add(egui::Shape::convex_polygon(
+                vec![key_pos - vec2(r, 0.0), key_pos - vec2(0.0, r), key_pos + vec2(r, 0.0), key_pos + vec2(0.0, r)],
+                egui::Color32::from_rgb(214, 160, 80),
+                egui::Stroke::NONE));
+            let key_rect = egui::Rect::from_center_size(key_pos, egui::Vec2::new(frame_w, frame_h));
+            if let Some(hover_pos) = response.hover_pos() {
+                if key_rect.contains(hover_pos) && response.clicked() {
+                    jump_to = Some(time);
+                }
+            }
+        }
+        if let Some(time) = jump_to {
+            state.time = ((time as f32 + 0.5) * state.frame_len() / state.sample_len()).floor() as i64;
+            state.pause();
+        }
+    }
+
+    fn frame_area_layer_camera(&self, ui: &mut egui::Ui, frame_w: f32, frame_h: f32, state: &mut EditorState, rect: egui::Rect, response: &egui::Response) -> Option<()> {
+        let layer = state.project.layers.get(self.layer)?;
+        let times = layer.camera_keys.iter().map(|key| key.get(&state.project).time).collect();
+        Self::frame_area_keys(ui, frame_w, frame_h, state, rect, response, times);
+        None
+    }
+
+    fn frame_area_transform_keys(&self, ui: &mut egui::Ui, frame_w: f32, frame_h: f32, state: &mut EditorState, rect: egui::Rect, response: &egui::Response) -> Option<()> {
+        Self::active_frame_highlight(self.layer, state, ui, rect);
+        let layer = state.project.layers.get(self.layer)?;
+        let times = layer.transform_keys.iter().map(|key| key.get(&state.project).time).collect();
+        Self::frame_area_keys(ui, frame_w, frame_h, state, rect, response, times);
+        None
+    }
+
     fn frame_area(&self, timeline: &mut TimelinePanel, ui: &mut egui::Ui, frame_w: f32, frame_h: f32, state: &mut EditorState, response: &egui::Response, mouse_went_down: bool, rect: egui::Rect) {
         match &self.kind {
             FrameGridRowKind::AnimationLayer => { self.frame_area_layer_animation(timeline, ui, frame_w, frame_h, state, rect, response, mouse_went_down); },
             FrameGridRowKind::AudioLayer => { self.frame_area_layer_audio(timeline, ui, frame_w, frame_h, state, rect, response, mouse_went_down); },
+            FrameGridRowKind::CameraLayer => { self.frame_area_layer_camera(ui, frame_w, frame_h, state, rect, response); },
+            FrameGridRowKind::TransformKeys => { self.frame_area_transform_keys(ui, frame_w, frame_h, state, rect, response); },
             _ => {}
         };
     }
 
 }
 
+// The selected frames, or the active layer's frame at the playhead if no frames are selected
+fn tween_menu_frames(state: &EditorState) -> Vec<ObjPtr<Frame>> {
+    if let Selection::Timeline(frames, _) = &state.selection {
+        if !frames.is_empty() {
+            return frames.clone();
+        }
+    }
+    state.project.layers.get(state.active_layer)
+        .and_then(|layer| layer.get_frame_at(&state.project, state.frame()))
+        .map(|frame| vec![frame.make_ptr()])
+        .unwrap_or_default()
+}
+
+fn tween_context_menu(state: &mut EditorState, response: &egui::Response) {
+    response.context_menu(|ui| {
+        let frames = tween_menu_frames(state);
+        if frames.is_empty() {
+            ui.close_menu();
+            return;
+        }
+
+        let mut tween = frames.iter().any(|frame| state.project.frames.get(*frame).map(|frame| frame.tween).unwrap_or(false));
+        if ui.checkbox(&mut tween, "Tween to Next Frame").changed() {
+            let mut action = Action::new();
+            for frame in &frames {
+                if let Some(act) = Frame::set_tween(&mut state.project, *frame, tween) {
+                    action.add(act);
+                }
+            }
+            state.actions.add(action);
+        }
+
+        ui.add_enabled_ui(tween, |ui| {
+            ui.menu_button("Easing", |ui| {
+                let current = frames.iter().find_map(|frame| state.project.frames.get(*frame).filter(|frame| frame.tween).map(|frame| frame.tween_easing));
+                for option in Easing::ALL {
+                    if ui.radio(current == Some(option), option.name()).clicked() {
+                        let mut action = Action::new();
+                        for frame in &frames {
+                            if let Some(act) = Frame::set_tween_easing(&mut state.project, *frame, option) {
+                                action.add(act);
+                            }
+                        }
+                        state.actions.add(action);
+                        ui.close_menu();
+                    }
+                }
+            });
+            if ui.button("Bake In-Betweens").clicked() {
+                let mut action = Action::new();
+                for frame in &frames {
+                    if let Some(acts) = Frame::bake_tween(&mut state.project, *frame) {
+                        action.add_list(acts);
+                    }
+                }
+                state.actions.add(action);
+                ui.close_menu();
+            }
+        });
+    });
+}
+
 pub fn frames(timeline: &mut TimelinePanel, ui: &mut egui::Ui, frame_w: f32, frame_h: f32, state: &mut EditorState, n_frames: i32, grid_rows: &Vec<FrameGridRow>) {
 
     let gfx = state.project.graphics.get(state.open_graphic).unwrap();
@@ -245,6 +357,8 @@ pub fn frames(timeline: &mut TimelinePanel, ui: &mut egui::Ui, frame_w: f32, fra
             egui::Stroke::NONE);
     }
 
+    tween_context_menu(state, &response);
+
     // Frame area 
     let mut y = 0.0;
     for row in grid_rows {
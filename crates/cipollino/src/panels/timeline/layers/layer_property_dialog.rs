@@ -1,13 +1,15 @@
 
 use unique_type_id::UniqueTypeId;
 
-use crate::{editor::{dialog::Dialog, state::EditorState, EditorSystems}, project::{action::{Action, ObjAction}, layer::{BlendingMode, Layer, LayerKind}, obj::{obj_list::ObjListTrait, ObjPtr}}, util::ui::drag_value};
+use crate::{editor::{dialog::Dialog, state::EditorState, EditorSystems}, project::{action::{Action, ObjAction}, easing::Easing, layer::{BlendingMode, Layer, LayerKind}, layer_key::LayerKey, layer_transform::{LayerTransform, TransformKey}, obj::{child_obj::ChildObj, obj_list::ObjListTrait, ObjPtr}}, util::ui::drag_value};
 
 #[derive(UniqueTypeId)]
 pub struct LayerPropertyDialog {
     layer: ObjPtr<Layer>,
 
-    set_alpha_action: Option<ObjAction> 
+    set_alpha_action: Option<ObjAction>,
+    // Keeps getting replaced while the transform is being edited
+    transform_action: Option<Action>
 }
 
 impl LayerPropertyDialog {
@@ -15,7 +17,96 @@ impl LayerPropertyDialog {
     pub fn new(layer: ObjPtr<Layer>) -> Self {
         Self {
             layer,
-            set_alpha_action: None
+            set_alpha_action: None,
+            transform_action: None
+        }
+    }
+
+    fn transform_section(&mut self, ui: &mut egui::Ui, state: &mut EditorState) {
+        let time = state.frame();
+        let old_transform = LayerTransform::at_time(&state.project, self.layer, time).unwrap_or_default();
+        let key = TransformKey::get_key_exactly_at(&state.project, self.layer, time);
+        let mut transform = old_transform;
+        let old_opacity = old_transform.opacity * 100.0;
+        let mut opacity = old_opacity;
+        let mut change = (false, false);
+
+        ui.separator();
+        ui.label(format!("Transform at frame {}:", time + 1));
+        let right_align_layout = egui::Layout::top_down(egui::Align::RIGHT);
+        egui::Grid::new(ui.next_auto_id()).min_col_width(80.0).show(ui, |ui| {
+            let vec_row = |ui: &mut egui::Ui, label: &str, val: &mut glam::Vec2, change: &mut (bool, bool)| {
+                ui.with_layout(right_align_layout, |ui| {
+                    ui.label(label);
+                });
+                ui.horizontal(|ui| {
+                    drag_value(ui, "X", &mut val.x, f32::MIN..=f32::MAX, Some(change));
+                    drag_value(ui, "Y", &mut val.y, f32::MIN..=f32::MAX, Some(change));
+                });
+                ui.end_row();
+            };
+            vec_row(ui, "Position:", &mut transform.position, &mut change);
+            vec_row(ui, "Scale:", &mut transform.scale, &mut change);
+            vec_row(ui, "Pivot:", &mut transform.pivot, &mut change);
+
+            ui.with_layout(right_align_layout, |ui| {
+                ui.label("Rotation:");
+            });
+            drag_value(ui, "", &mut transform.rotation, -3600.0..=3600.0, Some(&mut change));
+            ui.end_row();
+
+            ui.with_layout(right_align_layout, |ui| {
+                ui.label("Opacity:");
+            });
+            drag_value(ui, "", &mut opacity, 0.0..=100.0, Some(&mut change));
+            ui.end_row();
+
+            if let Some(key_ptr) = key {
+                let old_easing = state.project.transform_keys.get(key_ptr).map(|key| key.easing).unwrap_or(Easing::Linear);
+                let mut easing = old_easing;
+                ui.with_layout(right_align_layout, |ui| {
+                    ui.label("Easing:");
+                });
+                egui::ComboBox::new(ui.next_auto_id(), "")
+                    .selected_text(easing.name()).show_ui(ui, |ui| {
+                        for option in Easing::ALL {
+                            ui.selectable_value(&mut easing, option, option.name());
+                        }
+                });
+                ui.end_row();
+                if easing != old_easing {
+                    if let Some(act) = TransformKey::set_easing(&mut state.project, key_ptr, easing) {
+                        state.actions.add(Action::from_single(act));
+                    }
+                }
+            }
+        });
+
+        if opacity != old_opacity {
+            transform.opacity = opacity / 100.0;
+        }
+        if transform != old_transform {
+            if let Some(action) = self.transform_action.take() {
+                action.undo(&mut state.project);
+            }
+            self.transform_action = TransformKey::set_at(&mut state.project, self.layer, time, transform).map(Action::from_list);
+        }
+        if change.1 {
+            if let Some(action) = self.transform_action.take() {
+                state.actions.add(action);
+            }
+        }
+
+        if let Some(key_ptr) = key {
+            if ui.button("Delete Key").clicked() {
+                if let Some(act) = TransformKey::delete(&mut state.project, key_ptr) {
+                    state.actions.add(Action::from_single(act));
+                }
+            }
+        } else if ui.button("Add Key").clicked() {
+            if let Some(acts) = TransformKey::set_at(&mut state.project, self.layer, time, old_transform) {
+                state.actions.add(Action::from_list(acts));
+            }
         }
     }
 
@@ -30,10 +121,13 @@ impl Dialog for LayerPropertyDialog {
             return true;
         };
 
+        let layer_kind = layer.kind;
         let mut alpha = layer.alpha * 100.0;
         let mut set_alpha = (false, false);
         let mut blending = layer.blending;
         let initial_blending = blending;
+        let mut fill_reference = layer.fill_reference;
+        let initial_fill_reference = fill_reference;
 
         let right_align_layout = egui::Layout::top_down(egui::Align::RIGHT);
         egui::Grid::new(ui.next_auto_id()).min_col_width(80.0).show(ui, |ui| {
@@ -71,6 +165,12 @@ impl Dialog for LayerPropertyDialog {
                 });
                 ui.end_row();
 
+                ui.with_layout(right_align_layout, |ui| {
+                    ui.label("Fill Reference:");
+                });
+                ui.checkbox(&mut fill_reference, "")
+                    .on_hover_text("Use this layer's strokes as boundaries for bucket fills set to only use reference layers");
+                ui.end_row();
             }
         });
 
@@ -93,6 +193,16 @@ impl Dialog for LayerPropertyDialog {
             }
         } 
 
+        if fill_reference != initial_fill_reference {
+            if let Some(act) = Layer::set_fill_reference(&mut state.project, self.layer, fill_reference) {
+                state.actions.add(Action::from_single(act));
+            }
+        }
+
+        if layer_kind == LayerKind::Animation || layer_kind == LayerKind::Group {
+            self.transform_section(ui, state);
+        }
+
         false
     }
 
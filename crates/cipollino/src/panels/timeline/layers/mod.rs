@@ -5,7 +5,7 @@ use crate::{editor::{state::EditorState, EditorSystems}, project::{action::Actio
 
 use self::layer_property_dialog::LayerPropertyDialog;
 
-use super::{FrameGridRow, TimelinePanel};
+use super::{FrameGridRow, FrameGridRowKind, TimelinePanel};
 
 mod layer_property_dialog;
 
@@ -39,7 +39,7 @@ impl FrameGridRow {
                 timeline.layer_edit_curr_name = layer.name.clone();
                 ui.close_menu();
             }
-            if layer.kind != LayerKind::Group {
+            if layer.kind != LayerKind::Group && layer.kind != LayerKind::Camera {
                 ui.menu_button("Layer Type", |ui| {
                     if ui.button("Animation").clicked() {
                         *set_layer_kind = Some(LayerKind::Animation);
@@ -62,7 +62,7 @@ impl FrameGridRow {
         });
     } 
 
-    // The show/hide icon on animation layers, mute/unmute on audio layers
+    // The show/hide icon on animation layers, mute/unmute on audio layers, enable/disable on camera layers
     fn render_layer_icons(&self, project: &Project, layer: &Layer, ui: &mut egui::Ui, rect: &egui::Rect, show_hide_layer: &mut bool, lock_unlock_layer: &mut bool) {
         let icon_width = 15.0; 
 
@@ -70,13 +70,15 @@ impl FrameGridRow {
             match layer.kind {
                 LayerKind::Animation => egui_phosphor::regular::EYE,
                 LayerKind::Audio => egui_phosphor::regular::SPEAKER_HIGH,
-                LayerKind::Group => egui_phosphor::regular::EYE
+                LayerKind::Group => egui_phosphor::regular::EYE,
+                LayerKind::Camera => egui_phosphor::regular::VIDEO_CAMERA
             }
         } else {
             match layer.kind {
                 LayerKind::Animation => egui_phosphor::regular::EYE_CLOSED,
                 LayerKind::Audio => egui_phosphor::regular::SPEAKER_SLASH,
-                LayerKind::Group => egui_phosphor::regular::EYE_CLOSED
+                LayerKind::Group => egui_phosphor::regular::EYE_CLOSED,
+                LayerKind::Camera => egui_phosphor::regular::VIDEO_CAMERA_SLASH
             }
         };
         let eye_rect = egui::Rect::from_min_size(rect.max - vec2(2.0 * (icon_width + ui.spacing().icon_spacing), rect.height()), vec2(icon_width, rect.height())); 
@@ -200,8 +202,23 @@ impl FrameGridRow {
         None
     }
 
+    // Opens the layer's properties, where its transform can be keyed, when double clicked
+    fn render_transform_keys_row(&self, ui: &mut egui::Ui, rect: egui::Rect, state: &mut EditorState, response: &egui::Response, systems: &mut EditorSystems, layer_group_triangle_width: f32) {
+        let indent_size = 10.0;
+        if self.layer == state.active_layer {
+            ui.painter().rect(rect, 0.0, super::HIGHLIGHT, egui::Stroke::NONE);
+        }
+        let mut text_rect = rect;
+        text_rect.set_left(text_rect.left() + (self.indent as f32) * indent_size + layer_group_triangle_width);
+        let mut child_ui = ui.child_ui(text_rect, egui::Layout::top_down(egui::Align::LEFT));
+        child_ui.add(egui::Label::new(egui::RichText::new(format!("{} Transform", egui_phosphor::regular::ARROWS_OUT_CARDINAL)).weak()).selectable(false));
+        if response.double_clicked() {
+            systems.dialog.open_dialog(LayerPropertyDialog::new(self.layer));
+        }
+    }
+
     fn draggable(&self, timeline: &TimelinePanel) -> bool {
-        self.layer != timeline.layer_editing_name
+        self.layer != timeline.layer_editing_name && !matches!(self.kind, FrameGridRowKind::TransformKeys)
     }
 
 }
@@ -236,7 +253,11 @@ pub fn layers(timeline: &mut TimelinePanel, ui: &mut egui::Ui, frame_h: f32, sta
         for row in grid_rows {
             let mut render_layer = |ui: &mut egui::Ui, timeline: &mut TimelinePanel, _: bool| {
                 let (rect, response) = ui.allocate_exact_size(Vec2::new(sidebar_w, frame_h), egui::Sense::click());
-                row.render_layer(timeline, ui, rect, state, systems, &response, &mut layer_drop_idx, layer_group_triangle_width);
+                if let FrameGridRowKind::TransformKeys = row.kind {
+                    row.render_transform_keys_row(ui, rect, state, &response, systems, layer_group_triangle_width);
+                } else {
+                    row.render_layer(timeline, ui, rect, state, systems, &response, &mut layer_drop_idx, layer_group_triangle_width);
+                }
                 ((), response)
             };
             if row.draggable(timeline) {
@@ -54,7 +54,10 @@ pub struct TimelinePanel {
 pub enum FrameGridRowKind {
     AnimationLayer,
     AudioLayer,
-    GroupLayer
+    GroupLayer,
+    CameraLayer,
+    // Sub-row under a layer showing its transform keys
+    TransformKeys
 }
 
 pub struct FrameGridRow {
@@ -92,7 +95,11 @@ impl TimelinePanel {
                 LayerKind::Animation => FrameGridRowKind::AnimationLayer,
                 LayerKind::Audio => FrameGridRowKind::AudioLayer,
                 LayerKind::Group => FrameGridRowKind::GroupLayer, 
+                LayerKind::Camera => FrameGridRowKind::CameraLayer,
             }, indent));
+            if !layer.get(&state.project).transform_keys.is_empty() {
+                rows.push((layer.make_ptr(), local_idx, FrameGridRowKind::TransformKeys, indent + 1));
+            }
             if layer.get(&state.project).kind == LayerKind::Group && layer.get(&state.project).open {
                 self.calc_grid_rows_rec(state, &layer.get(&state.project).layers, rows, indent + 1);
             }
@@ -298,7 +305,7 @@ pub fn new_frame(state: &mut EditorState) -> Option<()> {
         if let Some((_, act)) = Frame::add(&mut state.project, state.active_layer, Frame {
             layer: state.active_layer,
             time,
-            strokes: Vec::new()
+            ..Frame::default()
         }) {
             state.actions.add(Action::from_single(act));
         }
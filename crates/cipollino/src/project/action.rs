@@ -83,7 +83,11 @@ impl Default for Action {
 
 pub struct ActionManager {
     actions: Vec<Action>,
-    curr: i32 
+    curr: i32,
+    // Set whenever an action is added, undone or redone
+    changed: bool,
+    // Counts every action added, undone or redone, for systems that recompute things only when the project changes
+    version: u64
 }
 
 impl ActionManager {
@@ -91,7 +95,9 @@ impl ActionManager {
     pub fn new() -> Self {
         Self {
             actions: Vec::new(),
-            curr: -1
+            curr: -1,
+            changed: false,
+            version: 0
         }
     }
 
@@ -99,6 +105,8 @@ impl ActionManager {
         self.actions.truncate((self.curr + 1) as usize);
         self.actions.push(act);
         self.curr += 1;
+        self.changed = true;
+        self.version += 1;
     }
 
     pub fn can_redo(&self) -> bool {
@@ -109,6 +117,8 @@ impl ActionManager {
         if self.can_redo() { 
             self.curr += 1;
             self.actions[self.curr as usize].redo(project);
+            self.changed = true;
+            self.version += 1;
         }
     }
 
@@ -120,7 +130,17 @@ impl ActionManager {
         if self.can_undo() {
             self.actions[self.curr as usize].undo(project);
             self.curr -= 1;
+            self.changed = true;
+            self.version += 1;
         }
     }
 
+    pub fn take_changed(&mut self) -> bool {
+        std::mem::replace(&mut self.changed, false)
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
 }
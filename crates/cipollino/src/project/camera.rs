@@ -0,0 +1,192 @@
+use glam::{vec3, Mat4, Vec2};
+use project_macros::{ObjClone, ObjSerialize, Object};
+use unique_type_id::UniqueTypeId;
+
+use super::{action::ObjAction, easing::Easing, graphic::Graphic, layer::{Layer, LayerKind}, layer_key::LayerKey, obj::{child_obj::{ChildObj, HasRootAsset}, Obj, ObjBox, ObjClone, ObjPtr}, Project};
+use crate::project::obj::obj_list::ObjListTrait;
+
+// A keyed camera state on a camera layer
+#[derive(Object, Clone, ObjClone, ObjSerialize, UniqueTypeId)]
+pub struct CameraKey {
+    #[parent]
+    pub layer: ObjPtr<Layer>,
+    #[field]
+    pub time: i32,
+    #[field]
+    pub position: Vec2,
+    #[field]
+    pub zoom: f32,
+    // Measured in degrees
+    #[field]
+    pub rotation: f32,
+    // How the camera moves from this key to the next one
+    #[field]
+    pub easing: Easing
+}
+
+impl ChildObj for CameraKey {
+    type Parent = ObjPtr<Layer>;
+
+    fn parent(&self) -> Self::Parent {
+        self.layer
+    }
+
+    fn parent_mut(&mut self) -> &mut Self::Parent {
+        &mut self.layer
+    }
+
+    fn get_list_in_parent(project: &Project, parent: Self::Parent) -> Option<&Vec<ObjBox<Self>>> {
+        Some(&project.layers.get(parent)?.camera_keys)
+    }
+
+    fn get_list_in_parent_mut(project: &mut Project, parent: Self::Parent) -> Option<&mut Vec<ObjBox<Self>>> {
+        Some(&mut project.layers.get_mut(parent)?.camera_keys)
+    }
+
+}
+
+impl HasRootAsset for CameraKey {
+
+    type RootAsset = Graphic;
+    fn get_root_asset(project: &Project, key: ObjPtr<Self>) -> Option<ObjPtr<Self::RootAsset>> {
+        Layer::get_root_asset(project, project.camera_keys.get(key)?.layer)
+    }
+
+}
+
+impl Default for CameraKey {
+
+    fn default() -> Self {
+        Self {
+            layer: ObjPtr::null(),
+            time: 0,
+            position: Vec2::ZERO,
+            zoom: 1.0,
+            rotation: 0.0,
+            easing: Easing::Linear
+        }
+    }
+
+}
+
+impl CameraKey {
+
+    pub fn camera(&self) -> Camera {
+        Camera {
+            position: self.position,
+            zoom: self.zoom,
+            rotation: self.rotation
+        }
+    }
+
+}
+
+impl LayerKey for CameraKey {
+
+    type Value = Camera;
+
+    fn time(&self) -> i32 {
+        self.time
+    }
+
+    fn easing(&self) -> Easing {
+        self.easing
+    }
+
+    fn value(&self) -> Camera {
+        self.camera()
+    }
+
+    fn new_key(layer: ObjPtr<Layer>, time: i32, camera: Camera) -> Self {
+        CameraKey {
+            layer,
+            time,
+            position: camera.position,
+            zoom: camera.zoom,
+            rotation: camera.rotation,
+            easing: Easing::Linear
+        }
+    }
+
+    fn set_value(project: &mut Project, key: ObjPtr<Self>, camera: Camera) -> Vec<ObjAction> {
+        let mut acts = Vec::new();
+        acts.extend(Self::set_position(project, key, camera.position));
+        acts.extend(Self::set_zoom(project, key, camera.zoom));
+        acts.extend(Self::set_rotation(project, key, camera.rotation));
+        acts
+    }
+
+    fn lerp(a: &Camera, b: &Camera, t: f32) -> Camera {
+        a.lerp(b, t)
+    }
+
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Camera {
+    pub position: Vec2,
+    pub zoom: f32,
+    // Measured in degrees
+    pub rotation: f32
+}
+
+impl Default for Camera {
+
+    fn default() -> Self {
+        Self {
+            position: Vec2::ZERO,
+            zoom: 1.0,
+            rotation: 0.0
+        }
+    }
+
+}
+
+impl Camera {
+
+    pub fn lerp(&self, other: &Camera, t: f32) -> Camera {
+        Camera {
+            position: self.position.lerp(other.position, t),
+            // Zooming is interpolated geometrically so that it looks even
+            zoom: self.zoom * (other.zoom / self.zoom).powf(t),
+            rotation: self.rotation + (other.rotation - self.rotation) * t
+        }
+    }
+
+    // Half the height of the area seen by the camera, for a clip of the given height
+    pub fn cam_size(&self, h: u32) -> f32 {
+        (h as f32) / 2.0 / self.zoom.max(0.001)
+    }
+
+    // Maps the clip's rectangle onto the area seen by the camera
+    pub fn model(&self) -> Mat4 {
+        Mat4::from_translation(vec3(self.position.x, self.position.y, 0.0))
+            * Mat4::from_rotation_z(self.rotation.to_radians())
+            * Mat4::from_scale(vec3(1.0 / self.zoom.max(0.001), 1.0 / self.zoom.max(0.001), 1.0))
+    }
+
+    // The camera of a camera layer at some time, interpolated between the surrounding keys
+    pub fn at_time(project: &Project, layer: ObjPtr<Layer>, time: i32) -> Option<Camera> {
+        project.layers.get(layer)?;
+        Some(CameraKey::value_at(project, layer, time).unwrap_or_default())
+    }
+
+    // The camera a clip is exported through, if it has a camera layer
+    pub fn of_graphic(project: &Project, gfx: ObjPtr<Graphic>, time: i32) -> Option<Camera> {
+        Self::at_time(project, Graphic::camera_layer(project, gfx)?, time)
+    }
+
+}
+
+impl Graphic {
+
+    // Only the topmost shown camera layer directly inside the graphic is used
+    pub fn camera_layer(project: &Project, gfx: ObjPtr<Graphic>) -> Option<ObjPtr<Layer>> {
+        let gfx = project.graphics.get(gfx)?;
+        gfx.layers.iter().find(|layer| {
+            let layer = layer.get(project);
+            layer.kind == LayerKind::Camera && layer.show
+        }).map(|layer| layer.make_ptr())
+    }
+
+}
@@ -0,0 +1,44 @@
+
+use project_macros::{ObjClone, ObjSerialize};
+
+use super::{obj::ObjClone, Project};
+
+// How a keyed value moves from one key to the next
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, ObjClone, ObjSerialize)]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    // Keeps the key's value until the next key
+    Hold
+}
+
+impl Easing {
+
+    pub const ALL: [Easing; 5] = [Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut, Easing::Hold];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Easing::Linear => "Linear",
+            Easing::EaseIn => "Ease In",
+            Easing::EaseOut => "Ease Out",
+            Easing::EaseInOut => "Ease In Out",
+            Easing::Hold => "Hold",
+        }
+    }
+
+    // Maps the fraction of time passed between two keys to the fraction of the value change
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+            Easing::Hold => 0.0,
+        }
+    }
+
+}
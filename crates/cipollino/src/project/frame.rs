@@ -2,7 +2,7 @@
 use project_macros::{ObjClone, ObjSerialize, Object};
 use unique_type_id::UniqueTypeId;
 
-use super::{action::ObjAction, graphic::Graphic, layer::Layer, obj::{child_obj::{ChildObj, HasRootAsset}, Obj, ObjBox, ObjClone, ObjPtr, ObjSerialize}, stroke::Stroke, Project};
+use super::{action::ObjAction, easing::Easing, graphic::Graphic, layer::Layer, obj::{child_obj::{ChildObj, HasRootAsset}, Obj, ObjBox, ObjClone, ObjPtr}, stroke::Stroke, symbol::SymbolInstance, Project};
 use crate::project::obj::obj_list::ObjListTrait;
 
 #[derive(Object, Clone, ObjClone, ObjSerialize, UniqueTypeId)]
@@ -11,7 +11,14 @@ pub struct Frame {
     pub layer: ObjPtr<Layer>,
     #[field]
     pub time: i32,
-    pub strokes: Vec<ObjBox<Stroke>>
+    // Shows in-between drawings leading up to the next frame
+    #[field(default)]
+    pub tween: bool,
+    #[field(default)]
+    pub tween_easing: Easing,
+    pub strokes: Vec<ObjBox<Stroke>>,
+    // Drawn on top of the frame's strokes
+    pub symbols: Vec<ObjBox<SymbolInstance>>
 }
 
 impl ChildObj for Frame {
@@ -50,7 +57,10 @@ impl Default for Frame {
         Self {
             layer: ObjPtr::null(),
             time: 0,
-            strokes: Vec::new()
+            tween: false,
+            tween_easing: Easing::default(),
+            strokes: Vec::new(),
+            symbols: Vec::new()
         }
     }
 
@@ -1,10 +1,27 @@
 
+use glam::{vec2, Vec2};
 use project_macros::{ObjClone, ObjSerialize, Object};
 use unique_type_id::UniqueTypeId;
 
-use super::{action::ObjAction, folder::Folder, layer::Layer, obj::{asset::Asset, child_obj::{ChildObj, HasRootAsset}, Obj, ObjBox, ObjClone, ObjPtr, ObjSerialize}, AssetPtr, Project};
+use super::{action::ObjAction, folder::Folder, layer::Layer, obj::{asset::Asset, child_obj::{ChildObj, HasRootAsset}, Obj, ObjBox, ObjClone, ObjPtr}, AssetPtr, Project};
 use crate::project::obj::obj_list::ObjListTrait;
 
+// A line across the scene that tools can snap to
+#[derive(Clone, Copy, PartialEq, Debug, Default, ObjClone, ObjSerialize)]
+pub struct Guide {
+    // Vertical guides are at x = pos, horizontal ones at y = pos
+    pub vertical: bool,
+    pub pos: f32
+}
+
+impl Guide {
+
+    pub fn closest_point(&self, pt: Vec2) -> Vec2 {
+        if self.vertical { vec2(self.pos, pt.y) } else { vec2(pt.x, self.pos) }
+    }
+
+}
+
 #[derive(Object, Clone, ObjClone, ObjSerialize, UniqueTypeId)]
 #[asset]
 pub struct Graphic {
@@ -18,6 +35,8 @@ pub struct Graphic {
     pub w: u32,
     #[field]
     pub h: u32,
+    #[field(default)]
+    pub guides: Vec<Guide>,
     pub layers: Vec<ObjBox<Layer>>,
     #[parent]
     pub folder: ObjPtr<Folder>
@@ -32,6 +51,7 @@ impl Default for Graphic {
             clip: false,
             w: 1920,
             h: 1080,
+            guides: Vec::new(),
             layers: Vec::new(),
             folder: ObjPtr::null()
         }
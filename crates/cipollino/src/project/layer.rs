@@ -2,18 +2,18 @@
 use project_macros::{ObjClone, ObjSerialize, Object};
 use unique_type_id::UniqueTypeId;
 
-use super::{action::ObjAction, frame::Frame, graphic::Graphic, obj::{child_obj::{ChildObj, HasRootAsset}, obj_clone_impls::PrimitiveObjClone, DynObjPtr, Obj, ObjClone, ObjPtr, ObjSerialize}, sound_instance::SoundInstance, ObjBox, Project};
+use super::{action::ObjAction, camera::CameraKey, frame::Frame, graphic::Graphic, layer_transform::TransformKey, obj::{child_obj::{ChildObj, HasRootAsset}, obj_clone_impls::PrimitiveObjClone, DynObjPtr, Obj, ObjClone, ObjPtr}, sound_instance::SoundInstance, ObjBox, Project};
 use crate::project::obj::obj_list::ObjListTrait;
 
-#[derive(Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, ObjClone, ObjSerialize)]
 pub enum LayerKind {
+    #[default]
     Animation,
     Audio,
-    Group
+    Group,
+    Camera
 }
 
-impl PrimitiveObjClone for LayerKind {}
-
 #[derive(Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq, Debug)]
 pub enum LayerParent {
     Graphic(ObjPtr<Graphic>),
@@ -22,7 +22,15 @@ pub enum LayerParent {
 
 impl PrimitiveObjClone for LayerParent {}
 
-#[derive(Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq, Debug)]
+impl Default for LayerParent {
+
+    fn default() -> Self {
+        Self::Graphic(ObjPtr::null())
+    }
+
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ObjClone, ObjSerialize)]
 pub enum BlendingMode {
     // Normal
     Normal,
@@ -66,7 +74,6 @@ impl BlendingMode {
 
 }
 
-impl PrimitiveObjClone for BlendingMode {}
 
 #[derive(Object, Clone, ObjClone, ObjSerialize, UniqueTypeId)]
 pub struct Layer {
@@ -83,14 +90,19 @@ pub struct Layer {
     #[field]
     pub kind: LayerKind,
 
-    #[field]
+    #[field(default = 1.0)]
     pub alpha: f32,
-    #[field]
+    #[field(default = BlendingMode::Normal)]
     pub blending: BlendingMode,
+    // Bucket fills can be set to only use the strokes on these layers as boundaries
+    #[field(default = false)]
+    pub fill_reference: bool,
 
     pub frames: Vec<ObjBox<Frame>>,
     pub sound_instances: Vec<ObjBox<SoundInstance>>,
-    pub layers: Vec<ObjBox<Layer>>
+    pub layers: Vec<ObjBox<Layer>>,
+    pub camera_keys: Vec<ObjBox<CameraKey>>,
+    pub transform_keys: Vec<ObjBox<TransformKey>>
 }
 
 impl Layer {
@@ -156,9 +168,10 @@ impl Layer {
         }
     }
 
+    // Refuses to move a layer into itself or one of its own sublayers
     pub fn transfer(project: &mut Project, layer: ObjPtr<Self>, new_parent: LayerParent) -> Option<ObjAction> {
         if let LayerParent::Layer(new_layer) = new_parent {
-            if Self::inside(project, new_layer, LayerParent::Layer(layer)) {
+            if Self::inside(project, layer, LayerParent::Layer(new_layer)) {
                 return None;
             }
         }
@@ -235,7 +248,7 @@ impl Default for Layer {
 
     fn default() -> Self {
         Self {
-            parent: LayerParent::Graphic(ObjPtr::null()),
+            parent: LayerParent::default(),
             name: "Layer".to_owned(),
             show: true,
             lock: false,
@@ -243,8 +256,11 @@ impl Default for Layer {
             kind: LayerKind::Animation,
             alpha: 1.0,
             blending: BlendingMode::Normal,
+            fill_reference: false,
             frames: Vec::new(),
             sound_instances: Vec::new(),
+            camera_keys: Vec::new(),
+            transform_keys: Vec::new(),
             layers: Vec::new()
         }
     }
@@ -0,0 +1,57 @@
+
+use super::{action::ObjAction, easing::Easing, layer::Layer, obj::{child_obj::ChildObj, ObjPtr}, Project};
+
+/*
+    Keys placed along a layer's timeline, holding a value that is interpolated between them.
+    Camera keys and layer transform keys share how they're found, keyed and interpolated through this.
+*/
+pub trait LayerKey: ChildObj<Parent = ObjPtr<Layer>> {
+    type Value: Copy;
+
+    fn time(&self) -> i32;
+    // How the value changes from this key to the next one
+    fn easing(&self) -> Easing;
+    fn value(&self) -> Self::Value;
+    fn new_key(layer: ObjPtr<Layer>, time: i32, value: Self::Value) -> Self;
+    // Sets every field of the key that makes up the value
+    fn set_value(project: &mut Project, key: ObjPtr<Self>, value: Self::Value) -> Vec<ObjAction>;
+    fn lerp(a: &Self::Value, b: &Self::Value, t: f32) -> Self::Value;
+
+    fn get_key_exactly_at(project: &Project, layer: ObjPtr<Layer>, time: i32) -> Option<ObjPtr<Self>> {
+        Self::get_list_in_parent(project, layer)?.iter().find(|key| key.get(project).time() == time).map(|key| key.make_ptr())
+    }
+
+    // Keys the value at some time, adding a key if there isn't one there yet
+    fn set_at(project: &mut Project, layer: ObjPtr<Layer>, time: i32, value: Self::Value) -> Option<Vec<ObjAction>> {
+        if let Some(key) = Self::get_key_exactly_at(project, layer, time) {
+            Some(Self::set_value(project, key, value))
+        } else {
+            let (_, act) = Self::add(project, layer, Self::new_key(layer, time, value))?;
+            Some(vec![act])
+        }
+    }
+
+    // The value at some time, interpolated between the surrounding keys. None if the layer has no keys.
+    fn value_at(project: &Project, layer: ObjPtr<Layer>, time: i32) -> Option<Self::Value> {
+        let mut before: Option<&Self> = None;
+        let mut after: Option<&Self> = None;
+        for key in Self::get_list_in_parent(project, layer)? {
+            let key = key.get(project);
+            if key.time() <= time && before.map(|before| key.time() > before.time()).unwrap_or(true) {
+                before = Some(key);
+            }
+            if key.time() > time && after.map(|after| key.time() < after.time()).unwrap_or(true) {
+                after = Some(key);
+            }
+        }
+        match (before, after) {
+            (Some(before), Some(after)) => {
+                let t = ((time - before.time()) as f32) / ((after.time() - before.time()) as f32);
+                Some(Self::lerp(&before.value(), &after.value(), before.easing().apply(t)))
+            },
+            (Some(key), None) | (None, Some(key)) => Some(key.value()),
+            (None, None) => None
+        }
+    }
+
+}
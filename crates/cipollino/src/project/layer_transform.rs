@@ -0,0 +1,198 @@
+use glam::{vec3, Mat4, Vec2};
+use project_macros::{ObjClone, ObjSerialize, Object};
+use unique_type_id::UniqueTypeId;
+
+use super::{action::ObjAction, easing::Easing, graphic::Graphic, layer::{Layer, LayerParent}, layer_key::LayerKey, obj::{child_obj::{ChildObj, HasRootAsset}, Obj, ObjBox, ObjClone, ObjPtr}, Project};
+use crate::project::obj::obj_list::ObjListTrait;
+
+// A keyed transform of everything in a layer
+#[derive(Object, Clone, ObjClone, ObjSerialize, UniqueTypeId)]
+pub struct TransformKey {
+    #[parent]
+    pub layer: ObjPtr<Layer>,
+    #[field]
+    pub time: i32,
+    #[field]
+    pub position: Vec2,
+    // Measured in degrees
+    #[field]
+    pub rotation: f32,
+    #[field]
+    pub scale: Vec2,
+    // The point the layer is rotated and scaled around
+    #[field]
+    pub pivot: Vec2,
+    // Multiplied with the layer's alpha
+    #[field]
+    pub opacity: f32,
+    // How the layer moves from this key to the next one
+    #[field]
+    pub easing: Easing
+}
+
+impl ChildObj for TransformKey {
+    type Parent = ObjPtr<Layer>;
+
+    fn parent(&self) -> Self::Parent {
+        self.layer
+    }
+
+    fn parent_mut(&mut self) -> &mut Self::Parent {
+        &mut self.layer
+    }
+
+    fn get_list_in_parent(project: &Project, parent: Self::Parent) -> Option<&Vec<ObjBox<Self>>> {
+        Some(&project.layers.get(parent)?.transform_keys)
+    }
+
+    fn get_list_in_parent_mut(project: &mut Project, parent: Self::Parent) -> Option<&mut Vec<ObjBox<Self>>> {
+        Some(&mut project.layers.get_mut(parent)?.transform_keys)
+    }
+
+}
+
+impl HasRootAsset for TransformKey {
+
+    type RootAsset = Graphic;
+    fn get_root_asset(project: &Project, key: ObjPtr<Self>) -> Option<ObjPtr<Self::RootAsset>> {
+        Layer::get_root_asset(project, project.transform_keys.get(key)?.layer)
+    }
+
+}
+
+impl Default for TransformKey {
+
+    fn default() -> Self {
+        Self {
+            layer: ObjPtr::null(),
+            time: 0,
+            position: Vec2::ZERO,
+            rotation: 0.0,
+            scale: Vec2::ONE,
+            pivot: Vec2::ZERO,
+            opacity: 1.0,
+            easing: Easing::Linear
+        }
+    }
+
+}
+
+impl TransformKey {
+
+    pub fn transform(&self) -> LayerTransform {
+        LayerTransform {
+            position: self.position,
+            rotation: self.rotation,
+            scale: self.scale,
+            pivot: self.pivot,
+            opacity: self.opacity
+        }
+    }
+
+}
+
+impl LayerKey for TransformKey {
+
+    type Value = LayerTransform;
+
+    fn time(&self) -> i32 {
+        self.time
+    }
+
+    fn easing(&self) -> Easing {
+        self.easing
+    }
+
+    fn value(&self) -> LayerTransform {
+        self.transform()
+    }
+
+    fn new_key(layer: ObjPtr<Layer>, time: i32, transform: LayerTransform) -> Self {
+        TransformKey {
+            layer,
+            time,
+            position: transform.position,
+            rotation: transform.rotation,
+            scale: transform.scale,
+            pivot: transform.pivot,
+            opacity: transform.opacity,
+            easing: Easing::Linear
+        }
+    }
+
+    fn set_value(project: &mut Project, key: ObjPtr<Self>, transform: LayerTransform) -> Vec<ObjAction> {
+        let mut acts = Vec::new();
+        acts.extend(Self::set_position(project, key, transform.position));
+        acts.extend(Self::set_rotation(project, key, transform.rotation));
+        acts.extend(Self::set_scale(project, key, transform.scale));
+        acts.extend(Self::set_pivot(project, key, transform.pivot));
+        acts.extend(Self::set_opacity(project, key, transform.opacity));
+        acts
+    }
+
+    fn lerp(a: &LayerTransform, b: &LayerTransform, t: f32) -> LayerTransform {
+        a.lerp(b, t)
+    }
+
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LayerTransform {
+    pub position: Vec2,
+    // Measured in degrees
+    pub rotation: f32,
+    pub scale: Vec2,
+    pub pivot: Vec2,
+    pub opacity: f32
+}
+
+impl Default for LayerTransform {
+
+    fn default() -> Self {
+        Self {
+            position: Vec2::ZERO,
+            rotation: 0.0,
+            scale: Vec2::ONE,
+            pivot: Vec2::ZERO,
+            opacity: 1.0
+        }
+    }
+
+}
+
+impl LayerTransform {
+
+    pub fn lerp(&self, other: &LayerTransform, t: f32) -> LayerTransform {
+        LayerTransform {
+            position: self.position.lerp(other.position, t),
+            rotation: self.rotation + (other.rotation - self.rotation) * t,
+            scale: self.scale.lerp(other.scale, t),
+            pivot: self.pivot.lerp(other.pivot, t),
+            opacity: self.opacity + (other.opacity - self.opacity) * t
+        }
+    }
+
+    pub fn matrix(&self) -> Mat4 {
+        Mat4::from_translation(vec3(self.position.x + self.pivot.x, self.position.y + self.pivot.y, 0.0))
+            * Mat4::from_rotation_z(self.rotation.to_radians())
+            * Mat4::from_scale(vec3(self.scale.x, self.scale.y, 1.0))
+            * Mat4::from_translation(vec3(-self.pivot.x, -self.pivot.y, 0.0))
+    }
+
+    // The layer's transform at some time, interpolated between the surrounding keys. Layers without keys aren't transformed.
+    pub fn at_time(project: &Project, layer: ObjPtr<Layer>, time: i32) -> Option<LayerTransform> {
+        TransformKey::value_at(project, layer, time)
+    }
+
+    // The layer's transform combined with the transforms of the groups it's inside of
+    pub fn world_matrix(project: &Project, layer_ptr: ObjPtr<Layer>, time: i32) -> Mat4 {
+        let Some(layer) = project.layers.get(layer_ptr) else { return Mat4::IDENTITY; };
+        let parent = match layer.parent {
+            LayerParent::Graphic(_) => Mat4::IDENTITY,
+            LayerParent::Layer(parent) => Self::world_matrix(project, parent, time),
+        };
+        let local = Self::at_time(project, layer_ptr, time).map(|transform| transform.matrix()).unwrap_or(Mat4::IDENTITY);
+        parent * local
+    }
+
+}
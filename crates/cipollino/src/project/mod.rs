@@ -2,6 +2,10 @@
 pub mod folder;
 pub mod graphic;
 pub mod layer;
+pub mod camera;
+pub mod layer_transform;
+pub mod layer_key;
+pub mod easing;
 pub mod frame;
 pub mod stroke;
 pub mod obj;
@@ -9,15 +13,21 @@ pub mod action;
 pub mod saveload;
 pub mod palette;
 pub mod sound_instance;
+pub mod symbol;
+pub mod tween;
 pub mod resource;
+pub mod validate;
 
-use std::{collections::HashSet, path::PathBuf};
+#[cfg(test)]
+pub mod tests;
+
+use std::path::PathBuf;
 
 use serde_json::json;
 
 use crate::util::fs::write_json_file;
 
-use self::{folder::Folder, frame::Frame, graphic::Graphic, layer::Layer, obj::{asset_list::AssetList, child_obj::ChildObj, obj_list::{ObjList, ObjListTrait}, ObjBox, ObjPtr}, palette::{Palette, PaletteColor}, resource::{audio::AudioFile, ResPtr, ResourceList}, sound_instance::SoundInstance, stroke::Stroke};
+use self::{camera::CameraKey, folder::Folder, frame::Frame, graphic::Graphic, layer::Layer, layer_transform::TransformKey, obj::{asset_list::AssetList, child_obj::ChildObj, obj_list::{ObjList, ObjListTrait}, ObjBox, ObjPtr}, palette::{Palette, PaletteColor}, resource::{audio::AudioFile, ResPtr, ResourceList}, sound_instance::SoundInstance, stroke::Stroke, symbol::SymbolInstance};
 
 pub struct Project {
     pub fps: f32,
@@ -31,16 +41,16 @@ pub struct Project {
     pub palettes: AssetList<Palette>,
     pub palette_colors: ObjList<PaletteColor>,
     pub sound_instances: ObjList<SoundInstance>,
+    pub symbol_instances: ObjList<SymbolInstance>,
+    pub camera_keys: ObjList<CameraKey>,
+    pub transform_keys: ObjList<TransformKey>,
 
     pub audio_files: ResourceList<AudioFile>, 
 
     pub root_folder: ObjBox<Folder>,
 
     // Path to the proj.cip file at the root of the project folder
-    pub save_path: PathBuf,
-
-    // List of strokes whose mesh needs to be updated 
-    pub remeshes_needed: HashSet<ObjPtr<Stroke>>
+    pub save_path: PathBuf
 }
 
 impl Project {
@@ -59,6 +69,7 @@ impl Project {
             clip: true,
             w: 1920,
             h: 1080,
+            guides: Vec::new(),
             layers: Vec::new(),
             folder: folder,
         }).unwrap();
@@ -71,6 +82,8 @@ impl Project {
             kind: layer::LayerKind::Animation,
             frames: Vec::new(),
             sound_instances: Vec::new(),
+            camera_keys: Vec::new(),
+            transform_keys: Vec::new(),
             layers: Vec::new(),
             ..Layer::default()
         }).unwrap();
@@ -92,19 +105,20 @@ impl Project {
             palettes: AssetList::new(),
             palette_colors: ObjList::new(),
             sound_instances: ObjList::new(),
+            symbol_instances: ObjList::new(),
+            camera_keys: ObjList::new(),
+            transform_keys: ObjList::new(),
 
             audio_files: ResourceList::new(),
 
             root_folder: root,
 
-            save_path: path,
-
-            remeshes_needed: HashSet::new()
+            save_path: path
         }
     }
 
     pub fn mutated(&self) -> bool {
-        self.folders.mutated() || self.graphics.mutated() || self.layers.mutated() || self.frames.mutated() || self.strokes.mutated() || self.palettes.mutated() || self.palette_colors.mutated() || self.sound_instances.mutated()
+        self.folders.mutated() || self.graphics.mutated() || self.layers.mutated() || self.frames.mutated() || self.strokes.mutated() || self.palettes.mutated() || self.palette_colors.mutated() || self.sound_instances.mutated() || self.symbol_instances.mutated() || self.camera_keys.mutated() || self.transform_keys.mutated()
     }
 
     pub fn garbage_collect_objs(&mut self) {
@@ -114,6 +128,9 @@ impl Project {
         self.frames.garbage_collect_objs();
         self.strokes.garbage_collect_objs();
         self.sound_instances.garbage_collect_objs();
+        self.symbol_instances.garbage_collect_objs();
+        self.camera_keys.garbage_collect_objs();
+        self.transform_keys.garbage_collect_objs();
         self.palettes.garbage_collect_objs();
         self.palette_colors.garbage_collect_objs();
     }
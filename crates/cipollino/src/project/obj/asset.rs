@@ -3,7 +3,7 @@ use std::{path::PathBuf, str::FromStr};
 
 use crate::{project::{action::ObjAction, folder::Folder, AssetPtr, Project}, util};
 
-use super::{asset_list::AssetList, child_obj::ChildObj, Obj, ObjBox, ObjPtr};
+use super::{asset_list::AssetList, child_obj::ChildObj, obj_event::ObjEvent, Obj, ObjBox, ObjPtr};
 
 use crate::project::obj::obj_list::ObjListTrait;
 
@@ -79,6 +79,7 @@ pub trait Asset : Obj<ListType = AssetList<Self>> + ChildObj<Parent = ObjPtr<Fol
             let path = obj.file_path(proj);
             let obj = Self::get_list_mut(proj).get_mut(obj_ptr).unwrap();
             *obj.name_mut() = new_name.clone(); 
+            Self::get_list_mut(proj).push_event(ObjEvent::Modified(obj_ptr, "name"));
             if let Some(path) = path {
                 util::fs::remove(&path);
             }
@@ -89,6 +90,7 @@ pub trait Asset : Obj<ListType = AssetList<Self>> + ChildObj<Parent = ObjPtr<Fol
             let path = obj.file_path(proj);
             let obj = Self::get_list_mut(proj).get_mut(obj_ptr).unwrap();
             *obj.name_mut() = init_name.clone(); 
+            Self::get_list_mut(proj).push_event(ObjEvent::Modified(obj_ptr, "name"));
             if let Some(path) = path {
                 util::fs::remove(&path);
             }
@@ -122,10 +124,12 @@ pub trait Asset : Obj<ListType = AssetList<Self>> + ChildObj<Parent = ObjPtr<Fol
         Some(vec![ObjAction::new(move |proj| {
             let obj = Self::get_list_mut(proj).get_mut(asset).unwrap();
             *obj.name_mut() = new_name.clone();
+            Self::get_list_mut(proj).push_event(ObjEvent::Modified(asset, "name"));
             let _ = std::fs::rename(init_path.clone(), new_path.clone());
         }, |_| {}), transfer_act, ObjAction::new(|_| {}, move |proj| {
             let obj = Self::get_list_mut(proj).get_mut(asset).unwrap();
             *obj.name_mut() = init_name.clone();
+            Self::get_list_mut(proj).push_event(ObjEvent::Modified(asset, "name"));
             let _ = std::fs::rename(new_path_1.clone(), init_path_1.clone());
         })])
     }
@@ -5,7 +5,7 @@ use bson::Bson;
 
 use crate::project::{folder::Folder, saveload::{asset_file::AssetFile, load::LoadingMetadata}, Project};
 
-use super::{asset::Asset, obj_list::{ObjList, ObjListTrait}, ObjBox, ObjPtr};
+use super::{asset::Asset, obj_event::ObjEventBus, obj_list::{ObjList, ObjListTrait}, ObjBox, ObjPtr};
 
 pub struct AssetList<T: Asset> {
     objs: ObjList<T>,
@@ -116,4 +116,8 @@ impl<T: Asset> ObjListTrait for AssetList<T> {
         self.objs.get_modified()
     }
 
+    fn event_bus(&mut self) -> &mut ObjEventBus<Self::ObjType> {
+        self.objs.event_bus()
+    }
+
 }
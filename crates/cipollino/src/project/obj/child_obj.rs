@@ -2,7 +2,7 @@ use std::sync::{Arc, Mutex};
 
 use crate::project::{action::ObjAction, Project};
 
-use super::{asset::Asset, asset_list::AssetList, DynObjPtr, Obj, ObjBox, ObjPtr, ObjSerialize, ToRawData};
+use super::{asset::Asset, asset_list::AssetList, obj_event::ObjEvent, DynObjPtr, Obj, ObjBox, ObjPtr, ObjSerialize, ToRawData};
 
 use crate::project::obj::obj_list::ObjListTrait;
 
@@ -107,6 +107,7 @@ pub trait ChildObj: Obj + 'static + ObjSerialize + ToRawData {
             Self::get_list_in_parent_mut(proj, new_parent).unwrap().push(obj_box);
             let obj = Self::get_list_mut(proj).get_mut(obj_ptr).unwrap();
             *obj.parent_mut() = new_parent;
+            Self::get_list_mut(proj).push_event(ObjEvent::Transferred(obj_ptr));
         };
 
         let undo = move |proj: &'_ mut Project| {
@@ -114,6 +115,7 @@ pub trait ChildObj: Obj + 'static + ObjSerialize + ToRawData {
             Self::get_list_in_parent_mut(proj, init_parent).unwrap().insert(idx, obj_box);
             let obj = Self::get_list_mut(proj).get_mut(obj_ptr).unwrap();
             *obj.parent_mut() = init_parent;
+            Self::get_list_mut(proj).push_event(ObjEvent::Transferred(obj_ptr));
         };
 
         redo(project);
@@ -137,6 +139,7 @@ pub trait ChildObj: Obj + 'static + ObjSerialize + ToRawData {
             let sibling_list = Self::get_list_in_parent_mut(proj, parent).unwrap();
             let obj = sibling_list.remove(old_idx);
             sibling_list.insert(new_idx, obj);
+            Self::get_list_mut(proj).push_event(ObjEvent::Reordered(obj_ptr));
         };
 
         let undo = move |proj: &'_ mut Project| {
@@ -145,6 +148,7 @@ pub trait ChildObj: Obj + 'static + ObjSerialize + ToRawData {
             let sibling_list = Self::get_list_in_parent_mut(proj, parent).unwrap();
             let obj = sibling_list.remove(new_idx);
             sibling_list.insert(old_idx, obj);
+            Self::get_list_mut(proj).push_event(ObjEvent::Reordered(obj_ptr));
         };
 
         redo(project);
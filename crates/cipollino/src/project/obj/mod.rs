@@ -13,6 +13,7 @@ pub mod child_obj;
 pub mod obj_clone_impls;
 pub mod asset;
 pub mod asset_list;
+pub mod obj_event;
 
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -108,6 +108,7 @@ impl PrimitiveObjClone for String {}
 impl PrimitiveObjClone for glam::Vec2 {}
 impl PrimitiveObjClone for glam::Vec3 {}
 impl PrimitiveObjClone for glam::Vec4 {}
+impl PrimitiveObjClone for glam::Mat4 {}
 
 impl<T: Obj> ObjClone for ObjPtr<T> {}
 
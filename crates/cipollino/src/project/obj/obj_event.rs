@@ -0,0 +1,117 @@
+use std::sync::{Arc, Mutex, Weak};
+
+use super::{obj_list::ObjListTrait, Obj, ObjPtr};
+
+pub enum ObjEvent<T: Obj> {
+    Created(ObjPtr<T>),
+    // The name of the field that was changed
+    Modified(ObjPtr<T>, &'static str),
+    Transferred(ObjPtr<T>),
+    Reordered(ObjPtr<T>),
+    // Sent once the object is actually garbage collected
+    Dropped(ObjPtr<T>)
+}
+
+impl<T: Obj> ObjEvent<T> {
+
+    pub fn ptr(&self) -> ObjPtr<T> {
+        match self {
+            ObjEvent::Created(ptr) => *ptr,
+            ObjEvent::Modified(ptr, _) => *ptr,
+            ObjEvent::Transferred(ptr) => *ptr,
+            ObjEvent::Reordered(ptr) => *ptr,
+            ObjEvent::Dropped(ptr) => *ptr,
+        }
+    }
+
+}
+
+impl<T: Obj> Clone for ObjEvent<T> {
+
+    fn clone(&self) -> Self {
+        *self
+    }
+
+}
+
+impl<T: Obj> Copy for ObjEvent<T> {}
+
+impl<T: Obj> std::fmt::Debug for ObjEvent<T> {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjEvent::Created(ptr) => f.debug_tuple("Created").field(ptr).finish(),
+            ObjEvent::Modified(ptr, field) => f.debug_tuple("Modified").field(ptr).field(field).finish(),
+            ObjEvent::Transferred(ptr) => f.debug_tuple("Transferred").field(ptr).finish(),
+            ObjEvent::Reordered(ptr) => f.debug_tuple("Reordered").field(ptr).finish(),
+            ObjEvent::Dropped(ptr) => f.debug_tuple("Dropped").field(ptr).finish(),
+        }
+    }
+
+}
+
+/*
+    Every ObjList owns a bus. Systems that cache data derived from objects(mesh caches, audio, etc.)
+    subscribe to it and drain the events at their own pace instead of polling the whole list.
+*/
+pub struct ObjEventBus<T: Obj> {
+    subscribers: Vec<Weak<Mutex<Vec<ObjEvent<T>>>>>
+}
+
+impl<T: Obj> ObjEventBus<T> {
+
+    pub fn new() -> Self {
+        Self {
+            subscribers: Vec::new()
+        }
+    }
+
+    pub fn push(&mut self, event: ObjEvent<T>) {
+        self.subscribers.retain(|subscriber| {
+            if let Some(subscriber) = subscriber.upgrade() {
+                subscriber.lock().unwrap().push(event);
+                true
+            } else {
+                false
+            }
+        });
+    }
+
+    pub fn subscribe(&mut self) -> ObjEventReceiver<T> {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        self.subscribers.push(Arc::downgrade(&events));
+        ObjEventReceiver {
+            events
+        }
+    }
+
+    pub fn is_subscribed(&self, receiver: &ObjEventReceiver<T>) -> bool {
+        let receiver = Arc::downgrade(&receiver.events);
+        self.subscribers.iter().any(|subscriber| subscriber.ptr_eq(&receiver))
+    }
+
+}
+
+pub struct ObjEventReceiver<T: Obj> {
+    events: Arc<Mutex<Vec<ObjEvent<T>>>>
+}
+
+impl<T: Obj> ObjEventReceiver<T> {
+
+    pub fn drain(&self) -> Vec<ObjEvent<T>> {
+        std::mem::replace(&mut *self.events.lock().unwrap(), Vec::new())
+    }
+
+    // Drains the events sent by the list. If the receiver is not subscribed to the list(because the project was just opened or replaced),
+    // it subscribes and returns None, meaning any data derived from the list needs to be rebuilt from scratch.
+    pub fn poll<L: ObjListTrait<ObjType = T>>(receiver: &mut Option<Self>, list: &mut L) -> Option<Vec<ObjEvent<T>>> {
+        if let Some(receiver) = receiver {
+            if list.event_bus().is_subscribed(receiver) {
+                return Some(receiver.drain());
+            }
+        }
+        *receiver = Some(list.event_bus().subscribe());
+        None
+    }
+
+}
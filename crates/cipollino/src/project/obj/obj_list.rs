@@ -1,6 +1,6 @@
 
 use std::{cell::RefCell, collections::{HashMap, HashSet}, marker::PhantomData, sync::{Arc, Mutex}};
-use super::{Obj, ObjBox, ObjPtr};
+use super::{obj_event::{ObjEvent, ObjEventBus}, Obj, ObjBox, ObjPtr};
 
 pub trait ObjListTrait {
 
@@ -28,6 +28,12 @@ pub trait ObjListTrait {
     fn get_created(&self) -> &HashSet<ObjPtr<Self::ObjType>>;
     fn get_modified(&self) -> &HashSet<ObjPtr<Self::ObjType>>;
 
+    fn event_bus(&mut self) -> &mut ObjEventBus<Self::ObjType>;
+
+    fn push_event(&mut self, event: ObjEvent<Self::ObjType>) {
+        self.event_bus().push(event);
+    }
+
     fn mutated(&self) -> bool {
         !self.get_dropped().lock().unwrap().is_empty() || !self.get_modified().is_empty()
     }
@@ -49,7 +55,9 @@ pub struct ObjList<T: Obj> {
     pub curr_key: u64,
     
     // First page pointers of every object in their respective asset files(see saveload::asset_file)
-    pub obj_file_ptrs: RefCell<HashMap<ObjPtr<T>, u64>>
+    pub obj_file_ptrs: RefCell<HashMap<ObjPtr<T>, u64>>,
+
+    pub events: ObjEventBus<T>
 }
 
 impl<T: Obj> ObjList<T> {
@@ -61,7 +69,8 @@ impl<T: Obj> ObjList<T> {
             created: HashSet::new(),
             modified: HashSet::new(),
             curr_key: 1,
-            obj_file_ptrs: RefCell::new(HashMap::new())
+            obj_file_ptrs: RefCell::new(HashMap::new()),
+            events: ObjEventBus::new()
         }
     }
 
@@ -74,7 +83,9 @@ impl<T: Obj> ObjList<T> {
             drop(dropped);
 
             for key in dropped_clone {
-                self.objs.remove(&key);
+                if self.objs.remove(&key).is_some() {
+                    self.events.push(ObjEvent::Dropped(ObjPtr::from_key(key)));
+                }
             }
 
             if self.dropped.lock().unwrap().is_empty() {
@@ -114,6 +125,7 @@ impl<T: Obj> ObjListTrait for ObjList<T> {
         };
         self.created.insert(ptr);
         self.modified.insert(ptr);
+        self.events.push(ObjEvent::Created(ptr));
         ObjBox {
             ptr,
             dropped: self.dropped.clone(),
@@ -124,6 +136,7 @@ impl<T: Obj> ObjListTrait for ObjList<T> {
         self.objs.insert(ptr.key, obj);
         self.created.insert(ptr);
         self.modified.insert(ptr);
+        self.events.push(ObjEvent::Created(ptr));
         ObjBox {
             ptr,
             dropped: self.dropped.clone(),
@@ -156,4 +169,8 @@ impl<T: Obj> ObjListTrait for ObjList<T> {
         &self.modified
     }
 
+    fn event_bus(&mut self) -> &mut ObjEventBus<Self::ObjType> {
+        &mut self.events
+    }
+
 }
@@ -191,6 +191,14 @@ impl<T: ResourceType> ResPtr<T> {
 
 }
 
+impl<T: ResourceType> Default for ResPtr<T> {
+
+    fn default() -> Self {
+        Self::null()
+    }
+
+}
+
 impl<T: ResourceType> Hash for ResPtr<T> {
 
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
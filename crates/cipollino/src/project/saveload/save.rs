@@ -6,7 +6,7 @@ use crate::{project::{graphic::Graphic, obj::{child_obj::HasRootAsset, ObjBox}},
 
 use super::asset_file::AssetFile;
 
-use super::super::{folder::Folder, frame::Frame, layer::Layer, obj::{asset::Asset, Obj, ObjPtr, ObjSerialize}, palette::{Palette, PaletteColor}, sound_instance::SoundInstance, stroke::Stroke, Project};
+use super::super::{camera::CameraKey, folder::Folder, frame::Frame, layer::Layer, layer_transform::TransformKey, obj::{asset::Asset, Obj, ObjPtr, ObjSerialize}, palette::{Palette, PaletteColor}, sound_instance::SoundInstance, stroke::Stroke, symbol::SymbolInstance, Project};
 use super::super::obj::obj_list::ObjListTrait;
 
 impl Project {
@@ -22,6 +22,9 @@ impl Project {
 
         self.save_obj_list_modifications::<Stroke, F>(log_error);
         self.save_obj_list_modifications::<SoundInstance, F>(log_error);
+        self.save_obj_list_modifications::<SymbolInstance, F>(log_error);
+        self.save_obj_list_modifications::<CameraKey, F>(log_error);
+        self.save_obj_list_modifications::<TransformKey, F>(log_error);
         self.save_obj_list_modifications::<Frame, F>(log_error);
         self.save_obj_list_modifications::<Layer, F>(log_error);
         self.save_obj_list_modifications::<Graphic, F>(log_error);
@@ -1,24 +1,60 @@
 
-use glam::{Vec2, Mat4, vec3, vec2};
+use glam::{Vec2, Vec3, Mat4, vec3, vec2};
 use project_macros::{ObjClone, ObjSerialize, Object};
 use unique_type_id::UniqueTypeId;
 
-use crate::util::curve::BezierSegment;
+use crate::util::{curve::{fit_curve, BezierSegment}, polygon::{polygon_boolean, BooleanOp, Polygon}};
 
-use super::{action::ObjAction, frame::Frame, graphic::Graphic, obj::{child_obj::{ChildObj, HasRootAsset}, DynObjPtr, Obj, ObjBox, ObjClone, ObjPtr, ObjSerialize, ToRawData}, palette::PaletteColor, saveload::{asset_file::AssetFile, load::LoadingMetadata}, Project};
+use super::{action::{Action, ObjAction}, frame::Frame, graphic::Graphic, obj::{child_obj::{ChildObj, HasRootAsset}, obj_event::ObjEvent, Obj, ObjBox, ObjClone, ObjPtr}, palette::PaletteColor, Project};
 use crate::project::obj::obj_list::ObjListTrait;
 
-#[derive(Clone, Copy, ObjClone, Default, ObjSerialize)]
+#[derive(Clone, Copy, ObjClone, ObjSerialize)]
 pub struct StrokePoint {
     pub a: Vec2,
     pub pt: Vec2,
-    pub b: Vec2
+    pub b: Vec2,
+    // Scales the stroke's radius at this point
+    #[field(default = 1.0)]
+    pub pressure: f32
 }
 
-#[derive(Clone, Copy, Debug)]
+impl Default for StrokePoint {
+
+    fn default() -> Self {
+        Self {
+            a: Vec2::ZERO,
+            pt: Vec2::ZERO,
+            b: Vec2::ZERO,
+            pressure: 1.0
+        }
+    }
+
+}
+
+// Both variants are untagged to keep the layout strokes were saved with before the derive
+#[derive(Clone, Copy, Debug, ObjClone, ObjSerialize)]
 pub enum StrokeColor {
+    #[serialize(untagged)]
     Color(glam::Vec4),
-    Palette(ObjPtr<PaletteColor>, glam::Vec4) 
+    #[serialize(untagged)]
+    Palette {
+        color: ObjPtr<PaletteColor>,
+        // Shown if the palette color is missing
+        #[field(default = black())]
+        backup: glam::Vec4
+    }
+}
+
+fn black() -> glam::Vec4 {
+    glam::vec4(0.0, 0.0, 0.0, 1.0)
+}
+
+impl Default for StrokeColor {
+
+    fn default() -> Self {
+        Self::Color(black())
+    }
+
 }
 
 impl StrokeColor {
@@ -26,79 +62,124 @@ impl StrokeColor {
     pub fn get_color(&self, project: &Project) -> glam::Vec4 {
         match self {
             Self::Color(color) => *color,
-            Self::Palette(ptr, backup_color) => {
-                let color = project.palette_colors.get(*ptr).map(|color| color.color);
-                color.unwrap_or(*backup_color)
+            Self::Palette { color, backup } => {
+                let color = project.palette_colors.get(*color).map(|color| color.color);
+                color.unwrap_or(*backup)
             }
         }
     }
+
+    // Palette colours match by the palette entry they use rather than by value
+    pub fn same_as(&self, other: &StrokeColor) -> bool {
+        match (self, other) {
+            (Self::Color(a), Self::Color(b)) => a == b,
+            (Self::Palette { color: a, .. }, Self::Palette { color: b, .. }) => a == b,
+            _ => false
+        }
+    }
     
 }
 
-impl ObjClone for StrokeColor {} 
-
-impl ObjSerialize for StrokeColor {
+pub fn iter_bezier_segments<'a>(pts: &'a Vec<StrokePoint>) -> impl Iterator<Item = BezierSegment<Vec2>> + 'a {
+    pts.windows(2).map(|arr| BezierSegment {
+        p0: arr[0].pt,
+        b0: arr[0].b,
+        a1: arr[1].a,
+        p1: arr[1].pt
+    })
+}
 
-    fn obj_serialize(&self, project: &Project, asset_file: &mut AssetFile) -> bson::Bson {
-        match self {
-            Self::Color(color) => bson::bson!([color.x, color.y, color.z, color.w]),
-            Self::Palette(ptr, backup_color) => bson::bson!({
-                "color": ptr.obj_serialize(project, asset_file),
-                "backup": [backup_color.x, backup_color.y, backup_color.z, backup_color.w]
-            })
+// Same as iter_bezier_segments, but with the pressure as the z coordinate, varying linearly along each segment
+pub fn iter_pressure_bezier_segments<'a>(pts: &'a Vec<StrokePoint>) -> impl Iterator<Item = BezierSegment<Vec3>> + 'a {
+    pts.windows(2).map(|arr| {
+        let p0 = arr[0].pressure;
+        let p1 = arr[1].pressure;
+        BezierSegment {
+            p0: arr[0].pt.extend(p0),
+            b0: arr[0].b.extend((2.0 * p0 + p1) / 3.0),
+            a1: arr[1].a.extend((p0 + 2.0 * p1) / 3.0),
+            p1: arr[1].pt.extend(p1)
         }
+    })
+}
+
+// Builds back a chain of stroke points from connected bezier segments with pressure
+pub fn pressure_bezier_segments_to_points(segments: &Vec<BezierSegment<Vec3>>) -> Vec<StrokePoint> {
+    let mut pts = Vec::new();
+    if segments.is_empty() {
+        return pts;
     }
+    let first = segments[0];
+    pts.push(StrokePoint {
+        a: (first.p0 - (first.b0 - first.p0)).truncate(),
+        pt: first.p0.truncate(),
+        b: first.b0.truncate(),
+        pressure: first.p0.z
+    });
+    for pair in segments.windows(2) {
+        pts.push(StrokePoint {
+            a: pair[0].a1.truncate(),
+            pt: pair[0].p1.truncate(),
+            b: pair[1].b0.truncate(),
+            pressure: pair[0].p1.z
+        });
+    }
+    let last = segments.last().unwrap();
+    pts.push(StrokePoint {
+        a: last.a1.truncate(),
+        pt: last.p1.truncate(),
+        b: (last.p1 + (last.p1 - last.a1)).truncate(),
+        pressure: last.p1.z
+    });
+    pts
+}
 
-    fn obj_serialize_full(&self, project: &Project, asset_file: &mut AssetFile) -> bson::Bson {
-        self.obj_serialize(project, asset_file)
-    }
-
-    fn obj_deserialize(project: &mut Project, data: &bson::Bson, parent: DynObjPtr, asset_file: &mut AssetFile, metadata: &mut LoadingMetadata) -> Option<Self> {
-        if let Some(arr) = data.as_array() {
-            let mut color = [0.0; 4];
-            color[3] = 1.0;
-            for i in 0..(arr.len().min(4)) {
-                if let Some(val) = arr[i].as_f64() {
-                    color[i] = val as f32;
-                } else {
-                    metadata.deserialization_error(format!("Stroke color channel {} should be a f64.", i), parent.key);
-                } 
-            } 
-            let color = glam::Vec4::from_slice(&color);
-            Some(StrokeColor::Color(color))
-        } else if let Some(obj) = data.as_document() {
-            let ptr = obj.get(&"color".to_owned()).map(|data| ObjPtr::<PaletteColor>::obj_deserialize(project, data, parent, asset_file, metadata).unwrap_or(ObjPtr::null())).unwrap_or(ObjPtr::null());
-            let backup = obj.get(&"backup".to_owned()).map(|data| glam::Vec4::obj_deserialize(project, data, parent, asset_file, metadata).unwrap_or(glam::vec4(0.0, 0.0, 0.0, 1.0))).unwrap_or(glam::vec4(0.0, 0.0, 0.0, 1.0));
-            Some(Self::Palette(ptr, backup))
-        } else {
-            metadata.deserialization_error("Could not deserialize stroke color.", parent.key); 
-            None
+// Fits smooth chains of stroke points through the rings of a polygon, used to turn polygons back into filled strokes
+pub fn polygon_to_points(polygon: &Polygon, err: f32) -> Vec<Vec<StrokePoint>> {
+    let mut res = Vec::new();
+    for ring in polygon {
+        if ring.len() < 3 {
+            continue;
+        }
+        let mut data = Vec::new();
+        for pt in ring.iter().chain(std::iter::once(&ring[0])) {
+            data.push(pt.x);
+            data.push(pt.y);
+        }
+        let curve_pts = fit_curve(2, data.as_slice(), err);
+        let chain: Vec<StrokePoint> = curve_pts.chunks_exact(6).map(|pt| StrokePoint {
+            a: vec2(pt[0], pt[1]),
+            pt: vec2(pt[2], pt[3]),
+            b: vec2(pt[4], pt[5]),
+            pressure: 1.0
+        }).collect();
+        if chain.len() >= 2 {
+            res.push(chain);
         }
     }
+    res
 }
 
-impl ToRawData for StrokeColor {
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, ObjClone, ObjSerialize)]
+pub enum StrokeCap {
+    #[default]
+    Round,
+    Square,
+    Butt
+}
 
-    type RawData = StrokeColor;
-    fn to_raw_data(&self, _project: &Project) -> Self::RawData {
-        *self        
-    }
-    
-    fn from_raw_data(_project: &mut Project, data: &Self::RawData) -> Self {
-        *data 
+impl StrokeCap {
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StrokeCap::Round => "Round",
+            StrokeCap::Square => "Square",
+            StrokeCap::Butt => "Butt",
+        }
     }
 
 }
 
-pub fn iter_bezier_segments<'a>(pts: &'a Vec<StrokePoint>) -> impl Iterator<Item = BezierSegment<Vec2>> + 'a {
-    pts.windows(2).map(|arr| BezierSegment {
-        p0: arr[0].pt,
-        b0: arr[0].b,
-        a1: arr[1].a,
-        p1: arr[1].pt
-    })
-}
-
 #[derive(Object, Clone, ObjClone, ObjSerialize, UniqueTypeId)]
 pub struct Stroke {
     #[parent]
@@ -109,6 +190,14 @@ pub struct Stroke {
     pub r: f32,
     #[field]
     pub filled: bool,
+    // Lengths over which the width goes down to 0 at the ends of unfilled strokes
+    #[field(default)]
+    pub taper_start: f32,
+    #[field(default)]
+    pub taper_end: f32,
+    // Strokes saved before caps existed had butt ends, while new strokes default to round ones
+    #[field(default = StrokeCap::Butt)]
+    pub cap: StrokeCap,
     pub points: Vec<Vec<StrokePoint>>,
 }
 
@@ -118,13 +207,103 @@ impl Stroke {
         self.points.iter().flat_map(|pts| iter_bezier_segments(pts))
     }
 
+    pub fn iter_pressure_bezier_segments(&self) -> impl Iterator<Item = BezierSegment<Vec3>> + '_ {
+        self.points.iter().flat_map(|pts| iter_pressure_bezier_segments(pts))
+    }
+
+    // The bounds of the stroke's centre line, ignoring its width
+    pub fn bounding_box(&self) -> Option<(Vec2, Vec2)> {
+        self.iter_bezier_segments().map(|segment| segment.bounding_box()).reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)))
+    }
+
+    // The region covered by a filled stroke, sampled the same way it is rendered
+    pub fn polygon(&self) -> Polygon {
+        let mut res = Vec::new();
+        for chain in &self.points {
+            let mut ring: Vec<Vec2> = Vec::new();
+            for segment in iter_bezier_segments(chain) {
+                for pt in segment.to_discrete::<10>() {
+                    if ring.last().map(|last| (*last - pt).length() > 0.0001).unwrap_or(true) {
+                        ring.push(pt);
+                    }
+                }
+            }
+            if ring.len() >= 3 {
+                res.push(ring);
+            }
+        }
+        res
+    }
+
+    /*
+        Replaces filled strokes from the same frame with a single stroke covering the result of the boolean operation between them.
+        The operation is applied in draw order, so a difference cuts the strokes on top out of the bottom one.
+    */
+    pub fn boolean(project: &mut Project, strokes: &Vec<ObjPtr<Stroke>>, op: BooleanOp) -> Option<(Option<ObjPtr<Stroke>>, Vec<ObjAction>)> {
+        let operands: Vec<ObjPtr<Stroke>> = strokes.iter().copied().filter(|stroke| project.strokes.get(*stroke).map(|stroke| stroke.filled).unwrap_or(false)).collect();
+        if operands.len() < 2 {
+            return None;
+        }
+        let frame = project.strokes.get(operands[0])?.frame;
+
+        // Everything is looked up before the frame is changed
+        let list = Stroke::get_list_in_parent(project, frame)?;
+        let mut operands: Vec<(usize, ObjPtr<Stroke>)> = operands.iter().map(|stroke| {
+            Some((list.iter().position(|other| other.make_ptr() == *stroke)?, *stroke))
+        }).collect::<Option<_>>()?;
+        operands.sort_by_key(|(idx, _)| *idx);
+        let (idx, bottom) = operands[0];
+        let bottom = project.strokes.get(bottom)?.clone();
+        let mut result = bottom.polygon();
+        for (_, stroke) in &operands[1..] {
+            result = polygon_boolean(&result, &project.strokes.get(*stroke)?.polygon(), op);
+        }
+        let points = polygon_to_points(&result, 0.25);
+
+        let mut acts = Vec::new();
+        for (_, stroke) in &operands {
+            let Some(act) = Stroke::delete(project, *stroke) else {
+                Action::from_list(acts).undo(project);
+                return None;
+            };
+            acts.push(act);
+        }
+        if points.is_empty() {
+            return Some((None, acts));
+        }
+        let n_strokes = Stroke::get_list_in_parent(project, frame)?.len();
+        let idx = if idx < n_strokes { idx as i32 } else { -1 };
+        let Some((new_stroke, act)) = Stroke::add_at_idx(project, frame, Stroke {
+            points,
+            ..bottom
+        }, idx) else {
+            Action::from_list(acts).undo(project);
+            return None;
+        };
+        acts.push(act);
+        Some((Some(new_stroke), acts))
+    }
+
+    pub fn set_points(project: &mut Project, stroke_ptr: ObjPtr<Stroke>, points: Vec<Vec<StrokePoint>>) -> Option<ObjAction> {
+        let act = project.strokes.get_then_mut(stroke_ptr, |stroke| {
+            let init_points = std::mem::replace(&mut stroke.points, points.clone());
+            ObjAction::new(move |proj| {
+                Stroke::set_points(proj, stroke_ptr, points.clone());
+            }, move |proj| {
+                Stroke::set_points(proj, stroke_ptr, init_points.clone());
+            })
+        })?;
+        project.strokes.push_event(ObjEvent::Modified(stroke_ptr, "points"));
+        Some(act)
+    }
+
     pub fn transform(project: &mut Project, stroke_ptr: ObjPtr<Stroke>, trans: Mat4) -> Option<ObjAction> {
         let transform_vec2 = |pt: Vec2, mat: Mat4| {
             let v3 = mat.transform_point3(vec3(pt.x, pt.y, 0.0));
             vec2(v3.x, v3.y)
         };
 
-        project.remeshes_needed.insert(stroke_ptr);
+        project.strokes.push_event(ObjEvent::Modified(stroke_ptr, "points"));
         project.strokes.get_then_mut(stroke_ptr, move |stroke| {
             for chain in &mut stroke.points {
                 for pt in chain {
@@ -181,6 +360,9 @@ impl Default for Stroke {
             color: StrokeColor::Color(glam::vec4(0.0, 0.0, 0.0, 1.0)),
             r: 0.05,
             filled: false,
+            taper_start: 0.0,
+            taper_end: 0.0,
+            cap: StrokeCap::default(),
             points: Vec::new()
         }
     }
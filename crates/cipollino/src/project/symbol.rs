@@ -0,0 +1,149 @@
+use glam::{Mat4, Vec4};
+use project_macros::{ObjClone, ObjSerialize, Object};
+use unique_type_id::UniqueTypeId;
+
+use super::{action::ObjAction, frame::Frame, graphic::Graphic, layer::LayerKind, obj::{child_obj::{ChildObj, HasRootAsset}, Obj, ObjBox, ObjClone, ObjPtr}, Project};
+use crate::project::obj::obj_list::ObjListTrait;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, ObjClone, ObjSerialize)]
+pub enum SymbolLoop {
+    // Starts over once the symbol's graphic ends
+    #[default]
+    Loop,
+    // Holds the last frame once the symbol's graphic ends
+    PlayOnce,
+    // Always shows the frame at the time offset
+    SingleFrame
+}
+
+impl SymbolLoop {
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SymbolLoop::Loop => "Loop",
+            SymbolLoop::PlayOnce => "Play Once",
+            SymbolLoop::SingleFrame => "Single Frame",
+        }
+    }
+
+}
+
+// A graphic placed inside a frame of another graphic
+#[derive(Object, Clone, ObjClone, ObjSerialize, UniqueTypeId)]
+pub struct SymbolInstance {
+    #[parent]
+    pub frame: ObjPtr<Frame>,
+    #[field]
+    pub graphic: ObjPtr<Graphic>,
+    #[field]
+    pub transform: Mat4,
+    // Frame of the symbol's graphic shown when the instance's frame begins
+    #[field]
+    pub time_offset: i32,
+    #[field]
+    pub loop_mode: SymbolLoop,
+    // Multiplied with the colors of everything inside the symbol
+    #[field]
+    pub tint: Vec4
+}
+
+impl ChildObj for SymbolInstance {
+    type Parent = ObjPtr<Frame>;
+
+    fn parent(&self) -> Self::Parent {
+        self.frame
+    }
+
+    fn parent_mut(&mut self) -> &mut Self::Parent {
+        &mut self.frame
+    }
+
+    fn get_list_in_parent(project: &Project, parent: Self::Parent) -> Option<&Vec<ObjBox<Self>>> {
+        Some(&project.frames.get(parent)?.symbols)
+    }
+
+    fn get_list_in_parent_mut(project: &mut Project, parent: Self::Parent) -> Option<&mut Vec<ObjBox<Self>>> {
+        Some(&mut project.frames.get_mut(parent)?.symbols)
+    }
+
+}
+
+impl HasRootAsset for SymbolInstance {
+
+    type RootAsset = Graphic;
+    fn get_root_asset(project: &Project, symbol: ObjPtr<Self>) -> Option<ObjPtr<Self::RootAsset>> {
+        Frame::get_root_asset(project, project.symbol_instances.get(symbol)?.frame)
+    }
+
+}
+
+impl Default for SymbolInstance {
+
+    fn default() -> Self {
+        Self {
+            frame: ObjPtr::null(),
+            graphic: ObjPtr::null(),
+            transform: Mat4::IDENTITY,
+            time_offset: 0,
+            loop_mode: SymbolLoop::Loop,
+            tint: Vec4::ONE
+        }
+    }
+
+}
+
+impl SymbolInstance {
+
+    // The time inside the symbol's graphic shown at the given time of the graphic it's placed in
+    pub fn graphic_time(&self, project: &Project, time: i32) -> Option<i32> {
+        let len = project.graphics.get(self.graphic)?.len as i32;
+        let frame_time = project.frames.get(self.frame)?.time;
+        if len <= 0 {
+            return None;
+        }
+        // Any time offset can be typed in, so this is done with enough room not to overflow
+        let local_time = time as i64 - frame_time as i64 + self.time_offset as i64;
+        Some(match self.loop_mode {
+            SymbolLoop::Loop => local_time.rem_euclid(len as i64) as i32,
+            SymbolLoop::PlayOnce => local_time.clamp(0, len as i64 - 1) as i32,
+            SymbolLoop::SingleFrame => self.time_offset.clamp(0, len - 1)
+        })
+    }
+
+}
+
+impl Graphic {
+
+    // Whether the graphic shows the other one, either directly or through symbols nested inside its symbols
+    pub fn contains_symbol(project: &Project, graphic: ObjPtr<Graphic>, other: ObjPtr<Graphic>) -> bool {
+        let mut visited = Vec::new();
+        Self::contains_symbol_visit(project, graphic, other, &mut visited)
+    }
+
+    fn contains_symbol_visit(project: &Project, graphic: ObjPtr<Graphic>, other: ObjPtr<Graphic>, visited: &mut Vec<ObjPtr<Graphic>>) -> bool {
+        if graphic == other {
+            return true;
+        }
+        if visited.contains(&graphic) {
+            return false;
+        }
+        visited.push(graphic);
+        let Some(graphic) = project.graphics.get(graphic) else { return false; };
+        let mut layers: Vec<_> = graphic.layers.iter().map(|layer| layer.make_ptr()).collect();
+        while let Some(layer) = layers.pop() {
+            let Some(layer) = project.layers.get(layer) else { continue; };
+            if layer.kind == LayerKind::Group {
+                layers.extend(layer.layers.iter().map(|layer| layer.make_ptr()));
+            }
+            for frame in &layer.frames {
+                for symbol in &frame.get(project).symbols {
+                    if Self::contains_symbol_visit(project, symbol.get(project).graphic, other, visited) {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+}
@@ -0,0 +1,76 @@
+
+use glam::{vec2, vec4};
+
+use crate::{editor::{clipboard::Clipboard, selection::Selection}, project::{frame::Frame, obj::{child_obj::ChildObj, obj_list::ObjListTrait}, stroke::{Stroke, StrokeColor, StrokePoint}}};
+
+use super::TestProject;
+
+fn test_stroke(x: f32) -> Stroke {
+    Stroke {
+        color: StrokeColor::Color(vec4(x, 0.0, 0.0, 1.0)),
+        r: x,
+        points: vec![vec![
+            StrokePoint { a: vec2(x, 0.0), pt: vec2(x, 1.0), b: vec2(x, 2.0), pressure: 1.0 },
+            StrokePoint { a: vec2(x, 3.0), pt: vec2(x, 4.0), b: vec2(x, 5.0), pressure: 1.0 }
+        ]],
+        ..Stroke::default()
+    }
+}
+
+#[test]
+fn clipboard_strokes() {
+    let mut test = TestProject::new();
+    let frame = test.frame();
+    let (stroke_a, _) = Stroke::add(&mut test.project, frame, test_stroke(1.0)).unwrap();
+    let (stroke_b, _) = Stroke::add(&mut test.project, frame, test_stroke(2.0)).unwrap();
+
+    let clipboard = Clipboard::from_selection(&Selection::Scene(vec![stroke_a, stroke_b]), &mut test.project);
+    let copies = if let Clipboard::Scene(copies) = &clipboard {
+        copies.iter().map(|copy| copy.make_ptr()).collect::<Vec<_>>()
+    } else {
+        panic!("expected scene clipboard");
+    };
+    assert_eq!(copies.len(), 2);
+    assert!(copies[0] != stroke_a && copies[1] != stroke_b);
+
+    // Editing the originals should not touch the copies
+    Stroke::set_r(&mut test.project, stroke_a, 10.0).unwrap();
+    Stroke::delete(&mut test.project, stroke_b).unwrap();
+    test.project.garbage_collect_objs();
+
+    let copy_a = test.project.strokes.get(copies[0]).unwrap();
+    assert_eq!(copy_a.r, 1.0);
+    assert_eq!(copy_a.points[0][1].pt, vec2(1.0, 4.0));
+    let copy_b = test.project.strokes.get(copies[1]).unwrap();
+    assert_eq!(copy_b.color.get_color(&test.project), vec4(2.0, 0.0, 0.0, 1.0));
+
+    // Dropping the clipboard frees the copies
+    drop(clipboard);
+    test.project.garbage_collect_objs();
+    assert!(test.project.strokes.get(copies[0]).is_none());
+}
+
+#[test]
+fn clipboard_frames_clone_children() {
+    let mut test = TestProject::new();
+    let (frame, _) = Frame::add(&mut test.project, test.layer, Frame {
+        time: 4,
+        ..Frame::default()
+    }).unwrap();
+    let (stroke, _) = Stroke::add(&mut test.project, frame, test_stroke(3.0)).unwrap();
+
+    let clipboard = Clipboard::from_selection(&Selection::Timeline(vec![frame], Vec::new()), &mut test.project);
+    let frame_copy = if let Clipboard::Timeline(frames, sounds) = &clipboard {
+        assert!(sounds.is_empty());
+        frames[0].make_ptr()
+    } else {
+        panic!("expected timeline clipboard");
+    };
+
+    let frame_copy = test.project.frames.get(frame_copy).unwrap();
+    assert_eq!(frame_copy.time, 4);
+    assert_eq!(frame_copy.strokes.len(), 1);
+    let stroke_copy = frame_copy.strokes[0].make_ptr();
+    assert!(stroke_copy != stroke);
+    assert_eq!(test.project.strokes.get(stroke_copy).unwrap().r, 3.0);
+}
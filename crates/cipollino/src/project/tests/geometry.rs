@@ -0,0 +1,114 @@
+
+use glam::{vec2, Vec2};
+
+use crate::{project::{frame::Frame, obj::{child_obj::ChildObj, obj_list::ObjListTrait}, stroke::{Stroke, StrokeCap, StrokePoint}}, renderer::scene::meshgen::unfilled_outline, util::polygon::{point_in_polygon, polygon_boolean, BooleanOp, Polygon}};
+
+use super::{line_stroke, TestProject};
+
+fn square(min: Vec2, max: Vec2) -> Vec<Vec2> {
+    vec![min, vec2(max.x, min.y), max, vec2(min.x, max.y)]
+}
+
+#[test]
+fn polygon_booleans() {
+    let a: Polygon = vec![square(vec2(0.0, 0.0), vec2(2.0, 2.0))];
+    let b: Polygon = vec![square(vec2(1.0, 1.0), vec2(3.0, 3.0))];
+
+    let union = polygon_boolean(&a, &b, BooleanOp::Union);
+    assert!(point_in_polygon(&union, vec2(0.5, 0.5)));
+    assert!(point_in_polygon(&union, vec2(2.5, 2.5)));
+    assert!(!point_in_polygon(&union, vec2(2.5, 0.5)));
+
+    let difference = polygon_boolean(&a, &b, BooleanOp::Difference);
+    assert!(point_in_polygon(&difference, vec2(0.5, 0.5)));
+    assert!(!point_in_polygon(&difference, vec2(1.5, 1.5)));
+    assert!(!point_in_polygon(&difference, vec2(2.5, 2.5)));
+
+    let intersection = polygon_boolean(&a, &b, BooleanOp::Intersection);
+    assert!(point_in_polygon(&intersection, vec2(1.5, 1.5)));
+    assert!(!point_in_polygon(&intersection, vec2(0.5, 0.5)));
+
+    // Cutting out a region fully inside the polygon leaves a hole
+    let hole = polygon_boolean(&a, &vec![square(vec2(0.5, 0.5), vec2(1.5, 1.5))], BooleanOp::Difference);
+    assert_eq!(hole.len(), 2);
+    assert!(!point_in_polygon(&hole, vec2(1.0, 1.0)));
+    assert!(point_in_polygon(&hole, vec2(0.25, 1.0)));
+
+    let exclude = polygon_boolean(&a, &b, BooleanOp::Exclude);
+    assert!(point_in_polygon(&exclude, vec2(0.5, 0.5)));
+    assert!(point_in_polygon(&exclude, vec2(2.5, 2.5)));
+    assert!(!point_in_polygon(&exclude, vec2(1.5, 1.5)));
+}
+
+#[test]
+fn stroke_booleans_replace_operands() {
+    let mut test = TestProject::new();
+    let frame = test.frame();
+    let add_square = |test: &mut TestProject, min: Vec2, max: Vec2, filled: bool| Stroke::add(&mut test.project, frame, Stroke {
+        filled,
+        points: vec![square(min, max).iter().chain(std::iter::once(&min)).map(|pt| StrokePoint { a: *pt, pt: *pt, b: *pt, pressure: 1.0 }).collect()],
+        ..Stroke::default()
+    }).unwrap().0;
+    let a = add_square(&mut test, vec2(0.0, 0.0), vec2(2.0, 2.0), true);
+    let b = add_square(&mut test, vec2(1.0, 1.0), vec2(3.0, 3.0), true);
+    let line = add_square(&mut test, vec2(5.0, 5.0), vec2(6.0, 6.0), false);
+
+    // Unfilled strokes are left alone
+    assert!(Stroke::boolean(&mut test.project, &vec![a, line], BooleanOp::Union).is_none());
+
+    let (_, acts) = Stroke::boolean(&mut test.project, &vec![a, b, line], BooleanOp::Union).unwrap();
+    test.check_undo_redo(acts, |project, applied| {
+        let strokes: Vec<_> = project.frames.get(frame).unwrap().strokes.iter().map(|stroke| stroke.make_ptr()).collect();
+        assert_eq!(strokes.contains(&a), !applied);
+        assert_eq!(strokes.contains(&b), !applied);
+        assert!(strokes.contains(&line));
+    });
+
+    // The stroke drawn on top is cut out of the one below, whatever order they were selected in
+    let c = add_square(&mut test, vec2(0.0, 0.0), vec2(2.0, 2.0), true);
+    let d = add_square(&mut test, vec2(1.0, 1.0), vec2(3.0, 3.0), true);
+    let (difference, acts) = Stroke::boolean(&mut test.project, &vec![d, c], BooleanOp::Difference).unwrap();
+    let difference = difference.unwrap();
+    test.check_undo_redo(acts, |project, applied| {
+        let strokes: Vec<_> = project.frames.get(frame).unwrap().strokes.iter().map(|stroke| stroke.make_ptr()).collect();
+        if !applied {
+            assert_eq!(&strokes[strokes.len() - 2..], &[c, d]);
+            return;
+        }
+        assert_eq!(strokes.last(), Some(&difference));
+        let polygon = project.strokes.get(difference).unwrap().polygon();
+        assert!(point_in_polygon(&polygon, vec2(0.5, 0.5)));
+        assert!(!point_in_polygon(&polygon, vec2(2.5, 2.5)));
+    });
+
+    // Strokes from different frames can't be combined
+    let (other_frame, _) = Frame::add(&mut test.project, test.layer, Frame { time: 5, ..Frame::default() }).unwrap();
+    let copy = test.project.strokes.get(difference).unwrap().clone();
+    let (other, _) = Stroke::add(&mut test.project, other_frame, copy).unwrap();
+    assert!(Stroke::boolean(&mut test.project, &vec![difference, other], BooleanOp::Union).is_none());
+    assert!(test.project.strokes.get(difference).is_some());
+}
+
+#[test]
+fn unfilled_outlines_include_caps() {
+    let mut stroke = line_stroke(vec2(0.0, 0.0), vec2(10.0, 0.0));
+    stroke.r = 1.0;
+
+    stroke.cap = StrokeCap::Butt;
+    let outline = unfilled_outline(&stroke);
+    assert_eq!(outline.len(), 1);
+    assert!(point_in_polygon(&outline, vec2(5.0, 0.9)));
+    assert!(!point_in_polygon(&outline, vec2(5.0, 1.1)));
+    assert!(!point_in_polygon(&outline, vec2(-0.5, 0.0)));
+
+    stroke.cap = StrokeCap::Round;
+    let outline = unfilled_outline(&stroke);
+    assert!(point_in_polygon(&outline, vec2(-0.5, 0.0)));
+    assert!(point_in_polygon(&outline, vec2(10.5, 0.0)));
+    assert!(!point_in_polygon(&outline, vec2(-0.8, 0.8)));
+
+    stroke.cap = StrokeCap::Square;
+    let outline = unfilled_outline(&stroke);
+    assert!(point_in_polygon(&outline, vec2(-0.8, 0.8)));
+    assert!(!point_in_polygon(&outline, vec2(11.2, 0.0)));
+}
@@ -0,0 +1,98 @@
+
+use std::{path::PathBuf, sync::atomic::{AtomicU32, Ordering}};
+
+use glam::Vec2;
+
+use super::{action::{Action, ObjAction}, frame::Frame, graphic::Graphic, layer::Layer, obj::{child_obj::ChildObj, ObjPtr}, stroke::{Stroke, StrokePoint}, Project};
+
+mod objs;
+mod saveload;
+mod clone;
+mod serialize;
+mod geometry;
+
+pub fn in_parent<T: ChildObj>(project: &Project, parent: T::Parent, ptr: ObjPtr<T>) -> bool {
+    T::get_list_in_parent(project, parent).map(|list| list.iter().any(|obj| obj.make_ptr() == ptr)).unwrap_or(false)
+}
+
+// A straight unfilled stroke between two points
+pub fn line_stroke(p0: Vec2, p1: Vec2) -> Stroke {
+    let dir = (p1 - p0) / 3.0;
+    Stroke {
+        points: vec![vec![
+            StrokePoint { a: p0 - dir, pt: p0, b: p0 + dir, pressure: 1.0 },
+            StrokePoint { a: p1 - dir, pt: p1, b: p1 + dir, pressure: 1.0 }
+        ]],
+        ..Stroke::default()
+    }
+}
+
+static NEXT_TEST_PROJECT_ID: AtomicU32 = AtomicU32::new(0);
+
+// A project created in its own temporary folder, deleted once the test is done
+pub struct TestProject {
+    pub project: Project,
+    pub gfx: ObjPtr<Graphic>,
+    pub layer: ObjPtr<Layer>,
+    pub dir: PathBuf
+}
+
+impl TestProject {
+
+    pub fn new() -> Self {
+        let dir = std::env::temp_dir().join(format!("cipollino_test_{}_{}", std::process::id(), NEXT_TEST_PROJECT_ID.fetch_add(1, Ordering::SeqCst)));
+        let _ = std::fs::remove_dir_all(&dir);
+        let (project, gfx, layer) = Project::create(dir.join("proj.cip"), 24.0, 44100.0);
+        Self {
+            project,
+            gfx,
+            layer,
+            dir
+        }
+    }
+
+    // Adds an empty frame at the start of the test layer
+    pub fn frame(&mut self) -> ObjPtr<Frame> {
+        Frame::add(&mut self.project, self.layer, Frame::default()).unwrap().0
+    }
+
+    pub fn save(&mut self) {
+        self.project.save(&mut |msg| panic!("save failed: {}", msg));
+        self.project.garbage_collect_objs();
+    }
+
+    // Saves the project, then loads a fresh copy of it from disk
+    pub fn reload(&mut self) -> Project {
+        self.save();
+        let (project, metadata) = Project::load(self.dir.join("proj.cip"));
+        for error in &metadata.errors {
+            panic!("load failed: {}", error.msg);
+        }
+        project
+    }
+
+    /*
+        Runs the checks against the project after the action was applied, after it was undone and after it was redone.
+        Objects are garbage collected between each step, just like the editor does between frames.
+    */
+    pub fn check_undo_redo<F>(&mut self, acts: Vec<ObjAction>, check: F) where F: Fn(&Project, bool) {
+        let action = Action::from_list(acts);
+        self.project.garbage_collect_objs();
+        check(&self.project, true);
+        action.undo(&mut self.project);
+        self.project.garbage_collect_objs();
+        check(&self.project, false);
+        action.redo(&mut self.project);
+        self.project.garbage_collect_objs();
+        check(&self.project, true);
+    }
+
+}
+
+impl Drop for TestProject {
+
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+
+}
@@ -0,0 +1,506 @@
+
+use glam::{vec2, vec4, Mat4};
+
+use crate::{editor::{symbol_commands::{convert_to_symbol, place_symbol}}, project::{camera::{Camera, CameraKey}, easing::Easing, folder::Folder, frame::Frame, graphic::Graphic, layer::{Layer, LayerKind, LayerParent}, layer_key::LayerKey, layer_transform::{LayerTransform, TransformKey}, obj::{asset::Asset, child_obj::ChildObj, obj_list::ObjListTrait, ObjPtr}, palette::{Palette, PaletteColor}, resource::ResPtr, sound_instance::SoundInstance, stroke::{iter_pressure_bezier_segments, pressure_bezier_segments_to_points, Stroke, StrokeColor, StrokePoint}, symbol::{SymbolInstance, SymbolLoop}, tween::resample_chain, Project}, tools::active_frame_proj_layer_frame};
+
+use super::{in_parent, TestProject};
+
+fn test_stroke() -> Stroke {
+    Stroke {
+        color: StrokeColor::Color(vec4(1.0, 0.0, 0.0, 1.0)),
+        r: 2.0,
+        filled: false,
+        points: vec![vec![
+            StrokePoint { a: vec2(-1.0, 0.0), pt: vec2(0.0, 0.0), b: vec2(1.0, 0.0), pressure: 1.0 },
+            StrokePoint { a: vec2(9.0, 0.0), pt: vec2(10.0, 0.0), b: vec2(11.0, 0.0), pressure: 1.0 }
+        ]],
+        ..Stroke::default()
+    }
+}
+
+fn add_frame(test: &mut TestProject, time: i32) -> ObjPtr<Frame> {
+    Frame::add(&mut test.project, test.layer, Frame {
+        time,
+        ..Frame::default()
+    }).unwrap().0
+}
+
+#[test]
+fn layer_add_delete() {
+    let mut test = TestProject::new();
+    let gfx = LayerParent::Graphic(test.gfx);
+
+    let (layer, act) = Layer::add(&mut test.project, gfx, Layer::default()).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        assert_eq!(in_parent(project, gfx, layer), applied);
+        assert_eq!(project.layers.get(layer).is_some(), applied);
+    });
+
+    let act = Layer::delete(&mut test.project, layer).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        assert_eq!(in_parent(project, gfx, layer), !applied);
+        assert_eq!(project.layers.get(layer).is_some(), !applied);
+    });
+}
+
+#[test]
+fn layer_set_and_transfer() {
+    let mut test = TestProject::new();
+    let layer = test.layer;
+
+    let act = Layer::set_name(&mut test.project, layer, "Renamed".to_owned()).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        assert_eq!(project.layers.get(layer).unwrap().name, if applied { "Renamed" } else { "Layer" });
+    });
+
+    let gfx = LayerParent::Graphic(test.gfx);
+    let (group, _) = Layer::add(&mut test.project, gfx, Layer {
+        kind: LayerKind::Group,
+        ..Layer::default()
+    }).unwrap();
+    let act = Layer::transfer(&mut test.project, layer, LayerParent::Layer(group)).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        assert_eq!(in_parent(project, LayerParent::Layer(group), layer), applied);
+        assert_eq!(in_parent(project, gfx, layer), !applied);
+        let expected_parent = if applied { LayerParent::Layer(group) } else { gfx };
+        assert_eq!(project.layers.get(layer).unwrap().parent, expected_parent);
+    });
+
+    // A layer can't be moved inside of itself
+    assert!(Layer::transfer(&mut test.project, group, LayerParent::Layer(layer)).is_none());
+
+    // But a layer can be moved out to a group further up
+    let (inner_group, _) = Layer::add(&mut test.project, LayerParent::Layer(group), Layer {
+        kind: LayerKind::Group,
+        ..Layer::default()
+    }).unwrap();
+    Layer::transfer(&mut test.project, layer, LayerParent::Layer(inner_group)).unwrap();
+    assert!(Layer::transfer(&mut test.project, layer, LayerParent::Layer(group)).is_some());
+}
+
+#[test]
+fn frame_add_set_delete() {
+    let mut test = TestProject::new();
+    let layer = test.layer;
+
+    let (frame, act) = Frame::add(&mut test.project, layer, Frame::default()).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        assert_eq!(in_parent(project, layer, frame), applied);
+    });
+
+    let act = Frame::set_time(&mut test.project, frame, 5).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        assert_eq!(project.frames.get(frame).unwrap().time, if applied { 5 } else { 0 });
+    });
+
+    let act = Frame::delete(&mut test.project, frame).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        assert_eq!(in_parent(project, layer, frame), !applied);
+        assert_eq!(project.frames.get(frame).is_some(), !applied);
+    });
+}
+
+#[test]
+fn frame_transfer() {
+    let mut test = TestProject::new();
+    let layer = test.layer;
+    let frame = add_frame(&mut test, 3);
+    let (other_layer, _) = Layer::add(&mut test.project, LayerParent::Graphic(test.gfx), Layer::default()).unwrap();
+
+    let act = Frame::transfer(&mut test.project, frame, other_layer).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        assert_eq!(in_parent(project, other_layer, frame), applied);
+        assert_eq!(in_parent(project, layer, frame), !applied);
+        assert_eq!(project.frames.get(frame).unwrap().layer, if applied { other_layer } else { layer });
+    });
+}
+
+#[test]
+fn stroke_add_set_delete() {
+    let mut test = TestProject::new();
+    let frame = add_frame(&mut test, 0);
+
+    let (stroke, act) = Stroke::add(&mut test.project, frame, test_stroke()).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        assert_eq!(in_parent(project, frame, stroke), applied);
+    });
+
+    let act_color = Stroke::set_color(&mut test.project, stroke, StrokeColor::Color(vec4(0.0, 0.0, 1.0, 1.0))).unwrap();
+    let act_r = Stroke::set_r(&mut test.project, stroke, 7.0).unwrap();
+    test.check_undo_redo(vec![act_color, act_r], |project, applied| {
+        let stroke = project.strokes.get(stroke).unwrap();
+        let expected_color = if applied { vec4(0.0, 0.0, 1.0, 1.0) } else { vec4(1.0, 0.0, 0.0, 1.0) };
+        assert_eq!(stroke.color.get_color(project), expected_color);
+        assert_eq!(stroke.r, if applied { 7.0 } else { 2.0 });
+    });
+
+    let act = Stroke::transform(&mut test.project, stroke, Mat4::from_translation(glam::vec3(5.0, 5.0, 0.0))).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        let stroke = project.strokes.get(stroke).unwrap();
+        let expected = if applied { vec2(5.0, 5.0) } else { vec2(0.0, 0.0) };
+        assert!((stroke.points[0][0].pt - expected).length() < 0.0001);
+    });
+
+    let act = Stroke::delete(&mut test.project, stroke).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        assert_eq!(in_parent(project, frame, stroke), !applied);
+        assert_eq!(project.strokes.get(stroke).is_some(), !applied);
+    });
+}
+
+#[test]
+fn stroke_transfer() {
+    let mut test = TestProject::new();
+    let frame = add_frame(&mut test, 0);
+    let other_frame = add_frame(&mut test, 1);
+    let (stroke, _) = Stroke::add(&mut test.project, frame, test_stroke()).unwrap();
+
+    let act = Stroke::transfer(&mut test.project, stroke, other_frame).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        assert_eq!(in_parent(project, other_frame, stroke), applied);
+        assert_eq!(in_parent(project, frame, stroke), !applied);
+        assert_eq!(project.strokes.get(stroke).unwrap().points[0].len(), 2);
+    });
+}
+
+#[test]
+fn stroke_pressure_segments() {
+    let pts = vec![
+        StrokePoint { a: vec2(-1.0, 0.0), pt: vec2(0.0, 0.0), b: vec2(1.0, 0.0), pressure: 0.2 },
+        StrokePoint { a: vec2(9.0, 0.0), pt: vec2(10.0, 0.0), b: vec2(11.0, 0.0), pressure: 1.0 }
+    ];
+    let segment = iter_pressure_bezier_segments(&pts).next().unwrap();
+    assert!((segment.sample(0.5).z - 0.6).abs() < 0.0001);
+
+    // Splitting a segment keeps the pressure at the cut
+    let (before, after) = segment.split(0.25);
+    let split_pts = pressure_bezier_segments_to_points(&vec![before, after]);
+    assert_eq!(split_pts.len(), 3);
+    assert_eq!(split_pts[0].pressure, 0.2);
+    assert!((split_pts[1].pressure - 0.4).abs() < 0.0001);
+    assert_eq!(split_pts[2].pressure, 1.0);
+    assert_eq!(split_pts[2].pt, vec2(10.0, 0.0));
+}
+
+#[test]
+fn sound_instance_add_set_delete_transfer() {
+    let mut test = TestProject::new();
+    let layer = test.layer;
+    let (audio_layer, _) = Layer::add(&mut test.project, LayerParent::Graphic(test.gfx), Layer {
+        kind: LayerKind::Audio,
+        ..Layer::default()
+    }).unwrap();
+
+    let (sound, act) = SoundInstance::add(&mut test.project, layer, SoundInstance {
+        begin: 0,
+        end: 100,
+        audio: ResPtr::null(),
+        ..SoundInstance::default()
+    }).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        assert_eq!(in_parent(project, layer, sound), applied);
+    });
+
+    let act = SoundInstance::set_end(&mut test.project, sound, 200).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        assert_eq!(project.sound_instances.get(sound).unwrap().end, if applied { 200 } else { 100 });
+    });
+
+    let act = SoundInstance::transfer(&mut test.project, sound, audio_layer).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        assert_eq!(in_parent(project, audio_layer, sound), applied);
+        assert_eq!(in_parent(project, layer, sound), !applied);
+    });
+
+    let act = SoundInstance::delete(&mut test.project, sound).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        assert_eq!(in_parent(project, audio_layer, sound), !applied);
+        assert_eq!(project.sound_instances.get(sound).map(|sound| sound.end), if applied { None } else { Some(200) });
+    });
+}
+
+#[test]
+fn palette_and_palette_colors() {
+    let mut test = TestProject::new();
+    let root = test.project.root_folder.make_ptr();
+
+    let (palette, acts) = Palette::asset_add(&mut test.project, root, Palette::new(root)).unwrap();
+    test.check_undo_redo(acts, |project, applied| {
+        assert_eq!(in_parent(project, root, palette), applied);
+    });
+    let (other_palette, _) = Palette::asset_add(&mut test.project, root, Palette::new(root)).unwrap();
+    assert_ne!(test.project.palettes.get(palette).unwrap().name, test.project.palettes.get(other_palette).unwrap().name);
+
+    let (color, act) = PaletteColor::add(&mut test.project, palette, PaletteColor {
+        color: vec4(0.0, 1.0, 0.0, 1.0),
+        palette
+    }).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        assert_eq!(in_parent(project, palette, color), applied);
+    });
+
+    let act = PaletteColor::set_color(&mut test.project, color, vec4(1.0, 1.0, 0.0, 1.0)).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        let expected = if applied { vec4(1.0, 1.0, 0.0, 1.0) } else { vec4(0.0, 1.0, 0.0, 1.0) };
+        assert_eq!(project.palette_colors.get(color).unwrap().color, expected);
+    });
+
+    let act = PaletteColor::transfer(&mut test.project, color, other_palette).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        assert_eq!(in_parent(project, other_palette, color), applied);
+        assert_eq!(in_parent(project, palette, color), !applied);
+    });
+
+    let act = PaletteColor::delete(&mut test.project, color).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        assert_eq!(project.palette_colors.get(color).is_some(), !applied);
+    });
+
+    let acts = Palette::asset_delete(&mut test.project, palette).unwrap();
+    test.check_undo_redo(acts, |project, applied| {
+        assert_eq!(in_parent(project, root, palette), !applied);
+    });
+}
+
+#[test]
+fn graphic_set_rename_transfer() {
+    let mut test = TestProject::new();
+    let gfx = test.gfx;
+    let root = test.project.root_folder.make_ptr();
+
+    let act = Graphic::set_len(&mut test.project, gfx, 48).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        assert_eq!(project.graphics.get(gfx).unwrap().len, if applied { 48 } else { 100 });
+    });
+
+    let act = Graphic::rename(&mut test.project, gfx, "Renamed".to_owned()).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        assert_eq!(project.graphics.get(gfx).unwrap().name, if applied { "Renamed" } else { "Clip" });
+    });
+
+    let (folder, acts) = Folder::asset_add(&mut test.project, root, Folder::new(root)).unwrap();
+    test.check_undo_redo(acts, |project, applied| {
+        assert_eq!(in_parent(project, root, folder), applied);
+    });
+
+    // Transferring moves the asset file, so it has to exist on disk first
+    test.save();
+    let acts = Graphic::asset_transfer(&mut test.project, gfx, folder).unwrap();
+    test.check_undo_redo(acts, |project, applied| {
+        assert_eq!(in_parent(project, folder, gfx), applied);
+        assert_eq!(in_parent(project, root, gfx), !applied);
+        assert!(project.graphics.get(gfx).unwrap().file_path(project).unwrap().exists());
+    });
+
+    let acts = Graphic::asset_delete(&mut test.project, gfx).unwrap();
+    test.check_undo_redo(acts, |project, applied| {
+        assert_eq!(in_parent(project, folder, gfx), !applied);
+    });
+}
+
+#[test]
+fn symbol_time() {
+    let mut test = TestProject::new();
+    let root = test.project.root_folder.make_ptr();
+    let (walk, _) = Graphic::asset_add(&mut test.project, root, Graphic { len: 4, ..Graphic::default() }).unwrap();
+    let (frame, _) = Frame::add(&mut test.project, test.layer, Frame { time: 10, ..Frame::default() }).unwrap();
+    let (symbol, _) = place_symbol(&mut test.project, frame, walk).unwrap();
+    let symbol_time = |project: &Project, time| project.symbol_instances.get(symbol).unwrap().graphic_time(project, time);
+
+    assert_eq!(symbol_time(&test.project, 10), Some(0));
+    assert_eq!(symbol_time(&test.project, 15), Some(1));
+    SymbolInstance::set_time_offset(&mut test.project, symbol, 2).unwrap();
+    assert_eq!(symbol_time(&test.project, 11), Some(3));
+    SymbolInstance::set_loop_mode(&mut test.project, symbol, SymbolLoop::PlayOnce).unwrap();
+    assert_eq!(symbol_time(&test.project, 30), Some(3));
+    SymbolInstance::set_loop_mode(&mut test.project, symbol, SymbolLoop::SingleFrame).unwrap();
+    assert_eq!(symbol_time(&test.project, 30), Some(2));
+
+    // Extreme offsets don't overflow
+    SymbolInstance::set_loop_mode(&mut test.project, symbol, SymbolLoop::Loop).unwrap();
+    SymbolInstance::set_time_offset(&mut test.project, symbol, i32::MAX).unwrap();
+    assert_eq!(symbol_time(&test.project, 11), Some(((i32::MAX as i64 + 1) % 4) as i32));
+    SymbolInstance::set_time_offset(&mut test.project, symbol, i32::MIN).unwrap();
+    SymbolInstance::set_loop_mode(&mut test.project, symbol, SymbolLoop::PlayOnce).unwrap();
+    assert_eq!(symbol_time(&test.project, 0), Some(0));
+}
+
+#[test]
+fn symbols_cannot_contain_themselves() {
+    let mut test = TestProject::new();
+    let frame = test.frame();
+    let strokes = vec![
+        Stroke::add(&mut test.project, frame, test_stroke()).unwrap().0,
+        Stroke::add(&mut test.project, frame, test_stroke()).unwrap().0
+    ];
+
+    let (symbol, acts) = convert_to_symbol(&mut test.project, &strokes).unwrap();
+    let symbol_gfx = test.project.symbol_instances.get(symbol).unwrap().graphic;
+    let symbol_frame = test.project.graphics.get(symbol_gfx).unwrap().layers[0].get(&test.project).frames[0].make_ptr();
+    test.check_undo_redo(acts, |project, applied| {
+        assert_eq!(project.graphics.get(symbol_gfx).is_some(), applied);
+        assert_eq!(in_parent(project, frame, symbol), applied);
+        for stroke in &strokes {
+            assert_eq!(in_parent(project, frame, *stroke), !applied);
+            assert_eq!(in_parent(project, symbol_frame, *stroke), applied);
+        }
+    });
+
+    // Neither the clip nor the symbol can be placed inside of the symbol
+    assert!(Graphic::contains_symbol(&test.project, test.gfx, symbol_gfx));
+    assert!(place_symbol(&mut test.project, symbol_frame, symbol_gfx).is_none());
+    assert!(place_symbol(&mut test.project, symbol_frame, test.gfx).is_none());
+    assert!(place_symbol(&mut test.project, frame, symbol_gfx).is_some());
+}
+
+#[test]
+fn camera_keys() {
+    let mut test = TestProject::new();
+    let (camera_layer, _) = Layer::add(&mut test.project, LayerParent::Graphic(test.gfx), Layer {
+        kind: LayerKind::Camera,
+        ..Layer::default()
+    }).unwrap();
+    assert_eq!(Graphic::camera_layer(&test.project, test.gfx), Some(camera_layer));
+    assert_eq!(Camera::of_graphic(&test.project, test.gfx, 5), Some(Camera::default()));
+
+    let acts = CameraKey::set_at(&mut test.project, camera_layer, 10, Camera { position: vec2(100.0, 0.0), zoom: 2.0, rotation: 90.0 }).unwrap();
+    test.check_undo_redo(acts, |project, applied| {
+        assert_eq!(CameraKey::get_key_exactly_at(project, camera_layer, 10).is_some(), applied);
+    });
+    CameraKey::add(&mut test.project, camera_layer, CameraKey { time: 0, ..CameraKey::default() }).unwrap();
+
+    // Before the first key and after the last one, the camera holds still
+    let camera_at = |project: &Project, time| Camera::of_graphic(project, test.gfx, time).unwrap();
+    assert_eq!(camera_at(&test.project, 20).position, vec2(100.0, 0.0));
+    let halfway = camera_at(&test.project, 5);
+    assert!((halfway.position - vec2(50.0, 0.0)).length() < 0.001);
+    assert!((halfway.zoom - 2.0f32.sqrt()).abs() < 0.001);
+    assert!((halfway.rotation - 45.0).abs() < 0.001);
+
+    let first_key = CameraKey::get_key_exactly_at(&test.project, camera_layer, 0).unwrap();
+    CameraKey::set_easing(&mut test.project, first_key, Easing::Hold).unwrap();
+    assert_eq!(camera_at(&test.project, 9), Camera::default());
+
+    // Hidden camera layers aren't used
+    Layer::set_show(&mut test.project, camera_layer, false).unwrap();
+    assert_eq!(Camera::of_graphic(&test.project, test.gfx, 5), None);
+}
+
+#[test]
+fn layer_transform_keys() {
+    let mut test = TestProject::new();
+    assert_eq!(LayerTransform::at_time(&test.project, test.layer, 0), None);
+
+    let layer = test.layer;
+    let acts = TransformKey::set_at(&mut test.project, layer, 0, LayerTransform::default()).unwrap();
+    test.check_undo_redo(acts, |project, applied| {
+        assert_eq!(LayerTransform::at_time(project, layer, 0).is_some(), applied);
+    });
+    TransformKey::set_at(&mut test.project, test.layer, 10, LayerTransform {
+        position: vec2(100.0, 0.0),
+        rotation: 90.0,
+        scale: vec2(3.0, 3.0),
+        opacity: 0.0,
+        ..LayerTransform::default()
+    }).unwrap();
+
+    let halfway = LayerTransform::at_time(&test.project, test.layer, 5).unwrap();
+    assert_eq!(halfway.position, vec2(50.0, 0.0));
+    assert_eq!(halfway.scale, vec2(2.0, 2.0));
+    assert_eq!(halfway.opacity, 0.5);
+
+    let first_key = TransformKey::get_key_exactly_at(&test.project, test.layer, 0).unwrap();
+    TransformKey::set_easing(&mut test.project, first_key, Easing::EaseIn).unwrap();
+    assert_eq!(LayerTransform::at_time(&test.project, test.layer, 5).unwrap().position, vec2(25.0, 0.0));
+
+    // Layers inside of groups are also moved by the group's transform
+    let (group, _) = Layer::add(&mut test.project, LayerParent::Graphic(test.gfx), Layer {
+        kind: LayerKind::Group,
+        ..Layer::default()
+    }).unwrap();
+    Layer::transfer(&mut test.project, test.layer, LayerParent::Layer(group)).unwrap();
+    TransformKey::set_at(&mut test.project, group, 0, LayerTransform {
+        position: vec2(0.0, 7.0),
+        ..LayerTransform::default()
+    }).unwrap();
+    let pt = LayerTransform::world_matrix(&test.project, test.layer, 10).transform_point3(glam::vec3(1.0, 0.0, 0.0));
+    assert!((pt - glam::vec3(100.0, 10.0, 0.0)).length() < 0.001);
+}
+
+#[test]
+fn resample_keeps_shape() {
+    let chain = test_stroke().points[0].clone();
+    let resampled = resample_chain(&chain, 5).unwrap();
+    assert_eq!(resampled.len(), 5);
+    assert_eq!(resampled[0].pt, chain[0].pt);
+    assert_eq!(resampled[4].pt, chain[1].pt);
+    assert!(resampled.iter().all(|pt| pt.pt.y == 0.0));
+    assert!(resample_chain(&resampled, 2).is_none());
+}
+
+#[test]
+fn frame_tweens() {
+    let mut test = TestProject::new();
+    let from = add_frame(&mut test, 0);
+    let to = add_frame(&mut test, 10);
+    Stroke::add(&mut test.project, from, Stroke {
+        frame: from,
+        ..test_stroke()
+    }).unwrap();
+    let mut moved = test_stroke();
+    for pt in &mut moved.points[0] {
+        pt.a.y += 10.0;
+        pt.pt.y += 10.0;
+        pt.b.y += 10.0;
+    }
+    moved.points[0].insert(1, StrokePoint { a: vec2(4.0, 10.0), pt: vec2(5.0, 10.0), b: vec2(6.0, 10.0), pressure: 1.0 });
+    Stroke::add(&mut test.project, to, Stroke {
+        frame: to,
+        color: StrokeColor::Color(vec4(0.0, 0.0, 1.0, 1.0)),
+        ..moved
+    }).unwrap();
+
+    assert!(Frame::tween_at(&test.project, from, 5).is_none());
+    Frame::set_tween(&mut test.project, from, true).unwrap();
+    assert_eq!(Frame::tween_target(&test.project, from), Some(to));
+    assert!(Frame::tween_at(&test.project, from, 0).is_none());
+    assert!(Frame::tween_at(&test.project, from, 10).is_none());
+
+    let halfway = Frame::tween_at(&test.project, from, 5).unwrap();
+    assert_eq!(halfway[0].points[0].len(), 3);
+    assert_eq!(halfway[0].points[0][0].pt, vec2(0.0, 5.0));
+    assert_eq!(halfway[0].color.get_color(&test.project), vec4(0.5, 0.0, 0.5, 1.0));
+
+    Frame::set_tween_easing(&mut test.project, from, Easing::EaseIn).unwrap();
+    assert_eq!(Frame::tween_at(&test.project, from, 5).unwrap()[0].points[0][0].pt, vec2(0.0, 2.5));
+
+    // Tools can't draw on the in-between drawings
+    assert!(active_frame_proj_layer_frame(&mut test.project, test.layer, 5).is_none());
+    assert_eq!(active_frame_proj_layer_frame(&mut test.project, test.layer, 0).unwrap().0, from);
+
+    let layer = test.layer;
+    let acts = Frame::bake_tween(&mut test.project, from).unwrap();
+    test.check_undo_redo(acts, |project, applied| {
+        let layer = project.layers.get(layer).unwrap();
+        assert_eq!(layer.frames.len(), if applied { 11 } else { 2 });
+        assert_eq!(project.frames.get(from).unwrap().tween, !applied);
+        if applied {
+            let in_between = layer.get_frame_at(project, 3).unwrap();
+            assert_eq!(in_between.get(project).time, 3);
+            assert_eq!(in_between.get(project).strokes.len(), 1);
+        }
+    });
+
+    // Drawings with different numbers of strokes can't be tweened
+    let from = add_frame(&mut test, 20);
+    let to = add_frame(&mut test, 30);
+    Frame::set_tween(&mut test.project, from, true).unwrap();
+    for frame in [from, to, to] {
+        Stroke::add(&mut test.project, frame, Stroke {
+            frame,
+            ..test_stroke()
+        }).unwrap();
+    }
+    assert!(Frame::tween_at(&test.project, from, 25).is_none());
+    assert!(Frame::bake_tween(&mut test.project, from).is_none());
+}
@@ -0,0 +1,205 @@
+
+use glam::{vec2, vec3, vec4, Mat4};
+
+use crate::project::{camera::CameraKey, easing::Easing, frame::Frame, graphic::{Graphic, Guide}, layer::{Layer, LayerKind, LayerParent}, obj::{asset::Asset, asset_list::AssetList, child_obj::ChildObj, obj_list::ObjListTrait, ObjPtr}, palette::{Palette, PaletteColor}, saveload::load::LoadingMetadata, stroke::{Stroke, StrokeCap, StrokeColor, StrokePoint}, symbol::{SymbolInstance, SymbolLoop}, Project};
+
+use super::TestProject;
+
+fn load_graphic(project: &mut Project, name: &str) -> ObjPtr<Graphic> {
+    let root = project.root_folder.make_ptr();
+    let gfx = project.folders.get(root).unwrap().graphics.iter()
+        .map(|gfx| gfx.make_ptr())
+        .find(|gfx| project.graphics.get_name(*gfx).as_deref() == Some(name))
+        .expect("graphic missing");
+    let mut metadata = LoadingMetadata::new();
+    AssetList::load(project, gfx, &mut metadata).unwrap();
+    assert!(metadata.errors.is_empty());
+    gfx
+}
+
+fn load_palette(project: &mut Project, name: &str) -> ObjPtr<Palette> {
+    let root = project.root_folder.make_ptr();
+    let palette = project.folders.get(root).unwrap().palettes.iter()
+        .map(|palette| palette.make_ptr())
+        .find(|palette| project.palettes.get_name(*palette).as_deref() == Some(name))
+        .expect("palette missing");
+    let mut metadata = LoadingMetadata::new();
+    AssetList::load(project, palette, &mut metadata).unwrap();
+    assert!(metadata.errors.is_empty());
+    palette
+}
+
+#[test]
+fn graphic_round_trip() {
+    let mut test = TestProject::new();
+    Graphic::set_len(&mut test.project, test.gfx, 42).unwrap();
+    Graphic::set_guides(&mut test.project, test.gfx, vec![Guide { vertical: true, pos: 12.5 }]).unwrap();
+    Layer::set_name(&mut test.project, test.layer, "Ink".to_owned()).unwrap();
+    let (frame, _) = Frame::add(&mut test.project, test.layer, Frame {
+        time: 7,
+        ..Frame::default()
+    }).unwrap();
+    Stroke::add(&mut test.project, frame, Stroke {
+        color: StrokeColor::Color(vec4(0.25, 0.5, 0.75, 1.0)),
+        r: 3.0,
+        filled: true,
+        taper_start: 12.0,
+        cap: StrokeCap::Square,
+        points: vec![vec![
+            StrokePoint { a: vec2(0.0, 1.0), pt: vec2(1.0, 2.0), b: vec2(2.0, 3.0), pressure: 0.5 },
+            StrokePoint { a: vec2(4.0, 5.0), pt: vec2(6.0, 7.0), b: vec2(8.0, 9.0), pressure: 0.75 },
+        ]],
+        ..Stroke::default()
+    }).unwrap();
+
+    let mut loaded = test.reload();
+    let gfx_ptr = load_graphic(&mut loaded, "Clip");
+    let gfx = loaded.graphics.get(gfx_ptr).unwrap();
+    assert_eq!(gfx.len, 42);
+    assert_eq!(gfx.guides, vec![Guide { vertical: true, pos: 12.5 }]);
+    assert_eq!(gfx.layers.len(), 1);
+
+    let layer = gfx.layers[0].get(&loaded);
+    assert_eq!(layer.name, "Ink");
+    assert_eq!(layer.parent, LayerParent::Graphic(gfx_ptr));
+    assert_eq!(layer.frames.len(), 1);
+
+    let frame = layer.frames[0].get(&loaded);
+    assert_eq!(frame.time, 7);
+    assert_eq!(frame.strokes.len(), 1);
+
+    let stroke = frame.strokes[0].get(&loaded);
+    assert_eq!(stroke.color.get_color(&loaded), vec4(0.25, 0.5, 0.75, 1.0));
+    assert_eq!(stroke.r, 3.0);
+    assert!(stroke.filled);
+    assert_eq!(stroke.taper_start, 12.0);
+    assert_eq!(stroke.taper_end, 0.0);
+    assert_eq!(stroke.cap, StrokeCap::Square);
+    assert_eq!(stroke.points[0].len(), 2);
+    assert_eq!(stroke.points[0][1].b, vec2(8.0, 9.0));
+    assert_eq!(stroke.points[0][0].pressure, 0.5);
+}
+
+#[test]
+fn incremental_saves() {
+    let mut test = TestProject::new();
+    let frame = test.frame();
+    let (stroke, _) = Stroke::add(&mut test.project, frame, Stroke {
+        r: 1.0,
+        points: vec![vec![StrokePoint::default(), StrokePoint::default()]],
+        ..Stroke::default()
+    }).unwrap();
+    let (other_frame, _) = Frame::add(&mut test.project, test.layer, Frame {
+        time: 3,
+        ..Frame::default()
+    }).unwrap();
+    test.save();
+
+    // Modify and delete objects that are already in the asset file
+    Stroke::set_r(&mut test.project, stroke, 4.0).unwrap();
+    Frame::delete(&mut test.project, other_frame).unwrap();
+
+    let mut loaded = test.reload();
+    let gfx = load_graphic(&mut loaded, "Clip");
+    let layer = loaded.graphics.get(gfx).unwrap().layers[0].get(&loaded);
+    assert_eq!(layer.frames.len(), 1);
+    let frame = layer.frames[0].get(&loaded);
+    assert_eq!(frame.time, 0);
+    assert_eq!(frame.strokes[0].get(&loaded).r, 4.0);
+}
+
+#[test]
+fn palette_round_trip() {
+    let mut test = TestProject::new();
+    let root = test.project.root_folder.make_ptr();
+    let (palette, _) = Palette::asset_add(&mut test.project, root, Palette::new(root)).unwrap();
+    let (color, _) = PaletteColor::add(&mut test.project, palette, PaletteColor {
+        color: vec4(1.0, 0.5, 0.0, 1.0),
+        palette
+    }).unwrap();
+    let frame = test.frame();
+    Stroke::add(&mut test.project, frame, Stroke {
+        color: StrokeColor::Palette { color, backup: vec4(0.0, 0.0, 0.0, 1.0) },
+        points: vec![vec![StrokePoint::default(), StrokePoint::default()]],
+        ..Stroke::default()
+    }).unwrap();
+
+    let mut loaded = test.reload();
+    let palette = load_palette(&mut loaded, "Palette");
+    let gfx = load_graphic(&mut loaded, "Clip");
+
+    let colors = &loaded.palettes.get(palette).unwrap().colors;
+    assert_eq!(colors.len(), 1);
+    assert_eq!(colors[0].get(&loaded).color, vec4(1.0, 0.5, 0.0, 1.0));
+
+    // The stroke should still point at the palette color
+    let layer = loaded.graphics.get(gfx).unwrap().layers[0].get(&loaded);
+    let stroke = layer.frames[0].get(&loaded).strokes[0].get(&loaded);
+    assert_eq!(stroke.color.get_color(&loaded), vec4(1.0, 0.5, 0.0, 1.0));
+}
+
+#[test]
+fn symbol_round_trip() {
+    let mut test = TestProject::new();
+    let root = test.project.root_folder.make_ptr();
+    let (walk, _) = Graphic::asset_add(&mut test.project, root, Graphic {
+        name: "Walk".to_owned(),
+        len: 8,
+        ..Graphic::default()
+    }).unwrap();
+    let frame = test.frame();
+    let transform = Mat4::from_translation(vec3(10.0, -5.0, 0.0)) * Mat4::from_scale(vec3(2.0, 2.0, 1.0));
+    SymbolInstance::add(&mut test.project, frame, SymbolInstance {
+        graphic: walk,
+        transform,
+        time_offset: 3,
+        loop_mode: SymbolLoop::PlayOnce,
+        tint: vec4(1.0, 0.5, 0.5, 0.8),
+        ..SymbolInstance::default()
+    }).unwrap();
+
+    let mut loaded = test.reload();
+    let gfx = load_graphic(&mut loaded, "Clip");
+    let frame = loaded.graphics.get(gfx).unwrap().layers[0].get(&loaded).frames[0].get(&loaded);
+    assert_eq!(frame.symbols.len(), 1);
+    let symbol = frame.symbols[0].get(&loaded);
+    assert_eq!(symbol.transform, transform);
+    assert_eq!(symbol.time_offset, 3);
+    assert_eq!(symbol.loop_mode, SymbolLoop::PlayOnce);
+    assert_eq!(symbol.tint, vec4(1.0, 0.5, 0.5, 0.8));
+
+    // Loading the symbol also loads the graphic it shows
+    assert_eq!(loaded.graphics.get(symbol.graphic).unwrap().len, 8);
+    assert_eq!(symbol.graphic_time(&loaded, 20), Some(7));
+}
+
+#[test]
+fn camera_round_trip() {
+    let mut test = TestProject::new();
+    let (camera_layer, _) = Layer::add(&mut test.project, LayerParent::Graphic(test.gfx), Layer {
+        name: "Camera".to_owned(),
+        kind: LayerKind::Camera,
+        ..Layer::default()
+    }).unwrap();
+    CameraKey::add(&mut test.project, camera_layer, CameraKey {
+        time: 12,
+        position: vec2(-40.0, 25.0),
+        zoom: 1.5,
+        rotation: -30.0,
+        easing: Easing::EaseInOut,
+        ..CameraKey::default()
+    }).unwrap();
+
+    let mut loaded = test.reload();
+    let gfx = load_graphic(&mut loaded, "Clip");
+    let camera_layer = Graphic::camera_layer(&loaded, gfx).unwrap();
+    let layer = loaded.layers.get(camera_layer).unwrap();
+    assert_eq!(layer.name, "Camera");
+    assert_eq!(layer.camera_keys.len(), 1);
+    let key = layer.camera_keys[0].get(&loaded);
+    assert_eq!(key.time, 12);
+    assert_eq!(key.position, vec2(-40.0, 25.0));
+    assert_eq!(key.zoom, 1.5);
+    assert_eq!(key.rotation, -30.0);
+    assert_eq!(key.easing, Easing::EaseInOut);
+}
@@ -0,0 +1,220 @@
+
+use glam::{vec2, vec4, Vec2};
+use project_macros::{ObjClone, ObjSerialize};
+
+use crate::{project::{layer::{BlendingMode, Layer, LayerKind}, obj::{asset::Asset, child_obj::ChildObj, ObjClone, ObjPtr, ObjSerialize, ToRawData}, saveload::{asset_file::AssetFile, load::LoadingMetadata}, palette::{Palette, PaletteColor}, stroke::{Stroke, StrokeCap, StrokeColor}, Project}, util::bson::bson_get};
+
+use super::TestProject;
+
+#[derive(Clone, Default, Debug, PartialEq, ObjClone, ObjSerialize)]
+struct Nested {
+    pos: Vec2,
+    #[serialize(rename = "w", alias = "weight")]
+    weight: f32
+}
+
+#[derive(Clone, Debug, PartialEq, ObjClone, ObjSerialize)]
+enum Shape {
+    Empty,
+    Point(Vec2),
+    Segment(Vec2, Vec2),
+    Circle {
+        center: Nested,
+        #[field(default = 1.0)]
+        radius: f32
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, ObjClone, ObjSerialize)]
+struct Drawing {
+    shapes: Vec<Shape>,
+    #[field(default)]
+    nested: Nested,
+    #[serialize(skip)]
+    cache: u32,
+    #[field(default = 2.0)]
+    scale: f32
+}
+
+fn asset_file(test: &TestProject) -> AssetFile {
+    AssetFile::create(test.dir.join("serialize_test"), 0, b"test").unwrap()
+}
+
+fn deserialize<T: ObjSerialize>(project: &mut Project, asset_file: &mut AssetFile, data: bson::Bson) -> (Option<T>, LoadingMetadata) {
+    let mut metadata = LoadingMetadata::new();
+    let res = T::obj_deserialize(project, &data, ObjPtr::<Layer>::null().into(), asset_file, &mut metadata);
+    (res, metadata)
+}
+
+#[test]
+fn derived_round_trip() {
+    let mut test = TestProject::new();
+    let mut file = asset_file(&test);
+    let drawing = Drawing {
+        shapes: vec![
+            Shape::Empty,
+            Shape::Point(vec2(1.0, 2.0)),
+            Shape::Segment(vec2(3.0, 4.0), vec2(5.0, 6.0)),
+            Shape::Circle { center: Nested { pos: vec2(7.0, 8.0), weight: 0.5 }, radius: 9.0 }
+        ],
+        nested: Nested { pos: vec2(-1.0, -2.0), weight: 3.0 },
+        cache: 5,
+        scale: 0.25
+    };
+
+    let data = drawing.obj_serialize(&test.project, &mut file);
+    let shapes = bson_get(&data, "shapes").unwrap();
+    assert_eq!(bson_get(shapes, 0), Some(&bson::bson!("Empty")));
+    assert_eq!(bson_get(shapes, 2), Some(&bson::bson!({ "Segment": [[3.0, 4.0], [5.0, 6.0]] })));
+    assert!(bson_get(&data, "cache").is_none());
+    assert!(bson_get(bson_get(&data, "nested").unwrap(), "w").is_some());
+
+    let (loaded, metadata) = deserialize::<Drawing>(&mut test.project, &mut file, data);
+    assert!(metadata.errors.is_empty());
+    assert_eq!(loaded.unwrap(), Drawing {
+        cache: 0,
+        ..drawing.clone()
+    });
+
+    let raw = drawing.to_raw_data(&test.project);
+    assert_eq!(Drawing::from_raw_data(&mut test.project, &raw), drawing);
+    assert_eq!(drawing.obj_clone(&mut test.project), drawing);
+}
+
+#[test]
+fn derived_defaults_and_aliases() {
+    let mut test = TestProject::new();
+    let mut file = asset_file(&test);
+
+    let (loaded, metadata) = deserialize::<Drawing>(&mut test.project, &mut file, bson::bson!({
+        "shapes": [{ "Circle": { "center": { "pos": [1.0, 1.0], "weight": 4.0 } } }]
+    }));
+    assert!(metadata.errors.is_empty());
+    let loaded = loaded.unwrap();
+    assert_eq!(loaded.scale, 2.0);
+    assert_eq!(loaded.nested, Nested::default());
+    assert_eq!(loaded.shapes, vec![Shape::Circle { center: Nested { pos: vec2(1.0, 1.0), weight: 4.0 }, radius: 1.0 }]);
+
+    // Enum variant fields without a default are required
+    let (loaded, metadata) = deserialize::<Shape>(&mut test.project, &mut file, bson::bson!({ "Circle": { "radius": 2.0 } }));
+    assert!(loaded.is_none());
+    assert_eq!(metadata.errors.len(), 1);
+
+    let (loaded, metadata) = deserialize::<Shape>(&mut test.project, &mut file, bson::bson!("Triangle"));
+    assert!(loaded.is_none());
+    assert_eq!(metadata.errors.len(), 1);
+
+    // Struct fields without one fall back to their type's default
+    let (loaded, metadata) = deserialize::<Drawing>(&mut test.project, &mut file, bson::bson!({ "scale": 3.0, "shapes": 5 }));
+    assert!(metadata.errors.is_empty());
+    let loaded = loaded.unwrap();
+    assert!(loaded.shapes.is_empty());
+    assert_eq!(loaded.scale, 3.0);
+}
+
+#[test]
+fn unit_enums_keep_serde_layout() {
+    let test = TestProject::new();
+    let mut file = asset_file(&test);
+    assert_eq!(LayerKind::Group.obj_serialize(&test.project, &mut file), bson::Bson::String("Group".to_owned()));
+    assert_eq!(BlendingMode::ColorDodge.obj_serialize(&test.project, &mut file), bson::Bson::String("ColorDodge".to_owned()));
+}
+
+#[test]
+fn fields_fall_back_to_defaults_quietly() {
+    let mut test = TestProject::new();
+    let mut file = asset_file(&test);
+
+    // A layer saved before blending and fill references existed, with a corrupted alpha
+    let old_layer = bson::bson!({
+        "name": "Old",
+        "show": true,
+        "lock": true,
+        "open": false,
+        "kind": "Animation",
+        "alpha": "half",
+        "frames": [],
+        "sound_instances": [],
+        "layers": []
+    });
+    let (layer, metadata) = deserialize::<Layer>(&mut test.project, &mut file, old_layer.clone());
+    assert!(metadata.errors.is_empty());
+    let layer = layer.unwrap();
+    assert_eq!(layer.name, "Old");
+    assert!(layer.show);
+    assert!(layer.lock);
+    assert_eq!(layer.alpha, 1.0);
+    assert_eq!(layer.blending, BlendingMode::Normal);
+    assert!(!layer.fill_reference);
+    assert!(layer.camera_keys.is_empty());
+
+    // Fields without an explicit default use their type's, keeping the rest of the layer
+    let mut broken_layer = old_layer.as_document().unwrap().clone();
+    broken_layer.insert("show", "yes");
+    broken_layer.remove("kind");
+    let (layer, metadata) = deserialize::<Layer>(&mut test.project, &mut file, bson::Bson::Document(broken_layer));
+    assert!(metadata.errors.is_empty());
+    let layer = layer.unwrap();
+    assert!(!layer.show);
+    assert!(layer.lock);
+    assert_eq!(layer.kind, LayerKind::default());
+    assert_eq!(layer.name, "Old");
+
+    // A stroke saved before tapers, caps and pressure existed
+    let (stroke, metadata) = deserialize::<Stroke>(&mut test.project, &mut file, bson::bson!({
+        "color": [1.0, 0.0, 0.0, 1.0],
+        "r": 2.0,
+        "filled": false,
+        "points": [[{ "a": [0.0, 0.0], "pt": [1.0, 1.0], "b": [2.0, 2.0] }]]
+    }));
+    assert!(metadata.errors.is_empty());
+    let stroke = stroke.unwrap();
+    assert_eq!(stroke.taper_start, 0.0);
+    assert_eq!(stroke.cap, StrokeCap::Butt);
+    assert_eq!(Stroke::default().cap, StrokeCap::Round);
+    assert_eq!(stroke.points[0][0].pressure, 1.0);
+}
+
+#[test]
+fn stroke_color_round_trip() {
+    let mut test = TestProject::new();
+    let mut file = asset_file(&test);
+    let root = test.project.root_folder.make_ptr();
+    let (palette, _) = Palette::asset_add(&mut test.project, root, Palette::new(root)).unwrap();
+    let (color, _) = PaletteColor::add(&mut test.project, palette, PaletteColor {
+        color: vec4(1.0, 0.5, 0.0, 1.0),
+        palette
+    }).unwrap();
+
+    // Plain colors are written as just their channels, like before the derive
+    let plain = StrokeColor::Color(vec4(0.25, 0.5, 0.75, 1.0));
+    let data = plain.obj_serialize(&test.project, &mut file);
+    assert_eq!(data, bson::bson!([0.25, 0.5, 0.75, 1.0]));
+    let (loaded, metadata) = deserialize::<StrokeColor>(&mut test.project, &mut file, data);
+    assert!(metadata.errors.is_empty());
+    assert!(loaded.unwrap().same_as(&plain));
+
+    let from_palette = StrokeColor::Palette { color, backup: vec4(0.0, 1.0, 0.0, 1.0) };
+    let data = from_palette.obj_serialize(&test.project, &mut file);
+    assert!(bson_get(&data, "color").is_some());
+    assert!(bson_get(&data, "backup").is_some());
+    let (loaded, metadata) = deserialize::<StrokeColor>(&mut test.project, &mut file, data);
+    assert!(metadata.errors.is_empty());
+    let loaded = loaded.unwrap();
+    assert!(loaded.same_as(&from_palette));
+    assert_eq!(loaded.get_color(&test.project), vec4(1.0, 0.5, 0.0, 1.0));
+
+    // Palette colors saved without a backup fall back to black
+    let mut data = from_palette.obj_serialize(&test.project, &mut file).as_document().unwrap().clone();
+    data.remove("backup");
+    let (loaded, _) = deserialize::<StrokeColor>(&mut test.project, &mut file, bson::Bson::Document(data));
+    let StrokeColor::Palette { backup, .. } = loaded.unwrap() else { panic!("expected a palette color") };
+    assert_eq!(backup, vec4(0.0, 0.0, 0.0, 1.0));
+
+    let (loaded, metadata) = deserialize::<StrokeColor>(&mut test.project, &mut file, bson::bson!("red"));
+    assert!(loaded.is_none());
+    assert_eq!(metadata.errors.len(), 1);
+
+    let raw = from_palette.to_raw_data(&test.project);
+    assert!(StrokeColor::from_raw_data(&mut test.project, &raw).same_as(&from_palette));
+}
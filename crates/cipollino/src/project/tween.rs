@@ -0,0 +1,150 @@
+use crate::util::curve::BezierSegment;
+
+use super::{action::{Action, ObjAction}, frame::Frame, layer::Layer, obj::{child_obj::ChildObj, obj_list::ObjListTrait, ObjPtr}, stroke::{Stroke, StrokeColor, StrokePoint}, Project};
+
+fn segment(chain: &Vec<StrokePoint>, idx: usize) -> BezierSegment<glam::Vec3> {
+    BezierSegment {
+        p0: chain[idx].pt.extend(chain[idx].pressure),
+        b0: chain[idx].b.extend(chain[idx].pressure),
+        a1: chain[idx + 1].a.extend(chain[idx + 1].pressure),
+        p1: chain[idx + 1].pt.extend(chain[idx + 1].pressure)
+    }
+}
+
+// Adds points to a chain without changing its shape, by splitting its longest segments in half
+pub fn resample_chain(chain: &Vec<StrokePoint>, n_points: usize) -> Option<Vec<StrokePoint>> {
+    if chain.len() > n_points || chain.len() < 2 {
+        return if chain.len() == n_points { Some(chain.clone()) } else { None };
+    }
+    let mut chain = chain.clone();
+    while chain.len() < n_points {
+        let control_polygon_len = |seg: &BezierSegment<glam::Vec3>| {
+            let (p0, b0, a1, p1) = (seg.p0.truncate(), seg.b0.truncate(), seg.a1.truncate(), seg.p1.truncate());
+            (b0 - p0).length() + (a1 - b0).length() + (p1 - a1).length()
+        };
+        let longest = (0..(chain.len() - 1)).max_by(|a, b| control_polygon_len(&segment(&chain, *a)).total_cmp(&control_polygon_len(&segment(&chain, *b))))?;
+        let (first, second) = segment(&chain, longest).split(0.5);
+        chain[longest].b = first.b0.truncate();
+        chain[longest + 1].a = second.a1.truncate();
+        chain.insert(longest + 1, StrokePoint {
+            a: first.a1.truncate(),
+            pt: first.p1.truncate(),
+            b: second.b0.truncate(),
+            pressure: first.p1.z
+        });
+    }
+    Some(chain)
+}
+
+fn lerp_point(a: &StrokePoint, b: &StrokePoint, t: f32) -> StrokePoint {
+    StrokePoint {
+        a: a.a.lerp(b.a, t),
+        pt: a.pt.lerp(b.pt, t),
+        b: a.b.lerp(b.b, t),
+        pressure: a.pressure + (b.pressure - a.pressure) * t
+    }
+}
+
+fn lerp_color(project: &Project, a: StrokeColor, b: StrokeColor, t: f32) -> StrokeColor {
+    if a.same_as(&b) {
+        return a;
+    }
+    StrokeColor::Color(a.get_color(project).lerp(b.get_color(project), t))
+}
+
+// Strokes are matched up by their order in the frame, so both drawings need the same number of strokes and chains
+fn tween_stroke(project: &Project, a: &Stroke, b: &Stroke, t: f32) -> Option<Stroke> {
+    if a.points.len() != b.points.len() {
+        return None;
+    }
+    let mut points = Vec::new();
+    for (chain_a, chain_b) in a.points.iter().zip(b.points.iter()) {
+        let n_points = chain_a.len().max(chain_b.len());
+        let chain_a = resample_chain(chain_a, n_points)?;
+        let chain_b = resample_chain(chain_b, n_points)?;
+        points.push(chain_a.iter().zip(chain_b.iter()).map(|(pt_a, pt_b)| lerp_point(pt_a, pt_b, t)).collect());
+    }
+    Some(Stroke {
+        frame: a.frame,
+        color: lerp_color(project, a.color, b.color, t),
+        r: a.r + (b.r - a.r) * t,
+        filled: a.filled,
+        taper_start: a.taper_start + (b.taper_start - a.taper_start) * t,
+        taper_end: a.taper_end + (b.taper_end - a.taper_end) * t,
+        cap: a.cap,
+        points
+    })
+}
+
+// The drawing a fraction of the way between two frames, if their strokes can be matched up
+pub fn tween_strokes(project: &Project, from: ObjPtr<Frame>, to: ObjPtr<Frame>, t: f32) -> Option<Vec<Stroke>> {
+    let from = project.frames.get(from)?;
+    let to = project.frames.get(to)?;
+    if from.strokes.len() != to.strokes.len() {
+        return None;
+    }
+    from.strokes.iter().zip(to.strokes.iter()).map(|(a, b)| tween_stroke(project, a.get(project), b.get(project), t)).collect()
+}
+
+impl Frame {
+
+    // The frame after this one on its layer, which a tweened frame moves towards
+    pub fn tween_target(project: &Project, frame_ptr: ObjPtr<Frame>) -> Option<ObjPtr<Frame>> {
+        let frame = project.frames.get(frame_ptr)?;
+        if !frame.tween {
+            return None;
+        }
+        let layer = project.layers.get(frame.layer)?;
+        Some(layer.get_frame_after(project, frame.time)?.make_ptr())
+    }
+
+    // The in-between drawing shown at some time after a tweened frame. Frames that can't be tweened keep showing their own drawing.
+    pub fn tween_at(project: &Project, frame_ptr: ObjPtr<Frame>, time: i32) -> Option<Vec<Stroke>> {
+        let frame = project.frames.get(frame_ptr)?;
+        let target_ptr = Self::tween_target(project, frame_ptr)?;
+        let target = project.frames.get(target_ptr)?;
+        if time <= frame.time || time >= target.time {
+            return None;
+        }
+        let t = ((time - frame.time) as f32) / ((target.time - frame.time) as f32);
+        tween_strokes(project, frame_ptr, target_ptr, frame.tween_easing.apply(t))
+    }
+
+    // Replaces a tween with real frames holding each of its in-between drawings
+    pub fn bake_tween(project: &mut Project, frame_ptr: ObjPtr<Frame>) -> Option<Vec<ObjAction>> {
+        let frame = project.frames.get(frame_ptr)?;
+        let (layer, time) = (frame.layer, frame.time);
+        let target = project.frames.get(Self::tween_target(project, frame_ptr)?)?;
+        let mut in_betweens = Vec::new();
+        for in_between_time in (time + 1)..target.time {
+            in_betweens.push((in_between_time, Self::tween_at(project, frame_ptr, in_between_time)?));
+        }
+
+        let mut acts = vec![Frame::set_tween(project, frame_ptr, false)?];
+        if Self::add_in_betweens(project, layer, in_betweens, &mut acts).is_none() {
+            Action::from_list(acts).undo(project);
+            return None;
+        }
+        Some(acts)
+    }
+
+    fn add_in_betweens(project: &mut Project, layer: ObjPtr<Layer>, in_betweens: Vec<(i32, Vec<Stroke>)>, acts: &mut Vec<ObjAction>) -> Option<()> {
+        for (in_between_time, strokes) in in_betweens {
+            let (in_between, act) = Frame::add(project, layer, Frame {
+                layer,
+                time: in_between_time,
+                ..Frame::default()
+            })?;
+            acts.push(act);
+            for stroke in strokes {
+                let (_, act) = Stroke::add(project, in_between, Stroke {
+                    frame: in_between,
+                    ..stroke
+                })?;
+                acts.push(act);
+            }
+        }
+        Some(())
+    }
+
+}
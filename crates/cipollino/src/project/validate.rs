@@ -0,0 +1,228 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{action::ObjAction, folder::Folder, frame::Frame, graphic::Graphic, layer::{Layer, LayerParent}, obj::{child_obj::ChildObj, obj_list::ObjListTrait, ObjPtr}, palette::PaletteColor, sound_instance::SoundInstance, stroke::{Stroke, StrokeColor}, symbol::SymbolInstance, Project};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Diagnostic {
+    // A stroke uses a palette color that no longer exists
+    DanglingPaletteColor {
+        stroke: ObjPtr<Stroke>,
+        color: ObjPtr<PaletteColor>,
+        backup: glam::Vec4
+    },
+    // A sound instance plays an audio file that is not in the project or missing on disk
+    MissingAudio {
+        sound_instance: ObjPtr<SoundInstance>
+    },
+    // Two frames on the same layer share the same time
+    DuplicateFrameTime {
+        layer: ObjPtr<Layer>,
+        frame: ObjPtr<Frame>,
+        time: i32
+    },
+    // A layer is (indirectly) its own parent
+    LayerParentCycle {
+        layer: ObjPtr<Layer>
+    },
+    // A symbol shows a graphic that was deleted
+    MissingSymbolGraphic {
+        symbol: ObjPtr<SymbolInstance>
+    }
+}
+
+impl Diagnostic {
+
+    pub fn message(&self, project: &Project) -> String {
+        match self {
+            Diagnostic::DanglingPaletteColor { .. } => "A stroke uses a palette color that was deleted.".to_owned(),
+            Diagnostic::MissingAudio { .. } => "A sound references an audio file that is missing.".to_owned(),
+            Diagnostic::DuplicateFrameTime { layer, time, .. } => {
+                let layer_name = project.layers.get(*layer).map(|layer| layer.name.clone()).unwrap_or_default();
+                format!("Layer \"{}\" has more than one frame at time {}.", layer_name, time + 1)
+            },
+            Diagnostic::LayerParentCycle { layer } => {
+                let layer_name = project.layers.get(*layer).map(|layer| layer.name.clone()).unwrap_or_default();
+                format!("Layer \"{}\" is inside of itself.", layer_name)
+            },
+            Diagnostic::MissingSymbolGraphic { .. } => "A symbol shows a graphic that was deleted.".to_owned(),
+        }
+    }
+
+    pub fn fix_description(&self) -> &'static str {
+        match self {
+            Diagnostic::DanglingPaletteColor { .. } => "Use the last known color",
+            Diagnostic::MissingAudio { .. } => "Delete the sound",
+            Diagnostic::DuplicateFrameTime { .. } => "Move the frame to the next free time",
+            Diagnostic::LayerParentCycle { .. } => "Move the layer to the open graphic",
+            Diagnostic::MissingSymbolGraphic { .. } => "Delete the symbol",
+        }
+    }
+
+    // Layers caught in a parent cycle are moved into the graphic given
+    pub fn fix(&self, project: &mut Project, graphic: ObjPtr<Graphic>) -> Option<ObjAction> {
+        match *self {
+            Diagnostic::DanglingPaletteColor { stroke, backup, .. } => Stroke::set_color(project, stroke, StrokeColor::Color(backup)),
+            Diagnostic::MissingAudio { sound_instance } => SoundInstance::delete(project, sound_instance),
+            Diagnostic::DuplicateFrameTime { layer, frame, time } => {
+                let layer = project.layers.get(layer)?;
+                let mut time = time;
+                while layer.get_frame_exactly_at(project, time).is_some() {
+                    time += 1;
+                }
+                Frame::set_time(project, frame, time)
+            },
+            Diagnostic::LayerParentCycle { layer } => {
+                project.graphics.get(graphic)?;
+                <Layer as ChildObj>::transfer(project, layer, LayerParent::Graphic(graphic))
+            },
+            Diagnostic::MissingSymbolGraphic { symbol } => SymbolInstance::delete(project, symbol),
+        }
+    }
+
+}
+
+impl Project {
+
+    // Checks the structural invariants of the loaded part of the project
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        let mut graphics = Vec::new();
+        let mut palette_colors = HashSet::new();
+        // Includes graphics that are not loaded yet
+        let mut graphic_ptrs = HashSet::new();
+        // If a palette is not loaded yet, we can't tell if a palette color is missing
+        let mut all_palettes_loaded = true;
+        self.validate_collect_folder(self.root_folder.make_ptr(), &mut graphics, &mut graphic_ptrs, &mut palette_colors, &mut all_palettes_loaded);
+
+        for graphic in graphics {
+            for layer in &graphic.layers {
+                self.validate_layer(layer.make_ptr(), &graphic_ptrs, &palette_colors, all_palettes_loaded, &mut diagnostics);
+            }
+        }
+
+        self.validate_layer_cycles(&mut diagnostics);
+
+        diagnostics
+    }
+
+    fn validate_collect_folder<'a>(&'a self, folder: ObjPtr<Folder>, graphics: &mut Vec<&'a Graphic>, graphic_ptrs: &mut HashSet<ObjPtr<Graphic>>, palette_colors: &mut HashSet<ObjPtr<PaletteColor>>, all_palettes_loaded: &mut bool) {
+        let folder = if let Some(folder) = self.folders.get(folder) {
+            folder
+        } else {
+            return;
+        };
+        for graphic in &folder.graphics {
+            graphic_ptrs.insert(graphic.make_ptr());
+            if let Some(graphic) = self.graphics.get(graphic.make_ptr()) {
+                graphics.push(graphic);
+            }
+        }
+        for palette in &folder.palettes {
+            if let Some(palette) = self.palettes.get(palette.make_ptr()) {
+                for color in &palette.colors {
+                    palette_colors.insert(color.make_ptr());
+                }
+            } else {
+                *all_palettes_loaded = false;
+            }
+        }
+        for subfolder in &folder.folders {
+            self.validate_collect_folder(subfolder.make_ptr(), graphics, graphic_ptrs, palette_colors, all_palettes_loaded);
+        }
+    }
+
+    fn validate_layer(&self, layer_ptr: ObjPtr<Layer>, graphic_ptrs: &HashSet<ObjPtr<Graphic>>, palette_colors: &HashSet<ObjPtr<PaletteColor>>, all_palettes_loaded: bool, diagnostics: &mut Vec<Diagnostic>) {
+        let layer = if let Some(layer) = self.layers.get(layer_ptr) {
+            layer
+        } else {
+            return;
+        };
+
+        let mut frame_times = HashMap::new();
+        for frame_box in &layer.frames {
+            let frame = frame_box.get(self);
+            if frame_times.contains_key(&frame.time) {
+                diagnostics.push(Diagnostic::DuplicateFrameTime {
+                    layer: layer_ptr,
+                    frame: frame_box.make_ptr(),
+                    time: frame.time
+                });
+            } else {
+                frame_times.insert(frame.time, frame_box.make_ptr());
+            }
+
+            if all_palettes_loaded {
+                for stroke_box in &frame.strokes {
+                    let stroke = stroke_box.get(self);
+                    if let StrokeColor::Palette { color, backup } = stroke.color {
+                        if !palette_colors.contains(&color) {
+                            diagnostics.push(Diagnostic::DanglingPaletteColor {
+                                stroke: stroke_box.make_ptr(),
+                                color,
+                                backup
+                            });
+                        }
+                    }
+                }
+            }
+
+            for symbol_box in &frame.symbols {
+                if !graphic_ptrs.contains(&symbol_box.get(self).graphic) {
+                    diagnostics.push(Diagnostic::MissingSymbolGraphic {
+                        symbol: symbol_box.make_ptr()
+                    });
+                }
+            }
+        }
+
+        for sound_box in &layer.sound_instances {
+            let sound = sound_box.get(self);
+            let exists = sound.audio.get(self).map(|audio| audio.absolute_path(self).exists()).unwrap_or(false);
+            if !exists {
+                diagnostics.push(Diagnostic::MissingAudio {
+                    sound_instance: sound_box.make_ptr()
+                });
+            }
+        }
+
+        for child in &layer.layers {
+            self.validate_layer(child.make_ptr(), graphic_ptrs, palette_colors, all_palettes_loaded, diagnostics);
+        }
+    }
+
+    fn validate_layer_cycles(&self, diagnostics: &mut Vec<Diagnostic>) {
+        let dropped = self.layers.dropped.lock().unwrap().clone();
+        for (key, layer) in &self.layers.objs {
+            if dropped.contains(key) {
+                continue;
+            }
+
+            // Walk up the parents. Each cycle is only reported once, for the layer with the smallest key in it.
+            let mut visited = HashSet::new();
+            let mut parent = layer.parent;
+            let mut smallest = true;
+            while let LayerParent::Layer(parent_ptr) = parent {
+                if parent_ptr.key == *key {
+                    if smallest {
+                        diagnostics.push(Diagnostic::LayerParentCycle {
+                            layer: ObjPtr::from_key(*key)
+                        });
+                    }
+                    break;
+                }
+                if !visited.insert(parent_ptr) {
+                    // Cycle higher up that does not include this layer
+                    break;
+                }
+                smallest &= parent_ptr.key > *key;
+                parent = if let Some(parent_layer) = self.layers.get(parent_ptr) {
+                    parent_layer.parent
+                } else {
+                    break;
+                };
+            }
+        }
+    }
+
+}
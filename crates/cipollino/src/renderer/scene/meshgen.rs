@@ -1,98 +1,177 @@
 
 use std::{f32::consts, sync::Arc};
 
-use crate::{project::{obj::{obj_list::ObjListTrait, ObjPtr}, stroke::{iter_bezier_segments, Stroke}, Project}, renderer::mesh::Mesh};
+use crate::{project::{obj::{obj_list::ObjListTrait, ObjPtr}, stroke::{iter_bezier_segments, iter_pressure_bezier_segments, Stroke, StrokeCap, StrokePoint}, Project}, renderer::mesh::Mesh, util::polygon::Polygon};
 
 use super::SceneRenderer;
 
-fn unfilled_mesh(project: &Project, stroke_ptr: ObjPtr<Stroke>, gl: &Arc<glow::Context>) -> Option<Mesh> {
-    let stroke = project.strokes.get(stroke_ptr)?;
-    let mut mesh = Mesh::new(vec![2], gl);
+fn taper_factor(dist: f32, taper: f32) -> f32 {
+    if taper <= 0.0 {
+        1.0
+    } else {
+        (dist / taper).min(1.0)
+    }
+}
 
-    let mut top_pts = Vec::new();
-    let mut btm_pts = Vec::new();
-    let r = stroke.r;
-    for segment in stroke.iter_bezier_segments() { 
+// Outline of a cap going around the end of the stroke from p0 to p1, including both
+pub fn cap_points(cap: StrokeCap, p0: glam::Vec2, p1: glam::Vec2) -> Vec<glam::Vec2> {
+    let center = (p0 + p1) * 0.5;
+    let r = (p0 - center).length();
+    if r < 0.0001 {
+        return vec![p0, p1];
+    }
+    let up = (p0 - center).normalize();
+    // Points away from the stroke
+    let left = glam::vec2(-up.y, up.x);
+
+    match cap {
+        StrokeCap::Round => {
+            let n = 20;
+            (0..n).map(|i| {
+                let a = consts::PI * (i as f32) / ((n - 1) as f32);
+                center + r * (up * a.cos() + left * a.sin())
+            }).collect()
+        },
+        StrokeCap::Square => vec![p0, p0 + left * r, p1 + left * r, p1],
+        StrokeCap::Butt => vec![p0, p1]
+    }
+}
+
+fn add_cap(verts: &mut Vec<f32>, idxs: &mut Vec<u32>, cap: StrokeCap, p0: glam::Vec2, p1: glam::Vec2) {
+    let pts = cap_points(cap, p0, p1);
+    let center = (p0 + p1) * 0.5;
+    let curr_idx = (verts.len() / 2) as u32;
+    verts.push(center.x);
+    verts.push(center.y);
+    for pt in &pts {
+        verts.push(pt.x);
+        verts.push(pt.y);
+    }
+    for i in 0..(pts.len().saturating_sub(1) as u32) {
+        idxs.push(curr_idx);
+        idxs.push(curr_idx + i + 1);
+        idxs.push(curr_idx + i + 2);
+    }
+}
+
+// The top and bottom edges of an unfilled chain, offset from the curve by the stroke's radius
+pub fn offset_curves(stroke: &Stroke, chain: &Vec<StrokePoint>) -> Option<(Vec<glam::Vec2>, Vec<glam::Vec2>)> {
+    let mut pts = Vec::new();
+    let mut rs = Vec::new();
+    let mut norms = Vec::new();
+    let mut tang = glam::vec2(1.0, 0.0);
+    for segment in iter_pressure_bezier_segments(chain) { 
         for i in 0..10 {
             let t = (i as f32) / 9.0; 
-            let pt = segment.sample(t); 
-            let tang = segment.dsample(t).normalize(); 
-            let norm = glam::vec2(-tang.y, tang.x); 
-
-            top_pts.push(pt + norm * r);
-            btm_pts.push(pt - norm * r);
+            let sample = segment.sample(t);
+            pts.push(sample.truncate()); 
+            rs.push(stroke.r * sample.z);
+            let next_tang = segment.dsample(t).truncate().normalize_or_zero(); 
+            if next_tang != glam::Vec2::ZERO {
+                tang = next_tang;
+            }
+            norms.push(glam::vec2(-tang.y, tang.x)); 
         }
     }
+    if pts.is_empty() {
+        return None;
+    }
 
-    let mut curr_idx = 0;
-    let mut verts = Vec::new();
-    let mut idxs = Vec::new();
-    if top_pts.len() > 0 {
-        for i in 0..(top_pts.len() - 1) {
-            let t0 = top_pts[i];
-            let t1 = top_pts[i + 1];
-            let b0 = btm_pts[i];
-            let b1 = btm_pts[i + 1];
-
-            verts.push(t0.x);
-            verts.push(t0.y);
-            verts.push(t1.x);
-            verts.push(t1.y);
-            verts.push(b0.x);
-            verts.push(b0.y);
-            verts.push(b1.x);
-            verts.push(b1.y);
-
-            idxs.push(curr_idx + 0);
-            idxs.push(curr_idx + 1);
-            idxs.push(curr_idx + 2);
-
-            idxs.push(curr_idx + 1);
-            idxs.push(curr_idx + 2);
-            idxs.push(curr_idx + 3);
-
-            curr_idx += 4;
-        }
+    // Distance along the stroke, used for tapering
+    let mut dists = vec![0.0];
+    for i in 1..pts.len() {
+        dists.push(dists[i - 1] + (pts[i] - pts[i - 1]).length());
     }
+    let len = *dists.last().unwrap();
 
-    // Stroke caps
-    if top_pts.len() > 0 {
+    let mut top_pts = Vec::new();
+    let mut btm_pts = Vec::new();
+    for i in 0..pts.len() {
+        let r = rs[i] * taper_factor(dists[i], stroke.taper_start) * taper_factor(len - dists[i], stroke.taper_end);
+        top_pts.push(pts[i] + norms[i] * r);
+        btm_pts.push(pts[i] - norms[i] * r);
+    }
+    Some((top_pts, btm_pts))
+}
 
-        let mut add_cap = |p0: glam::Vec2, p1: glam::Vec2| {
-            let center = (p0 + p1) * 0.5;
-            let r = (p0 - center).length();
-            let up = (p0 - center).normalize();
-            let left = glam::vec2(-up.y, up.x);
+fn add_unfilled_chain(stroke: &Stroke, chain: &Vec<StrokePoint>, verts: &mut Vec<f32>, idxs: &mut Vec<u32>) {
+    let Some((top_pts, btm_pts)) = offset_curves(stroke, chain) else { return; };
+
+    let mut curr_idx = (verts.len() / 2) as u32;
+    for i in 0..(top_pts.len() - 1) {
+        let t0 = top_pts[i];
+        let t1 = top_pts[i + 1];
+        let b0 = btm_pts[i];
+        let b1 = btm_pts[i + 1];
+
+        verts.push(t0.x);
+        verts.push(t0.y);
+        verts.push(t1.x);
+        verts.push(t1.y);
+        verts.push(b0.x);
+        verts.push(b0.y);
+        verts.push(b1.x);
+        verts.push(b1.y);
+
+        idxs.push(curr_idx + 0);
+        idxs.push(curr_idx + 1);
+        idxs.push(curr_idx + 2);
+
+        idxs.push(curr_idx + 1);
+        idxs.push(curr_idx + 2);
+        idxs.push(curr_idx + 3);
+
+        curr_idx += 4;
+    }
 
-            verts.push(center.x);
-            verts.push(center.y);
-            curr_idx += 1;
-            let n = 20;
-            for i in 0..n {
-                let a = consts::PI * (i as f32) / 19.0;
-                let pt = center + r * (up * a.cos() + left * a.sin());
-                verts.push(pt.x);
-                verts.push(pt.y);
-            }
-            for i in 0..(n - 1) {
-                idxs.push(curr_idx - 1);
-                idxs.push(curr_idx + i);
-                idxs.push(curr_idx + i + 1);
+    // Stroke caps
+    add_cap(verts, idxs, stroke.cap, top_pts[0], btm_pts[0]);
+    add_cap(verts, idxs, stroke.cap, *btm_pts.last().unwrap(), *top_pts.last().unwrap());
+}
+
+// The closed outline of an unfilled stroke, one ring per chain, going along the top edge, around the end cap, back along the bottom edge and around the start cap
+pub fn unfilled_outline(stroke: &Stroke) -> Polygon {
+    let mut polygon = Vec::new();
+    for chain in &stroke.points {
+        let Some((top_pts, btm_pts)) = offset_curves(stroke, chain) else { continue; };
+        let mut ring: Vec<glam::Vec2> = Vec::new();
+        let end_cap = cap_points(stroke.cap, *btm_pts.last().unwrap(), *top_pts.last().unwrap());
+        let start_cap = cap_points(stroke.cap, top_pts[0], btm_pts[0]);
+        let pts = top_pts.iter()
+            .chain(end_cap.iter().rev())
+            .chain(btm_pts.iter().rev())
+            .chain(start_cap.iter().rev());
+        for pt in pts {
+            // Tapered ends and segment joins produce repeated points
+            if ring.last().map(|last| (*last - *pt).length() < 0.0001).unwrap_or(false) {
+                continue;
             }
-            curr_idx += n;
-        };
+            ring.push(*pt);
+        }
+        while ring.len() > 1 && (ring[0] - ring[ring.len() - 1]).length() < 0.0001 {
+            ring.pop();
+        }
+        if ring.len() >= 3 {
+            polygon.push(ring);
+        }
+    }
+    polygon
+}
 
-        add_cap(top_pts[0], btm_pts[0]);
-        add_cap(*btm_pts.last().unwrap(), *top_pts.last().unwrap());
-        
+fn unfilled_mesh(stroke: &Stroke, gl: &Arc<glow::Context>) -> Mesh {
+    let mut mesh = Mesh::new(vec![2], gl);
+
+    let mut verts = Vec::new();
+    let mut idxs = Vec::new();
+    for chain in &stroke.points {
+        add_unfilled_chain(stroke, chain, &mut verts, &mut idxs);
     }
 
     mesh.upload(&verts, &idxs, gl);
-    Some(mesh)
+    mesh
 }
 
-fn filled_mesh(project: &Project, stroke_ptr: ObjPtr<Stroke>, gl: &Arc<glow::Context>) -> Option<Mesh> {
-    let stroke = project.strokes.get(stroke_ptr)?;
+fn filled_mesh(stroke: &Stroke, gl: &Arc<glow::Context>) -> Mesh {
     let mut mesh = Mesh::new(vec![2], gl);
 
     let mut verts = Vec::new();
@@ -125,7 +204,11 @@ fn filled_mesh(project: &Project, stroke_ptr: ObjPtr<Stroke>, gl: &Arc<glow::Con
 
     mesh.upload(&verts, &idxs, gl);
     
-    Some(mesh)
+    mesh
+}
+
+pub fn stroke_mesh(stroke: &Stroke, gl: &Arc<glow::Context>) -> Mesh {
+    if stroke.filled { filled_mesh(stroke, gl) } else { unfilled_mesh(stroke, gl) }
 }
 
 impl SceneRenderer {
@@ -133,12 +216,9 @@ impl SceneRenderer {
     pub fn get_mesh<'a>(&'a mut self, project: &Project, stroke_ptr: ObjPtr<Stroke>, gl: &Arc<glow::Context>) -> Option<&'a Mesh> {
         if !self.stroke_meshes.contains_key(&stroke_ptr) {
             if let Some(stroke) = project.strokes.get(stroke_ptr) {
-                let stroke_filled = stroke.filled;
-                let mesh = if stroke_filled { filled_mesh(project, stroke_ptr, gl) } else { unfilled_mesh(project, stroke_ptr, gl) };
-                if let Some(mesh) = mesh {
-                    if let Some(prev_mesh) = self.stroke_meshes.insert(stroke_ptr, mesh) {
-                        prev_mesh.delete(gl);
-                    }
+                let mesh = stroke_mesh(stroke, gl);
+                if let Some(prev_mesh) = self.stroke_meshes.insert(stroke_ptr, mesh) {
+                    prev_mesh.delete(gl);
                 }
             }
         }
@@ -1,13 +1,13 @@
 
-mod meshgen;
+pub mod meshgen;
 mod fb_manager;
 
 use std::{collections::HashMap, sync::Arc};
 
-use glam::vec4;
+use glam::{vec4, Mat4, Vec4};
 use glow::{Context, HasContext};
 
-use crate::project::{graphic::Graphic, layer::{BlendingMode, Layer, LayerKind}, obj::{obj_list::ObjListTrait, ObjBox, ObjPtr}, stroke::Stroke, Project};
+use crate::project::{frame::Frame, graphic::Graphic, layer::{BlendingMode, Layer, LayerKind}, layer_transform::LayerTransform, obj::{obj_event::{ObjEvent, ObjEventReceiver}, obj_list::ObjListTrait, ObjBox, ObjPtr}, palette::PaletteColor, stroke::Stroke, symbol::SymbolInstance, Project};
 
 use self::fb_manager::FramebufferManager;
 
@@ -45,9 +45,21 @@ pub struct SceneRenderer {
     pub screen_shader: Shader,
 
     stroke_meshes: HashMap<ObjPtr<Stroke>, Mesh>,
+    stroke_events: Option<ObjEventReceiver<Stroke>>,
+    // In-between drawings of tweened frames by the frame and the time they're shown at, None if the frame's strokes can't be tweened
+    tween_meshes: HashMap<(ObjPtr<Frame>, i32), Option<Vec<(Stroke, Mesh)>>>,
+    frame_events: Option<ObjEventReceiver<Frame>>,
+    palette_color_events: Option<ObjEventReceiver<PaletteColor>>,
 
     framebuffers: FramebufferManager,
 
+    proj_view: Mat4,
+    // The graphics whose contents are being rendered, so that symbols containing themselves are skipped
+    symbol_stack: Vec<ObjPtr<Graphic>>,
+    // Combined transform and tint of the symbols being rendered
+    symbol_trans: Mat4,
+    symbol_tint: Vec4,
+
     blend_normal: Shader,
     blend_add: Shader,
     blend_screen: Shader,
@@ -129,9 +141,18 @@ impl SceneRenderer {
             screen_shader: Shader::new(include_str!("shaders/screen_vs.glsl"), include_str!("shaders/screen_fs.glsl"), gl),
 
             stroke_meshes: HashMap::new(),
+            stroke_events: None,
+            tween_meshes: HashMap::new(),
+            frame_events: None,
+            palette_color_events: None,
 
             framebuffers: FramebufferManager::new(),
 
+            proj_view: Mat4::IDENTITY,
+            symbol_stack: Vec::new(),
+            symbol_trans: Mat4::IDENTITY,
+            symbol_tint: Vec4::ONE,
+
             blend_normal: blend_mode_shader!("normal"),
             blend_add: blend_mode_shader!("add"),
             blend_screen: blend_mode_shader!("screen"),
@@ -162,14 +183,39 @@ impl SceneRenderer {
             return;
         };
         let filled = stroke.filled;
-        let color = color_override.unwrap_or(stroke.color.get_color(project));
+        let color = color_override.unwrap_or(stroke.color.get_color(project) * self.symbol_tint);
+        self.render_stroke_with(filled, color, |renderer| renderer.render_stroke_mesh(project, stroke_ptr, gl), gl);
+    }
+
+    // Renders the in-between drawing of a tweened frame, returning false if there isn't one at this time
+    fn render_tween(&mut self, project: &Project, frame: ObjPtr<Frame>, time: i32, color_override: Option<glam::Vec4>, gl: &Arc<Context>) -> bool {
+        if Frame::tween_target(project, frame).is_none() {
+            return false;
+        }
+        let tween = self.tween_meshes.remove(&(frame, time)).unwrap_or_else(|| {
+            Frame::tween_at(project, frame, time).map(|strokes| strokes.into_iter().map(|stroke| {
+                let mesh = meshgen::stroke_mesh(&stroke, gl);
+                (stroke, mesh)
+            }).collect())
+        });
+        if let Some(strokes) = &tween {
+            for (stroke, mesh) in strokes {
+                let color = color_override.unwrap_or(stroke.color.get_color(project) * self.symbol_tint);
+                self.render_stroke_with(stroke.filled, color, |_| mesh.render(gl), gl);
+            }
+        }
+        let rendered = tween.is_some();
+        self.tween_meshes.insert((frame, time), tween);
+        rendered
+    }
 
+    fn render_stroke_with<F>(&mut self, filled: bool, color: Vec4, render_mesh: F, gl: &Arc<Context>) where F: Fn(&mut SceneRenderer) {
         if !filled {
             unsafe {
                 gl.clear(glow::DEPTH_BUFFER_BIT);
             }
             self.flat_color_shader.set_vec4("uColor", glam::vec4(color.x, color.y, color.z, color.w), gl);
-            self.render_stroke_mesh(project, stroke_ptr, gl);
+            render_mesh(self);
         } else {
             self.flat_color_shader.set_vec4("uColor", glam::vec4(color.x, color.y, color.z, 1.0), gl);
             unsafe {
@@ -179,7 +225,7 @@ impl SceneRenderer {
                 gl.stencil_func(glow::NEVER, 1, 0xFF);
                 gl.stencil_op(glow::INVERT, glow::INVERT, glow::INVERT);
             }
-            self.render_stroke_mesh(project, stroke_ptr, gl);
+            render_mesh(self);
             unsafe {
                 gl.stencil_func(glow::EQUAL, 0xFF, 0xFF);
                 gl.stencil_op(glow::KEEP, glow::KEEP, glow::KEEP);
@@ -187,7 +233,7 @@ impl SceneRenderer {
                 gl.clear(glow::DEPTH_BUFFER_BIT);
             }
             self.flat_color_shader.set_vec4("uColor", glam::vec4(color.x, color.y, color.z, color.w), gl);
-            self.render_stroke_mesh(project, stroke_ptr, gl);
+            render_mesh(self);
             unsafe {
                 gl.disable(glow::STENCIL_TEST);
             }
@@ -197,8 +243,9 @@ impl SceneRenderer {
     fn render_onion_skin<'a, I>(&mut self, project: &Project, time: i32, onion_before: i32, onion_after: i32, layer_iter: I, gl: &Arc<Context>) where I: Iterator<Item = &'a &'a ObjBox<Layer>> {
         let mut onion_before_strokes = vec![Vec::new(); onion_before as usize];
         let mut onion_after_strokes = vec![Vec::new(); onion_before as usize];
-        for layer in layer_iter { 
-            let layer = layer.get(project);
+        for layer_box in layer_iter { 
+            let layer = layer_box.get(project);
+            let trans = LayerTransform::world_matrix(project, layer_box.make_ptr(), time);
             if let Some(frame) = layer.get_frame_at(project, time) {
                 if onion_before != 0 {
                     let mut curr_time = frame.get(project).time;
@@ -208,7 +255,7 @@ impl SceneRenderer {
                                 if stroke.get(&project).filled {
                                     continue;
                                 }
-                                onion_before_strokes[i as usize].push(stroke.make_ptr());
+                                onion_before_strokes[i as usize].push((stroke.make_ptr(), trans));
                             }
                             
                             curr_time = frame.get(project).time;
@@ -223,7 +270,7 @@ impl SceneRenderer {
                                 if stroke.get(project).filled {
                                     continue;
                                 }
-                                onion_after_strokes[i as usize].push(stroke.make_ptr());
+                                onion_after_strokes[i as usize].push((stroke.make_ptr(), trans));
                             }
                             curr_time = frame.get(project).time;
                         }
@@ -237,7 +284,8 @@ impl SceneRenderer {
 
         let mut alpha = initial_alpha * alpha_decay.powi(onion_before - 1);
         for strokes in onion_before_strokes.iter().rev() {
-            for stroke in strokes {
+            for (stroke, trans) in strokes {
+                self.set_symbol_trans(*trans, gl);
                 self.render_stroke(project, *stroke, Some(vec4(1.0, 0.3, 1.0, alpha)), gl);
             }
             alpha /= alpha_decay;
@@ -245,18 +293,25 @@ impl SceneRenderer {
 
         let mut alpha = initial_alpha * alpha_decay.powi(onion_before - 1);
         for strokes in onion_after_strokes.iter().rev() {
-            for stroke in strokes {
+            for (stroke, trans) in strokes {
+                self.set_symbol_trans(*trans, gl);
                 self.render_stroke(project, *stroke, Some(vec4(0.3, 1.0, 1.0, alpha)), gl);
             }
             alpha /= alpha_decay;
         }
+        self.set_symbol_trans(Mat4::IDENTITY, gl);
 
     }
 
     fn render_picking<'a, I>(&mut self, project: &Project, time: i32, layer_iter: I, color_key_map: &mut Vec<ObjPtr<Stroke>>, gl: &Arc<Context>) where I: Iterator<Item = &'a &'a ObjBox<Layer>> {
-        for layer in layer_iter { 
-            let layer = layer.get(project);
+        for layer_box in layer_iter { 
+            let layer = layer_box.get(project);
+            self.set_symbol_trans(LayerTransform::world_matrix(project, layer_box.make_ptr(), time), gl);
             if let Some(frame) = layer.get_frame_at(project, time) {
+                // In-between drawings can't be picked, but still hide what's under them
+                if self.render_tween(project, frame.make_ptr(), time, Some(glam::vec4(0.0, 0.0, 0.0, 1.0)), gl) {
+                    continue;
+                }
                 for stroke in &frame.get(project).strokes  {
                     let mut color = 0 as u32;
                     for i in 0..color_key_map.len() {
@@ -278,6 +333,7 @@ impl SceneRenderer {
                 }
             }
         }
+        self.set_symbol_trans(Mat4::IDENTITY, gl);
     }
 
     fn get_shown_layers<'a>(&mut self, project: &'a Project, layers: &'a Vec<ObjBox<Layer>>, res_layers: &mut Vec<&'a ObjBox<Layer>>) {
@@ -294,8 +350,8 @@ impl SceneRenderer {
         }
     }
 
-    fn render_layer_contents_with_blending<F>(&mut self, project: &Project, w: u32, h: u32,  layer: &Layer, prev_fb: &Framebuffer, render_contents: F, gl: &Arc<Context>) where F: FnOnce(&mut SceneRenderer, &Project, &Layer, &Framebuffer) {
-        if layer.requires_offscreen_render() {
+    fn render_layer_contents_with_blending<F>(&mut self, project: &Project, w: u32, h: u32,  layer: &Layer, opacity: f32, prev_fb: &Framebuffer, render_contents: F, gl: &Arc<Context>) where F: FnOnce(&mut SceneRenderer, &Project, &Layer, &Framebuffer) {
+        if layer.requires_offscreen_render() || opacity < 0.999 {
             let mut bottom = self.framebuffers.alloc(w, h, gl);
             bottom.copy_from(prev_fb, gl);
 
@@ -319,7 +375,7 @@ impl SceneRenderer {
             blend_shader.enable(gl);
             blend_shader.set_int("uTopLayer", 0, gl);
             blend_shader.set_int("uBottomLayer", 1, gl);
-            blend_shader.set_float("uLayerAlpha", layer.alpha, gl);
+            blend_shader.set_float("uLayerAlpha", layer.alpha * opacity, gl);
             unsafe {
                 gl.disable(glow::DEPTH_TEST);
             }
@@ -330,29 +386,68 @@ impl SceneRenderer {
         }
     }
     
-    fn render_layer(&mut self, project: &Project, w: u32, h: u32, time: i32, layer: &Layer, prev_fb: &Framebuffer, gl: &Arc<Context>) {
-        self.render_layer_contents_with_blending(project, w, h, layer, prev_fb, |renderer: &mut SceneRenderer, project: &Project, layer: &Layer, _: &Framebuffer| {
+    fn render_layer(&mut self, project: &Project, w: u32, h: u32, time: i32, layer: &Layer, opacity: f32, prev_fb: &Framebuffer, gl: &Arc<Context>) {
+        self.render_layer_contents_with_blending(project, w, h, layer, opacity, prev_fb, |renderer: &mut SceneRenderer, project: &Project, layer: &Layer, fb: &Framebuffer| {
             if let Some(frame) = layer.get_frame_at(project, time) {
-                for stroke in &frame.get(project).strokes {
-                    renderer.render_stroke(project, stroke.make_ptr(), None, gl);
+                if !renderer.render_tween(project, frame.make_ptr(), time, None, gl) {
+                    for stroke in &frame.get(project).strokes {
+                        renderer.render_stroke(project, stroke.make_ptr(), None, gl);
+                    }
+                }
+                for symbol in &frame.get(project).symbols {
+                    renderer.render_symbol(project, w, h, time, symbol.get(project), fb, gl);
                 }
             }
         }, gl);
     }
 
+    fn set_symbol_trans(&mut self, trans: Mat4, gl: &Arc<Context>) {
+        self.symbol_trans = trans;
+        self.flat_color_shader.enable(gl);
+        self.flat_color_shader.set_mat4("uTrans", &(self.proj_view * trans), gl);
+    }
+
+    fn render_symbol(&mut self, project: &Project, w: u32, h: u32, time: i32, symbol: &SymbolInstance, prev_fb: &Framebuffer, gl: &Arc<Context>) {
+        if self.symbol_stack.contains(&symbol.graphic) {
+            return;
+        }
+        let (Some(gfx), Some(symbol_time)) = (project.graphics.get(symbol.graphic), symbol.graphic_time(project, time)) else { return; };
+
+        let prev_trans = self.symbol_trans;
+        let prev_tint = self.symbol_tint;
+        self.set_symbol_trans(prev_trans * symbol.transform, gl);
+        self.symbol_tint = prev_tint * symbol.tint;
+        self.symbol_stack.push(symbol.graphic);
+
+        self.render_layers(project, w, h, symbol_time, &gfx.layers, prev_fb, gl);
+
+        self.symbol_stack.pop();
+        self.symbol_tint = prev_tint;
+        self.set_symbol_trans(prev_trans, gl);
+    }
+
     fn render_layers(&mut self, project: &Project, w: u32, h: u32, time: i32, layers: &Vec<ObjBox<Layer>>, prev_fb: &Framebuffer, gl: &Arc<Context>) { 
-        for layer in layers.iter().rev() {
-            let layer = layer.get(project);
+        for layer_box in layers.iter().rev() {
+            let layer = layer_box.get(project);
             if !layer.show {
                 continue;
             }
+            let transform = LayerTransform::at_time(project, layer_box.make_ptr(), time);
+            let opacity = transform.map(|transform| transform.opacity).unwrap_or(1.0);
+            let prev_trans = self.symbol_trans;
+            if let Some(transform) = transform {
+                self.set_symbol_trans(prev_trans * transform.matrix(), gl);
+            }
             if layer.kind == LayerKind::Animation {
-                self.render_layer(project, w, h, time, layer, prev_fb, gl); 
+                self.render_layer(project, w, h, time, layer, opacity, prev_fb, gl); 
             } else if layer.kind == LayerKind::Group {
-                self.render_layer_contents_with_blending(project, w, h, layer, prev_fb, |renderer: &mut SceneRenderer, project: &Project, layer: &Layer, fb: &Framebuffer| {
+                self.render_layer_contents_with_blending(project, w, h, layer, opacity, prev_fb, |renderer: &mut SceneRenderer, project: &Project, layer: &Layer, fb: &Framebuffer| {
                     renderer.render_layers(project, w, h, time, &layer.layers, fb, gl);
                 }, gl);
             }
+            if transform.is_some() {
+                self.set_symbol_trans(prev_trans, gl);
+            }
         }
     }
 
@@ -366,6 +461,8 @@ impl SceneRenderer {
 
         cam_pos: glam::Vec2,
         cam_size: f32,
+        // Measured in degrees
+        cam_rot: f32,
 
         project: &mut Project,
         gfx: ObjPtr<Graphic>,
@@ -377,12 +474,34 @@ impl SceneRenderer {
         gl: &Arc<Context>
     ) -> Option<glam::Mat4> {
 
-        for stroke in &project.remeshes_needed {
-            if let Some(mesh) = self.stroke_meshes.remove(stroke) {
+        let stroke_events = ObjEventReceiver::poll(&mut self.stroke_events, &mut project.strokes);
+        let strokes_changed = stroke_events.as_ref().map(|events| !events.is_empty()).unwrap_or(true);
+        if let Some(events) = stroke_events {
+            for event in events {
+                // Changing the color doesn't affect the mesh
+                if let ObjEvent::Modified(_, "color") = event {
+                    continue;
+                }
+                if let Some(mesh) = self.stroke_meshes.remove(&event.ptr()) {
+                    mesh.delete(gl);
+                }
+            }
+        } else {
+            for (_, mesh) in self.stroke_meshes.drain() {
                 mesh.delete(gl);
             }
         }
-        project.remeshes_needed.clear();
+
+        // In-betweens depend on the strokes and frames on both ends of the tween, and on the palette colors they blend
+        let frames_changed = ObjEventReceiver::poll(&mut self.frame_events, &mut project.frames).map(|events| !events.is_empty()).unwrap_or(true);
+        let colors_changed = ObjEventReceiver::poll(&mut self.palette_color_events, &mut project.palette_colors).map(|events| !events.is_empty()).unwrap_or(true);
+        if strokes_changed || frames_changed || colors_changed {
+            for (_, tween) in self.tween_meshes.drain() {
+                for (_, mesh) in tween.into_iter().flatten() {
+                    mesh.delete(gl);
+                }
+            }
+        }
 
         fb.resize(w, h, gl);
         fb.render_to(gl);
@@ -397,10 +516,14 @@ impl SceneRenderer {
 
         let aspect = (w as f32) / (h as f32);
         let proj = glam::Mat4::orthographic_rh_gl(-aspect * cam_size, aspect * cam_size, -cam_size, cam_size, -1.0, 1.0);
-        let view = glam::Mat4::from_translation(-glam::vec3(cam_pos.x, cam_pos.y, 0.0));
+        let view = glam::Mat4::from_rotation_z(-cam_rot.to_radians()) * glam::Mat4::from_translation(-glam::vec3(cam_pos.x, cam_pos.y, 0.0));
         let proj_view = proj * view;
         self.flat_color_shader.enable(gl);
         self.flat_color_shader.set_mat4("uTrans", &proj_view, gl);
+        self.proj_view = proj_view;
+        self.symbol_stack = vec![gfx];
+        self.symbol_trans = Mat4::IDENTITY;
+        self.symbol_tint = Vec4::ONE;
 
         let gfx = project.graphics.get(gfx)?;
         let mut layers = Vec::new();
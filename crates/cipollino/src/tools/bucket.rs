@@ -1,25 +1,142 @@
 
 use std::{sync::Arc, collections::{VecDeque, HashSet, HashMap}};
 
-use glam::{Vec2, vec2};
-use crate::{editor::{state::EditorState, EditorSystems}, keybind, panels::scene::ScenePanel, project::{action::Action, layer::Layer, obj::{child_obj::ChildObj, obj_list::ObjListTrait}, stroke::{Stroke, StrokePoint}}, util::{curve::fit_curve, geo::LineSegment}};
+use glam::{Vec2, vec2, vec4};
+use crate::{editor::{state::EditorState, EditorSystems}, keybind, panels::scene::{overlay::OverlayRenderer, ScenePanel}, project::{action::{Action, ObjAction}, layer::{Layer, LayerParent}, obj::{child_obj::ChildObj, obj_event::{ObjEvent, ObjEventReceiver}, obj_list::ObjListTrait, ObjPtr}, stroke::{iter_bezier_segments, Stroke, StrokePoint}, Project}, util::{curve::fit_curve, geo::LineSegment}};
 
-use super::{Tool, active_frame};
+use super::{Tool, active_frame, symmetry_panel};
 
 pub struct Bucket {
-
+    // Gaps between stroke ends up to this size are closed off before filling
+    gap_tolerance: f32,
+    max_area: f32,
+    // Add the fill below the strokes already in the frame
+    paint_behind: bool,
+    // Only use strokes on layers marked as fill references as boundaries
+    reference_layers_only: bool,
+    preview_closures: Vec<(Vec2, Vec2)>,
+    // What the preview was found from, so it's only recomputed once the strokes or the tolerance change
+    preview_source: Option<(f32, Vec<ObjPtr<Stroke>>)>,
+    stroke_events: Option<ObjEventReceiver<Stroke>>
 }
 
 impl Bucket {
 
     pub fn new() -> Self {
         Self {
+            gap_tolerance: 0.0,
+            max_area: 4000000.0,
+            paint_behind: true,
+            reference_layers_only: false,
+            preview_closures: Vec::new(),
+            preview_source: None,
+            stroke_events: None
+        }
+    }
 
+    fn boundary_strokes(&self, state: &EditorState) -> Vec<ObjPtr<Stroke>> {
+        let strokes = state.visible_strokes_in_layer_space(false);
+        if !self.reference_layers_only {
+            return strokes;
+        }
+        let reference_strokes: Vec<ObjPtr<Stroke>> = strokes.iter().copied().filter(|stroke| {
+            state.project.strokes.get(*stroke)
+                .and_then(|stroke| state.project.frames.get(stroke.frame))
+                .map(|frame| is_fill_reference(&state.project, frame.layer))
+                .unwrap_or(false)
+        }).collect();
+        // Without any reference layers, everything is used
+        if reference_strokes.is_empty() {
+            strokes
+        } else {
+            reference_strokes
         }
     }
 
 }
 
+// Layers inside a reference group are references too
+fn stroke_locked(project: &Project, stroke: ObjPtr<Stroke>) -> bool {
+    project.strokes.get(stroke)
+        .and_then(|stroke| project.frames.get(stroke.frame))
+        .map(|frame| Layer::locked(project, frame.layer))
+        .unwrap_or(false)
+}
+
+fn is_fill_reference(project: &Project, layer: ObjPtr<Layer>) -> bool {
+    let Some(layer) = project.layers.get(layer) else { return false; };
+    if layer.fill_reference {
+        return true;
+    }
+    match layer.parent {
+        LayerParent::Layer(parent) => is_fill_reference(project, parent),
+        LayerParent::Graphic(_) => false
+    }
+}
+
+// Finds the segments that virtually close the gaps between the ends of unfilled strokes and nearby lines
+pub fn gap_closures(project: &Project, strokes: &Vec<ObjPtr<Stroke>>, tolerance: f32) -> Vec<(Vec2, Vec2)> {
+    let mut closures = Vec::new();
+    if tolerance <= 0.0 {
+        return closures;
+    }
+
+    // Each chain of each stroke is a separate curve
+    let mut endpoints = Vec::new();
+    let mut curves = Vec::new();
+    for stroke in strokes {
+        let Some(stroke) = project.strokes.get(*stroke) else { continue; };
+        for chain in &stroke.points {
+            let curve = curves.len();
+            curves.push(iter_bezier_segments(chain).collect::<Vec<_>>());
+            if !stroke.filled && chain.len() >= 2 {
+                endpoints.push((chain.first().unwrap().pt, curve));
+                endpoints.push((chain.last().unwrap().pt, curve));
+            }
+        }
+    }
+
+    let mut closed = vec![false; endpoints.len()];
+    for i in 0..endpoints.len() {
+        for j in (i + 1)..endpoints.len() {
+            let dist = (endpoints[i].0 - endpoints[j].0).length();
+            if dist <= tolerance {
+                closures.push((endpoints[i].0, endpoints[j].0));
+                closed[i] = true;
+                closed[j] = true;
+            }
+        }
+    }
+
+    // Ends that aren't near another end can still be close to the middle of another line
+    for (i, (pt, curve)) in endpoints.iter().enumerate() {
+        if closed[i] {
+            continue;
+        }
+        let mut best = None;
+        let mut best_dist = tolerance;
+        for (other, segments) in curves.iter().enumerate() {
+            if other == *curve {
+                continue;
+            }
+            for segment in segments {
+                let nearest = segment.sample(segment.nearest_t(*pt));
+                let dist = (nearest - *pt).length();
+                if dist <= best_dist {
+                    best_dist = dist;
+                    best = Some(nearest);
+                }
+            }
+        }
+        if let Some(nearest) = best {
+            closures.push((*pt, nearest));
+        }
+    }
+
+    closures.retain(|(a, b)| (*a - *b).length() > 0.01);
+    closures
+}
+
 #[derive(Clone, Copy)]
 struct BoundarySegment {
     a: Vec2,
@@ -35,15 +152,21 @@ impl Tool for Bucket {
 
         // If we click on an existing stroke, let's just change its color
         if let Some(stroke) = scene.sample_pick(mouse_pos, gl) {
-            if let Some(stroke) = state.project.strokes.get(stroke) {
-                if let Some(frame) = state.project.frames.get(stroke.frame) {
-                    if Layer::locked(&state.project, frame.layer) {
-                        return;
+            if stroke_locked(&state.project, stroke) {
+                return;
+            }
+            // Along with the strokes under the mirrored points
+            let mut strokes = vec![stroke];
+            for pt in state.symmetry.copy_points(mouse_pos, scene.layer_mat) {
+                if let Some(other) = scene.sample_pick(pt, gl) {
+                    if !strokes.contains(&other) && !stroke_locked(&state.project, other) {
+                        strokes.push(other);
                     }
                 }
             }
-            if let Some(act) = Stroke::set_color(&mut state.project, stroke, state.color) {
-                state.actions.add(Action::from_single(act));
+            let acts: Vec<ObjAction> = strokes.iter().filter_map(|stroke| Stroke::set_color(&mut state.project, *stroke, state.color)).collect();
+            if !acts.is_empty() {
+                state.actions.add(Action::from_list(acts));
                 return;
             }
         }
@@ -68,7 +191,8 @@ impl Tool for Bucket {
         };
 
         // Step 0: Precompute boundary line segments
-        let visible_strokes = state.visible_strokes(false); 
+        let visible_strokes = self.boundary_strokes(state);
+        let closures = gap_closures(&state.project, &visible_strokes, self.gap_tolerance);
         let mut boundary_segments = HashMap::new();
         let chunk_size = 20.0;
 
@@ -153,6 +277,15 @@ impl Tool for Bucket {
             }
         }
 
+        for (a, b) in closures {
+            add_boundary_segment(BoundarySegment {
+                a,
+                b,
+                src_a: a,
+                src_b: b
+            });
+        }
+
         // Step 1: Floodfill using BFS to find the boundary points
         let mut bfs = VecDeque::new();
         let mut vis = HashSet::new();
@@ -166,7 +299,17 @@ impl Tool for Bucket {
             [ 0, -1],
         ];
         let mut boundary = HashMap::new(); 
+        let max_cells = (self.max_area / (grid_size * grid_size)) as usize;
         while let Some(curr) = bfs.pop_front() {
+            // Give up before a leaky fill takes forever
+            if vis.len() > max_cells {
+                for act in acts.iter().rev() {
+                    act.undo(&mut state.project);
+                }
+                state.error_messages.push("The area to fill is too big. Try closing the gaps in the line art or increasing the gap closing tolerance.".to_owned());
+                return;
+            }
+
             let curr_unsnapped = unsnap_coords(curr);
             'offset: for [x_off, y_off] in offsets {
                 let next = (curr.0 + x_off, curr.1 + y_off);
@@ -340,26 +483,72 @@ impl Tool for Bucket {
                 pts.push(StrokePoint {
                     a,
                     pt: p,
-                    b
+                    b,
+                    pressure: 1.0
                 }); 
             }
             all_pts.push(pts);
         }
 
-        if let Some((_, act)) = Stroke::add_at_idx(&mut state.project, frame, Stroke {
+        let fill = Stroke {
             frame: frame,
             color: state.color,
             r: 0.05,
             filled: true,
-            points: all_pts
-        }, 0) {
-            acts.push(act);
+            points: all_pts,
+            ..Stroke::default()
+        };
+        let copies = state.symmetry.copy_strokes(&fill, state.layer_matrix());
+        for stroke in std::iter::once(fill).chain(copies) {
+            if let Some((_, act)) = Stroke::add_at_idx(&mut state.project, frame, stroke, if self.paint_behind { 0 } else { -1 }) {
+                acts.push(act);
+            }
         }
 
         state.actions.add(Action::from_list(acts));
 
     }
 
+    fn mouse_cursor(&mut self, _mouse_pos: Vec2, state: &mut EditorState, _scene: &mut ScenePanel, _gl: &Arc<glow::Context>) -> egui::CursorIcon {
+        let strokes_changed = ObjEventReceiver::poll(&mut self.stroke_events, &mut state.project.strokes)
+            .map(|events| events.iter().any(|event| !matches!(event, ObjEvent::Modified(_, "color"))))
+            .unwrap_or(true);
+        let source = (self.gap_tolerance, self.boundary_strokes(state));
+        if strokes_changed || self.preview_source.as_ref() != Some(&source) {
+            self.preview_closures = gap_closures(&state.project, &source.1, source.0);
+            self.preview_source = Some(source);
+        }
+        egui::CursorIcon::Default
+    }
+
+    fn reset(&mut self, _state: &mut EditorState) {
+        // Stops listening for stroke changes while another tool is in use. The preview is rebuilt when the bucket is hovered again
+        self.stroke_events = None;
+        self.preview_source = None;
+        self.preview_closures.clear();
+    }
+
+    fn draw_overlay(&mut self, overlay: &mut OverlayRenderer, _state: &EditorState) {
+        for (a, b) in &self.preview_closures {
+            overlay.line(*a, *b, vec4(1.0, 0.0, 1.0, 1.0));
+        }
+    }
+
+    fn tool_panel(&mut self, ui: &mut egui::Ui, state: &mut EditorState) {
+        ui.checkbox(&mut self.paint_behind, "Paint behind");
+        ui.checkbox(&mut self.reference_layers_only, "Reference layers only")
+            .on_hover_text("Only fill up to strokes on layers marked as fill references in the layer properties");
+        ui.horizontal(|ui| {
+            ui.label("Close gaps:");
+            ui.add(egui::DragValue::new(&mut self.gap_tolerance).clamp_range(0.0..=200.0).speed(0.5));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Max area:");
+            ui.add(egui::DragValue::new(&mut self.max_area).clamp_range(10000.0..=100000000.0).speed(10000.0));
+        });
+        symmetry_panel(ui, state);
+    }
+
     fn get_icon(&self) -> &str {
         egui_phosphor::regular::PAINT_BUCKET
     }
@@ -0,0 +1,164 @@
+
+use std::sync::Arc;
+
+use glam::Vec2;
+
+use crate::{editor::{state::EditorState, EditorSystems}, keybind, panels::scene::ScenePanel, project::{action::Action, camera::{Camera, CameraKey}, easing::Easing, graphic::Graphic, layer::Layer, layer_key::LayerKey, obj::{child_obj::ChildObj, obj_list::ObjListTrait, ObjPtr}}, util::ui::drag_value};
+
+use super::Tool;
+
+// Moves and keys the open graphic's camera
+pub struct CameraTool {
+    // Where the drag started, relative to the view, along with the camera's position back then
+    drag_start: Option<(Vec2, Vec2)>,
+    // Keeps getting replaced while the camera is dragged or edited in the panel
+    edit_action: Option<Action>
+}
+
+impl CameraTool {
+
+    pub fn new() -> Self {
+        Self {
+            drag_start: None,
+            edit_action: None
+        }
+    }
+
+    fn camera_layer(state: &EditorState) -> Option<ObjPtr<Layer>> {
+        let layer = Graphic::camera_layer(&state.project, state.open_graphic)?;
+        if Layer::locked(&state.project, layer) {
+            return None;
+        }
+        Some(layer)
+    }
+
+    fn edit(&mut self, state: &mut EditorState, layer: ObjPtr<Layer>, camera: Camera) {
+        if let Some(action) = self.edit_action.take() {
+            action.undo(&mut state.project);
+        }
+        let time = state.frame();
+        self.edit_action = CameraKey::set_at(&mut state.project, layer, time, camera).map(Action::from_list);
+    }
+
+    fn finish_edit(&mut self, state: &mut EditorState) {
+        if let Some(action) = self.edit_action.take() {
+            state.actions.add(action);
+        }
+    }
+
+}
+
+impl Tool for CameraTool {
+
+    fn mouse_click(&mut self, mouse_pos: Vec2, state: &mut EditorState, _ui: &mut egui::Ui, scene: &mut ScenePanel, _gl: &Arc<glow::Context>) {
+        let Some(layer) = Self::camera_layer(state) else { return; };
+        let Some(camera) = Camera::at_time(&state.project, layer, state.frame()) else { return; };
+        state.pause();
+        // The camera lives in the scene, not the active layer
+        self.drag_start = Some((scene.to_scene(mouse_pos) - scene.cam_pos, camera.position));
+    }
+
+    fn mouse_down(&mut self, mouse_pos: Vec2, state: &mut EditorState, scene: &mut ScenePanel) {
+        let Some((start_mouse, start_pos)) = self.drag_start else { return; };
+        let Some(layer) = Self::camera_layer(state) else { return; };
+        let Some(mut camera) = Camera::at_time(&state.project, layer, state.frame()) else { return; };
+        let delta = (scene.to_scene(mouse_pos) - scene.cam_pos) - start_mouse;
+        camera.position = if scene.camera_view {
+            // Looking through the camera, the scene follows the mouse instead
+            start_pos - delta
+        } else {
+            start_pos + delta
+        };
+        self.edit(state, layer, camera);
+    }
+
+    fn mouse_release(&mut self, _mouse_pos: Vec2, state: &mut EditorState, _ui: &mut egui::Ui, _scene: &mut ScenePanel, _gl: &Arc<glow::Context>) {
+        self.drag_start = None;
+        self.finish_edit(state);
+    }
+
+    fn mouse_cursor(&mut self, _mouse_pos: Vec2, state: &mut EditorState, _scene: &mut ScenePanel, _gl: &Arc<glow::Context>) -> egui::CursorIcon {
+        if Self::camera_layer(state).is_none() {
+            egui::CursorIcon::NotAllowed
+        } else if self.drag_start.is_some() {
+            egui::CursorIcon::Grabbing
+        } else {
+            egui::CursorIcon::Grab
+        }
+    }
+
+    fn tool_panel(&mut self, ui: &mut egui::Ui, state: &mut EditorState) {
+        ui.checkbox(&mut state.camera_view, "Camera View");
+        let Some(layer) = Graphic::camera_layer(&state.project, state.open_graphic) else {
+            ui.label("The open graphic has no camera layer.");
+            return;
+        };
+        let time = state.frame();
+        let Some(old_camera) = Camera::at_time(&state.project, layer, time) else { return; };
+        let key = CameraKey::get_key_exactly_at(&state.project, layer, time);
+
+        ui.add_enabled_ui(!Layer::locked(&state.project, layer), |ui| {
+            let mut camera = old_camera;
+            let mut change = (false, false);
+            ui.label("Position:");
+            ui.horizontal(|ui| {
+                drag_value(ui, "X", &mut camera.position.x, f32::MIN..=f32::MAX, Some(&mut change));
+                drag_value(ui, "Y", &mut camera.position.y, f32::MIN..=f32::MAX, Some(&mut change));
+            });
+            drag_value(ui, "Zoom", &mut camera.zoom, 0.01..=100.0, Some(&mut change));
+            drag_value(ui, "Rotation", &mut camera.rotation, -3600.0..=3600.0, Some(&mut change));
+            if camera != old_camera {
+                self.edit(state, layer, camera);
+            }
+            if change.1 {
+                self.finish_edit(state);
+            }
+
+            if let Some(key_ptr) = key {
+                let old_easing = state.project.camera_keys.get(key_ptr).map(|key| key.easing).unwrap_or(Easing::Linear);
+                let mut easing = old_easing;
+                egui::ComboBox::from_label("Easing")
+                    .selected_text(easing.name())
+                    .show_ui(ui, |ui| {
+                        for option in Easing::ALL {
+                            ui.selectable_value(&mut easing, option, option.name());
+                        }
+                    });
+                if easing != old_easing {
+                    if let Some(act) = CameraKey::set_easing(&mut state.project, key_ptr, easing) {
+                        state.actions.add(Action::from_single(act));
+                    }
+                }
+                if ui.button("Delete Key").clicked() {
+                    if let Some(act) = CameraKey::delete(&mut state.project, key_ptr) {
+                        state.actions.add(Action::from_single(act));
+                    }
+                }
+            } else if ui.button("Add Key").clicked() {
+                if let Some(acts) = CameraKey::set_at(&mut state.project, layer, time, old_camera) {
+                    state.actions.add(Action::from_list(acts));
+                }
+            }
+        });
+    }
+
+    fn reset(&mut self, state: &mut EditorState) {
+        self.drag_start = None;
+        self.finish_edit(state);
+    }
+
+    fn get_icon(&self) -> &str {
+        egui_phosphor::regular::VIDEO_CAMERA
+    }
+
+    fn name(&self) -> &str {
+        "Camera"
+    }
+
+    fn shortcut(&self, systems: &mut EditorSystems) -> egui::KeyboardShortcut {
+        systems.prefs.get::<CameraToolKeybind>()
+    }
+
+}
+
+keybind!(CameraToolKeybind, "Camera", NONE, C);
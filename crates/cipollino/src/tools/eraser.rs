@@ -0,0 +1,254 @@
+
+use std::sync::Arc;
+
+use glam::{vec2, Vec2, Vec3};
+
+use crate::{editor::{state::EditorState, EditorSystems}, keybind, panels::scene::{overlay::OverlayRenderer, ScenePanel}, project::{action::{Action, ObjAction}, obj::{child_obj::ChildObj, obj_list::ObjListTrait, ObjPtr}, stroke::{iter_pressure_bezier_segments, polygon_to_points, pressure_bezier_segments_to_points, Stroke, StrokePoint}, Project}, util::{curve::BezierSegment, geo::LineSegment, polygon::{capsule_polygon, point_in_polygon, polygon_boolean, BooleanOp}}};
+
+use super::Tool;
+
+pub struct Eraser {
+    radius: f32,
+    whole_stroke: bool,
+    prev_pt: Option<Vec2>,
+    mouse_pos: Option<Vec2>,
+    acts: Vec<ObjAction>
+}
+
+impl Eraser {
+
+    pub fn new() -> Self {
+        Self {
+            radius: 10.0,
+            whole_stroke: false,
+            prev_pt: None,
+            mouse_pos: None,
+            acts: Vec::new()
+        }
+    }
+
+    fn erase(&mut self, mouse_pos: Vec2, state: &mut EditorState) {
+        let p0 = self.prev_pt.unwrap_or(mouse_pos);
+        for stroke_ptr in state.visible_strokes_in_layer_space(true) {
+            if self.whole_stroke {
+                if stroke_touches_capsule(&state.project, stroke_ptr, p0, mouse_pos, self.radius) {
+                    if let Some(act) = Stroke::delete(&mut state.project, stroke_ptr) {
+                        self.acts.push(act);
+                    }
+                }
+            } else {
+                erase_stroke(&mut state.project, stroke_ptr, p0, mouse_pos, self.radius, &mut self.acts);
+            }
+        }
+        self.prev_pt = Some(mouse_pos);
+    }
+
+}
+
+fn dist_to_segment(pt: Vec2, p0: Vec2, p1: Vec2) -> f32 {
+    let dir = p1 - p0;
+    let len_sq = dir.length_squared();
+    let t = if len_sq > 0.0 { ((pt - p0).dot(dir) / len_sq).clamp(0.0, 1.0) } else { 0.0 };
+    (pt - (p0 + dir * t)).length()
+}
+
+fn stroke_touches_capsule(project: &Project, stroke_ptr: ObjPtr<Stroke>, p0: Vec2, p1: Vec2, r: f32) -> bool {
+    let Some(stroke) = project.strokes.get(stroke_ptr) else { return false; };
+    if stroke.filled {
+        let polygon = stroke.polygon();
+        return point_in_polygon(&polygon, p0) || point_in_polygon(&polygon, p1) ||
+            polygon.iter().flatten().any(|pt| dist_to_segment(*pt, p0, p1) < r);
+    }
+    stroke.iter_pressure_bezier_segments().any(|segment| {
+        segment.to_discrete::<20>().iter().any(|pt| dist_to_segment(pt.truncate(), p0, p1) < r + stroke.r * pt.z)
+    })
+}
+
+// Finds the parts of the curve outside of the capsule, as ranges of t
+fn kept_ranges(segment: &BezierSegment<Vec3>, p0: Vec2, p1: Vec2, r: f32) -> Vec<(f32, f32)> {
+    let curve = segment.map(|pt| pt.truncate());
+    let inside = |t: f32| dist_to_segment(curve.sample(t), p0, p1) < r;
+
+    // The boundary of the capsule is made of two straight sides and two half circles
+    let mut ts = Vec::new();
+    let norm = (p1 - p0).try_normalize().map(|dir| vec2(-dir.y, dir.x) * r);
+    if let Some(norm) = norm {
+        ts.extend(curve.intersect_segment_ts(&LineSegment::new(p0 + norm, p1 + norm)));
+        ts.extend(curve.intersect_segment_ts(&LineSegment::new(p0 - norm, p1 - norm)));
+    }
+    let n = 32;
+    for i in 0..n {
+        let mut t0 = (i as f32) / (n as f32);
+        let mut t1 = ((i + 1) as f32) / (n as f32);
+        let inside_t0 = inside(t0);
+        if inside_t0 == inside(t1) {
+            continue;
+        }
+        for _ in 0..16 {
+            let mid = (t0 + t1) * 0.5;
+            if inside(mid) == inside_t0 {
+                t0 = mid;
+            } else {
+                t1 = mid;
+            }
+        }
+        ts.push((t0 + t1) * 0.5);
+    }
+    ts.push(0.0);
+    ts.push(1.0);
+    ts.sort_by(|a, b| a.total_cmp(b));
+
+    let mut res: Vec<(f32, f32)> = Vec::new();
+    for range in ts.windows(2) {
+        let (t0, t1) = (range[0], range[1]);
+        if t1 - t0 < 0.0001 || inside((t0 + t1) * 0.5) {
+            continue;
+        }
+        match res.last_mut() {
+            Some(last) if (last.1 - t0).abs() < 0.0001 => last.1 = t1,
+            _ => res.push((t0, t1))
+        }
+    }
+    res
+}
+
+fn sub_segment(segment: &BezierSegment<Vec3>, t0: f32, t1: f32) -> BezierSegment<Vec3> {
+    let (_, rest) = segment.split(t0);
+    if t1 >= 1.0 {
+        rest
+    } else {
+        rest.split((t1 - t0) / (1.0 - t0)).0
+    }
+}
+
+// Keeps the stroke itself when anything is left of it, so its style and place in the frame don't change
+fn set_stroke_points(project: &mut Project, stroke_ptr: ObjPtr<Stroke>, points: Vec<Vec<StrokePoint>>, acts: &mut Vec<ObjAction>) -> Option<()> {
+    if points.is_empty() {
+        acts.push(Stroke::delete(project, stroke_ptr)?);
+    } else {
+        acts.push(Stroke::set_points(project, stroke_ptr, points)?);
+    }
+    Some(())
+}
+
+fn erase_unfilled_stroke(project: &mut Project, stroke_ptr: ObjPtr<Stroke>, p0: Vec2, p1: Vec2, r: f32, acts: &mut Vec<ObjAction>) -> Option<()> {
+    let stroke = project.strokes.get(stroke_ptr)?;
+    let mut changed = false;
+    let mut new_points = Vec::new();
+    for chain in &stroke.points {
+        // Single points are dots, so they're either erased whole or kept as they are
+        if chain.len() < 2 {
+            if chain.iter().any(|pt| dist_to_segment(pt.pt, p0, p1) < r + stroke.r * pt.pressure) {
+                changed = true;
+            } else {
+                new_points.push(chain.clone());
+            }
+            continue;
+        }
+        let mut pieces = vec![Vec::new()];
+        for segment in iter_pressure_bezier_segments(chain) {
+            let ranges = kept_ranges(&segment, p0, p1, r);
+            if ranges.len() == 1 && ranges[0] == (0.0, 1.0) {
+                pieces.last_mut().unwrap().push(segment);
+                continue;
+            }
+            changed = true;
+            if ranges.is_empty() {
+                pieces.push(Vec::new());
+            }
+            for (t0, t1) in ranges {
+                if t0 > 0.0 {
+                    pieces.push(Vec::new());
+                }
+                pieces.last_mut().unwrap().push(sub_segment(&segment, t0, t1));
+                if t1 < 1.0 {
+                    pieces.push(Vec::new());
+                }
+            }
+        }
+        new_points.extend(pieces.iter().filter(|piece| !piece.is_empty()).map(pressure_bezier_segments_to_points));
+    }
+    if !changed {
+        return None;
+    }
+    set_stroke_points(project, stroke_ptr, new_points, acts)
+}
+
+fn erase_filled_stroke(project: &mut Project, stroke_ptr: ObjPtr<Stroke>, p0: Vec2, p1: Vec2, r: f32, acts: &mut Vec<ObjAction>) -> Option<()> {
+    let stroke = project.strokes.get(stroke_ptr)?;
+    let polygon = stroke.polygon();
+    let capsule = vec![capsule_polygon(p0, p1, r)];
+    let overlaps = polygon.iter().flatten().any(|pt| dist_to_segment(*pt, p0, p1) < r) || capsule[0].iter().any(|pt| point_in_polygon(&polygon, *pt));
+    if !overlaps {
+        return None;
+    }
+    let points = polygon_to_points(&polygon_boolean(&polygon, &capsule, BooleanOp::Difference), 0.25);
+    set_stroke_points(project, stroke_ptr, points, acts)
+}
+
+// Removes the region swept by a circle of radius r moving from p0 to p1 from the stroke
+pub fn erase_stroke(project: &mut Project, stroke_ptr: ObjPtr<Stroke>, p0: Vec2, p1: Vec2, r: f32, acts: &mut Vec<ObjAction>) -> Option<()> {
+    if project.strokes.get(stroke_ptr)?.filled {
+        erase_filled_stroke(project, stroke_ptr, p0, p1, r, acts)
+    } else {
+        erase_unfilled_stroke(project, stroke_ptr, p0, p1, r, acts)
+    }
+}
+
+impl Tool for Eraser {
+
+    fn mouse_click(&mut self, mouse_pos: Vec2, state: &mut EditorState, _ui: &mut egui::Ui, _scene: &mut ScenePanel, _gl: &Arc<glow::Context>) {
+        self.prev_pt = None;
+        self.erase(mouse_pos, state);
+    }
+
+    fn mouse_down(&mut self, mouse_pos: Vec2, state: &mut EditorState, _scene: &mut ScenePanel) {
+        self.mouse_pos = Some(mouse_pos);
+        if self.prev_pt.map(|prev_pt| (mouse_pos - prev_pt).length() > 0.5).unwrap_or(true) {
+            self.erase(mouse_pos, state);
+        }
+    }
+
+    fn mouse_release(&mut self, _mouse_pos: Vec2, state: &mut EditorState, _ui: &mut egui::Ui, _scene: &mut ScenePanel, _gl: &Arc<glow::Context>) {
+        state.pause();
+        self.reset(state);
+    }
+
+    fn mouse_cursor(&mut self, mouse_pos: Vec2, _state: &mut EditorState, _scene: &mut ScenePanel, _gl: &Arc<glow::Context>) -> egui::CursorIcon {
+        self.mouse_pos = Some(mouse_pos);
+        egui::CursorIcon::Crosshair
+    }
+
+    fn draw_overlay(&mut self, overlay: &mut OverlayRenderer, _state: &EditorState) {
+        if let Some(mouse_pos) = self.mouse_pos {
+            overlay.circle(mouse_pos, glam::vec4(1.0, 1.0, 1.0, 0.3), self.radius);
+        }
+    }
+
+    fn reset(&mut self, state: &mut EditorState) {
+        self.prev_pt = None;
+        if !self.acts.is_empty() {
+            state.actions.add(Action::from_list(std::mem::replace(&mut self.acts, Vec::new())));
+        }
+    }
+
+    fn tool_panel(&mut self, ui: &mut egui::Ui, _state: &mut EditorState) {
+        ui.add(egui::Slider::new(&mut self.radius, 1.0..=100.0));
+        ui.checkbox(&mut self.whole_stroke, "Whole strokes");
+    }
+
+    fn get_icon(&self) -> &str {
+        egui_phosphor::regular::ERASER
+    }
+
+    fn name(&self) -> &str {
+        "Eraser"
+    }
+
+    fn shortcut(&self, systems: &mut EditorSystems) -> egui::KeyboardShortcut {
+        systems.prefs.get::<EraserToolKeybind>()
+    }
+
+}
+
+keybind!(EraserToolKeybind, "Eraser", NONE, E);
@@ -4,15 +4,16 @@ use std::{mem, sync::Arc};
 
 use glam::{vec2, Vec2};
 
-use crate::{editor::{state::EditorState, EditorSystems}, keybind, panels::scene::ScenePanel, project::{action::{Action, ObjAction}, frame::Frame, obj::{child_obj::ChildObj, ObjPtr}, stroke::{Stroke, StrokePoint}}};
+use crate::{editor::{snapping::snap_point, state::EditorState, symmetry::SymmetryCopies, EditorSystems}, keybind, panels::scene::ScenePanel, project::{action::{Action, ObjAction}, frame::Frame, obj::{child_obj::ChildObj, ObjPtr}, stroke::{Stroke, StrokePoint}}};
 
-use super::{active_frame, Tool};
+use super::{active_frame, stroke_style_panel, symmetry_panel, Tool};
 
 pub struct Line {
     first_point: Vec2,
     curr_stroke_frame: Option<(ObjPtr<Stroke>, ObjPtr<Frame>)>,
     frame_creation_acts: Vec<ObjAction>,
-    stroke_act: Option<ObjAction> 
+    stroke_act: Option<ObjAction>,
+    symmetry_copies: SymmetryCopies
 }
 
 impl Line {
@@ -22,7 +23,8 @@ impl Line {
             first_point: Vec2::ZERO,
             curr_stroke_frame: None,
             frame_creation_acts: Vec::new(),
-            stroke_act: None
+            stroke_act: None,
+            symmetry_copies: SymmetryCopies::new()
         }
     }
 
@@ -35,6 +37,7 @@ impl Line {
         let acts = std::mem::replace(&mut self.frame_creation_acts, Vec::new());
         action.add_list(acts);
         action.add(mem::replace(&mut self.stroke_act, None).unwrap());
+        action.add_list(self.symmetry_copies.take_acts());
         action
     }
 
@@ -42,12 +45,13 @@ impl Line {
 
 impl Tool for Line {
 
-    fn mouse_click(&mut self, mouse_pos: glam::Vec2, state: &mut EditorState, _ui: &mut egui::Ui, _scene: &mut ScenePanel, _gl: &Arc<glow::Context>) {
+    fn mouse_click(&mut self, mouse_pos: glam::Vec2, state: &mut EditorState, _ui: &mut egui::Ui, scene: &mut ScenePanel, _gl: &Arc<glow::Context>) {
         let active_frame = active_frame(state);
         if active_frame.is_none() {
             return;
         }
         let (frame, frame_act) = active_frame.unwrap(); 
+        let mouse_pos = snap_point(state, mouse_pos, scene.cam_size, &Vec::new());
 
         self.frame_creation_acts = frame_act;
 
@@ -57,43 +61,58 @@ impl Tool for Line {
                 pt: mouse_pos,
                 a: mouse_pos - offset,
                 b: mouse_pos + offset,
+                pressure: 1.0
             },
             StrokePoint {
                 pt: mouse_pos + offset,
                 a: mouse_pos - offset,
                 b: mouse_pos + offset,
+                pressure: 1.0
             }
         ]];
 
         self.first_point = mouse_pos;
 
-        if let Some((stroke, act)) = Stroke::add(&mut state.project, frame, Stroke {
+        let stroke = Stroke {
             frame: frame,
             points: pts,
             color: state.color,
             r: state.stroke_r,
-            filled: state.stroke_filled
-        }) {
+            filled: state.stroke_filled,
+            taper_start: state.stroke_taper_start,
+            taper_end: state.stroke_taper_end,
+            cap: state.stroke_cap
+        };
+        self.symmetry_copies.update(&mut state.project, &state.symmetry, scene.layer_mat, frame, &stroke);
+        if let Some((stroke, act)) = Stroke::add(&mut state.project, frame, stroke) {
             self.curr_stroke_frame = Some((stroke, frame));
             self.stroke_act = Some(act);
         }
 
     }
 
-    fn mouse_down(&mut self, mouse_pos: glam::Vec2, state: &mut EditorState, _scene: &mut ScenePanel) {
-        if let Some((_stroke, frame)) = self.curr_stroke_frame {
+    fn mouse_down(&mut self, mouse_pos: glam::Vec2, state: &mut EditorState, scene: &mut ScenePanel) {
+        if let Some((stroke, frame)) = self.curr_stroke_frame {
+            let mut drawn_strokes = self.symmetry_copies.strokes();
+            drawn_strokes.push(stroke);
+            let mouse_pos = snap_point(state, mouse_pos, scene.cam_size, &drawn_strokes);
             let dir = (mouse_pos - self.first_point) / 3.0;
             mem::replace(&mut self.stroke_act, None).unwrap().undo(&mut state.project);
-            if let Some((new_stroke, act)) = Stroke::add(&mut state.project, frame, Stroke {
+            let stroke = Stroke {
                 frame: frame,
                 color: state.color,
                 r: state.stroke_r,
                 filled: state.stroke_filled,
+                taper_start: state.stroke_taper_start,
+                taper_end: state.stroke_taper_end,
+                cap: state.stroke_cap,
                 points: vec![vec![
-                    StrokePoint { a: self.first_point - dir, pt: self.first_point, b: self.first_point + dir },
-                    StrokePoint { a: mouse_pos - dir, pt: mouse_pos, b: mouse_pos + dir }
+                    StrokePoint { a: self.first_point - dir, pt: self.first_point, b: self.first_point + dir, pressure: 1.0 },
+                    StrokePoint { a: mouse_pos - dir, pt: mouse_pos, b: mouse_pos + dir, pressure: 1.0 }
                 ]]
-            }) {
+            };
+            self.symmetry_copies.update(&mut state.project, &state.symmetry, scene.layer_mat, frame, &stroke);
+            if let Some((new_stroke, act)) = Stroke::add(&mut state.project, frame, stroke) {
                 self.stroke_act = Some(act);
                 self.curr_stroke_frame = Some((new_stroke, frame));
             }
@@ -116,6 +135,12 @@ impl Tool for Line {
         }
     }
 
+    fn tool_panel(&mut self, ui: &mut egui::Ui, state: &mut EditorState) {
+        stroke_style_panel(ui, state);
+        ui.checkbox(&mut state.stroke_filled, "Filled");
+        symmetry_panel(ui, state);
+    }
+
     fn get_icon(&self) -> &str {
         egui_phosphor::regular::LINE_SEGMENT
     }
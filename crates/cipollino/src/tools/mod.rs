@@ -3,7 +3,7 @@ use std::sync::Arc;
 
 use glam::Vec2;
 
-use crate::{editor::{state::EditorState, EditorSystems}, project::{action::ObjAction, frame::Frame, layer::{BlendingMode, Layer, LayerKind, LayerParent}, obj::{child_obj::ChildObj, obj_list::ObjListTrait, ObjPtr}, Project}};
+use crate::{editor::{state::EditorState, symmetry::SymmetryMode, EditorSystems}, project::{action::ObjAction, frame::Frame, layer::{BlendingMode, Layer, LayerKind, LayerParent}, obj::{child_obj::ChildObj, obj_list::ObjListTrait, ObjPtr}, stroke::StrokeCap, Project}};
 
 use super::panels::scene::{overlay::OverlayRenderer, ScenePanel};
 
@@ -13,8 +13,15 @@ pub mod bucket;
 pub mod color_picker;
 pub mod line;
 pub mod scissors;
+pub mod eraser;
+pub mod shape;
+pub mod node;
+pub mod camera;
 pub mod state_machine;
 
+#[cfg(test)]
+mod tests;
+
 pub trait Tool {
 
     fn mouse_click(&mut self, _mouse_pos: Vec2, _state: &mut EditorState, _ui: &mut egui::Ui, _scene: &mut ScenePanel, _gl: &Arc<glow::Context>) {}
@@ -27,6 +34,12 @@ pub trait Tool {
     fn tool_panel(&mut self, _ui: &mut egui::Ui, _state: &mut EditorState) {}
     fn reset(&mut self, _state: &mut EditorState) {}
 
+    // Lets the delete keybind remove something selected inside the tool, instead of the selected strokes
+    fn has_selection(&self) -> bool {
+        false
+    }
+    fn delete_selected(&mut self, _state: &mut EditorState) {}
+
     fn get_icon(&self) -> &str;
     fn name(&self) -> &str;
     fn shortcut(&self, systems: &mut EditorSystems) -> egui::KeyboardShortcut;
@@ -35,13 +48,17 @@ pub trait Tool {
 
 pub fn active_frame_proj_layer_frame(project: &mut Project, active_layer: ObjPtr<Layer>, frame: i32) -> Option<(ObjPtr<Frame>, Option<ObjAction>)> {
     let layer = project.layers.get(active_layer)?;
-    if let Some(frame) = layer.get_frame_at(project, frame) {
-        Some((frame.make_ptr(), None))
+    if let Some(key) = layer.get_frame_at(project, frame) {
+        // In-between drawings of a tween can't be drawn on
+        if Frame::tween_at(project, key.make_ptr(), frame).is_some() {
+            return None;
+        }
+        Some((key.make_ptr(), None))
     } else {
         let (frame, act) = Frame::add(project, active_layer, Frame {
             layer: active_layer,
             time: frame,
-            strokes: Vec::new()
+            ..Frame::default()
         }).unwrap();
         Some((frame, Some(act)))
     }
@@ -59,8 +76,11 @@ pub fn active_frame(state: &mut EditorState) -> Option<(ObjPtr<Frame>, Vec<ObjAc
             kind: LayerKind::Animation,
             alpha: 1.0,
             blending: BlendingMode::Normal,
+            fill_reference: false,
             frames: Vec::new(),
             sound_instances: Vec::new(),
+            camera_keys: Vec::new(),
+            transform_keys: Vec::new(),
             layers: Vec::new()
         })?;
         state.active_layer = layer;
@@ -72,7 +92,10 @@ pub fn active_frame(state: &mut EditorState) -> Option<(ObjPtr<Frame>, Vec<ObjAc
     if layer.kind != LayerKind::Animation || layer.lock {
         return None;
     }
-    let (frame, frame_act) = active_frame_proj_layer_frame(&mut state.project, state.active_layer, frame)?;
+    let Some((frame, frame_act)) = active_frame_proj_layer_frame(&mut state.project, state.active_layer, frame) else {
+        state.error_messages.push("Can't edit an in-between drawing of a tween. Bake the tween or add a frame here first.".to_owned());
+        return None;
+    };
     let mut acts = Vec::new();
     if let Some(layer_act) = layer_act {
         acts.push(layer_act);
@@ -81,4 +104,49 @@ pub fn active_frame(state: &mut EditorState) -> Option<(ObjPtr<Frame>, Vec<ObjAc
         acts.push(frame_act);
     }
     Some((frame, acts))
-}
\ No newline at end of file
+}
+// Width, taper and cap options shared by the tools that draw unfilled strokes
+pub fn stroke_style_panel(ui: &mut egui::Ui, state: &mut EditorState) {
+    ui.add(egui::Slider::new(&mut state.stroke_r, 1.0..=50.0));
+    ui.horizontal(|ui| {
+        ui.label("Taper:");
+        ui.add(egui::DragValue::new(&mut state.stroke_taper_start).clamp_range(0.0..=1000.0).speed(1.0).prefix("Start: "));
+        ui.add(egui::DragValue::new(&mut state.stroke_taper_end).clamp_range(0.0..=1000.0).speed(1.0).prefix("End: "));
+    });
+    ui.horizontal(|ui| {
+        ui.label("Cap:");
+        egui::ComboBox::new("stroke_cap", "")
+            .selected_text(state.stroke_cap.name()).show_ui(ui, |ui| {
+                for cap in [StrokeCap::Round, StrokeCap::Square, StrokeCap::Butt] {
+                    ui.selectable_value(&mut state.stroke_cap, cap, cap.name());
+                }
+        });
+    });
+}
+
+// Symmetry options shared by the tools that draw mirrored copies
+pub fn symmetry_panel(ui: &mut egui::Ui, state: &mut EditorState) {
+    let symmetry = &mut state.symmetry;
+    ui.horizontal(|ui| {
+        ui.label("Symmetry:");
+        egui::ComboBox::new("symmetry_mode", "")
+            .selected_text(symmetry.mode.name()).show_ui(ui, |ui| {
+                for mode in [SymmetryMode::Off, SymmetryMode::Horizontal, SymmetryMode::Vertical, SymmetryMode::Radial] {
+                    ui.selectable_value(&mut symmetry.mode, mode, mode.name());
+                }
+        });
+        if symmetry.mode == SymmetryMode::Radial {
+            ui.add(egui::DragValue::new(&mut symmetry.radial_count).clamp_range(2..=32).prefix("Copies: "));
+        }
+    });
+    if symmetry.mode != SymmetryMode::Off {
+        ui.horizontal(|ui| {
+            ui.label("Centre:");
+            ui.add(egui::DragValue::new(&mut symmetry.center.x).speed(1.0).prefix("x: "));
+            ui.add(egui::DragValue::new(&mut symmetry.center.y).speed(1.0).prefix("y: "));
+            if ui.button("Reset").clicked() {
+                symmetry.center = Vec2::ZERO;
+            }
+        });
+    }
+}
@@ -0,0 +1,350 @@
+
+use std::sync::Arc;
+
+use glam::{vec4, Vec2};
+
+use crate::{editor::{state::EditorState, EditorSystems}, keybind, panels::scene::{overlay::OverlayRenderer, ScenePanel}, project::{action::{Action, ObjAction}, obj::{child_obj::ChildObj, obj_list::ObjListTrait, ObjPtr}, stroke::{iter_pressure_bezier_segments, Stroke, StrokePoint}, Project}};
+
+use super::Tool;
+
+// How close to a node the mouse needs to be to grab it, relative to the camera size
+const NODE_GRAB_DIST: f32 = 0.015;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum HandleMode {
+    // The opposite handle stays in line, keeping its length
+    Smooth,
+    // Handles move independently
+    Corner,
+    // The opposite handle mirrors the dragged one
+    Symmetric
+}
+
+impl HandleMode {
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HandleMode::Smooth => "Smooth",
+            HandleMode::Corner => "Corner",
+            HandleMode::Symmetric => "Symmetric",
+        }
+    }
+
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum NodePart {
+    Anchor,
+    HandleA,
+    HandleB
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Node {
+    chain: usize,
+    idx: usize
+}
+
+pub struct NodeTool {
+    stroke: Option<ObjPtr<Stroke>>,
+    selected: Option<Node>,
+    handle_mode: HandleMode,
+
+    drag: Option<(Node, NodePart)>,
+    drag_start: Vec2,
+    orig_points: Vec<Vec<StrokePoint>>,
+    drag_act: Option<ObjAction>
+}
+
+// Closed chains repeat their first point at the end, so both copies are edited together
+fn is_closed(chain: &Vec<StrokePoint>) -> bool {
+    chain.len() > 2 && (chain[0].pt - chain[chain.len() - 1].pt).length() < 0.0001
+}
+
+fn visible_nodes(chain: &Vec<StrokePoint>) -> usize {
+    if is_closed(chain) { chain.len() - 1 } else { chain.len() }
+}
+
+/*
+    The shared point of a closed chain is drawn with the first point's outgoing handle and the last point's incoming one.
+    Only the position and the incoming handle are copied, so neither copy's other handle gets overwritten.
+*/
+fn sync_closed_chain(chain: &mut Vec<StrokePoint>, edited: usize) {
+    if !is_closed(chain) {
+        return;
+    }
+    let last = chain.len() - 1;
+    let (from, to) = if edited == 0 {
+        (0, last)
+    } else if edited == last {
+        (last, 0)
+    } else {
+        return;
+    };
+    chain[to].pt = chain[from].pt;
+    chain[to].a = chain[from].a;
+}
+
+// Splits the segment starting at the given point, adding a new point at t without changing the curve's shape
+pub fn insert_point(points: &mut Vec<Vec<StrokePoint>>, chain: usize, segment: usize, t: f32) -> Option<usize> {
+    let pts = points.get_mut(chain)?;
+    let bezier = iter_pressure_bezier_segments(pts).nth(segment)?;
+    let (before, after) = bezier.split(t);
+    pts[segment].b = before.b0.truncate();
+    pts[segment + 1].a = after.a1.truncate();
+    pts.insert(segment + 1, StrokePoint {
+        a: before.a1.truncate(),
+        pt: before.p1.truncate(),
+        b: after.b0.truncate(),
+        pressure: before.p1.z
+    });
+    // The point after the new one had its incoming handle changed
+    sync_closed_chain(pts, segment + 2);
+    Some(segment + 1)
+}
+
+// Removes a point, dropping the whole chain once it no longer forms a curve
+pub fn delete_point(points: &mut Vec<Vec<StrokePoint>>, chain: usize, idx: usize) {
+    let Some(pts) = points.get_mut(chain) else { return; };
+    let closed = is_closed(pts);
+    if idx >= pts.len() {
+        return;
+    }
+    pts.remove(idx);
+    if closed && idx == 0 && !pts.is_empty() {
+        let last = pts.len() - 1;
+        pts[last].pt = pts[0].pt;
+        pts[last].a = pts[0].a;
+    }
+    let min_len = if closed { 3 } else { 2 };
+    if pts.len() < min_len {
+        points.remove(chain);
+    }
+}
+
+impl NodeTool {
+
+    pub fn new() -> Self {
+        Self {
+            stroke: None,
+            selected: None,
+            handle_mode: HandleMode::Smooth,
+            drag: None,
+            drag_start: Vec2::ZERO,
+            orig_points: Vec::new(),
+            drag_act: None
+        }
+    }
+
+    fn stroke_points(&self, project: &Project) -> Option<Vec<Vec<StrokePoint>>> {
+        Some(project.strokes.get(self.stroke?)?.points.clone())
+    }
+
+    fn find_node(&self, project: &Project, mouse_pos: Vec2, grab_dist: f32) -> Option<(Node, NodePart)> {
+        let points = self.stroke_points(project)?;
+        let mut best = None;
+        let mut best_dist = grab_dist;
+        for (chain_idx, chain) in points.iter().enumerate() {
+            for idx in 0..visible_nodes(chain) {
+                let pt = chain[idx];
+                let node = Node { chain: chain_idx, idx };
+                let mut parts = vec![(pt.pt, NodePart::Anchor)];
+                // Handles are only shown for the selected point
+                if self.selected == Some(node) {
+                    parts.push((pt.a, NodePart::HandleA));
+                    parts.push((pt.b, NodePart::HandleB));
+                }
+                for (pos, part) in parts {
+                    let dist = (pos - mouse_pos).length();
+                    if dist < best_dist {
+                        best_dist = dist;
+                        best = Some((node, part));
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    // Finds the segment and t of the curve closest to the mouse
+    fn find_curve_point(&self, project: &Project, mouse_pos: Vec2, grab_dist: f32) -> Option<(usize, usize, f32)> {
+        let points = self.stroke_points(project)?;
+        let mut best = None;
+        let mut best_dist = grab_dist;
+        for (chain_idx, chain) in points.iter().enumerate() {
+            for (segment_idx, segment) in iter_pressure_bezier_segments(chain).enumerate() {
+                let curve = segment.map(|pt| pt.truncate());
+                let t = curve.nearest_t(mouse_pos);
+                let dist = (curve.sample(t) - mouse_pos).length();
+                if dist < best_dist {
+                    best_dist = dist;
+                    best = Some((chain_idx, segment_idx, t));
+                }
+            }
+        }
+        best
+    }
+
+    fn edit_points<F>(&mut self, state: &mut EditorState, edit: F) where F: FnOnce(&mut Vec<Vec<StrokePoint>>) {
+        let Some(stroke) = self.stroke else { return; };
+        let Some(mut points) = self.stroke_points(&state.project) else { return; };
+        edit(&mut points);
+        let act = if points.is_empty() {
+            self.stroke = None;
+            Stroke::delete(&mut state.project, stroke)
+        } else {
+            Stroke::set_points(&mut state.project, stroke, points)
+        };
+        if let Some(act) = act {
+            state.actions.add(Action::from_list(vec![act]));
+        }
+    }
+
+    fn dragged_points(&self, mouse_pos: Vec2) -> Vec<Vec<StrokePoint>> {
+        let mut points = self.orig_points.clone();
+        let Some((node, part)) = self.drag else { return points; };
+        let delta = mouse_pos - self.drag_start;
+        let chain = &mut points[node.chain];
+        let pt = &mut chain[node.idx];
+        match part {
+            NodePart::Anchor => {
+                pt.a += delta;
+                pt.pt += delta;
+                pt.b += delta;
+            },
+            NodePart::HandleA | NodePart::HandleB => {
+                let (moved, other) = if part == NodePart::HandleA { (&mut pt.a, &mut pt.b) } else { (&mut pt.b, &mut pt.a) };
+                *moved += delta;
+                let dir = (pt.pt - *moved).normalize_or_zero();
+                match self.handle_mode {
+                    HandleMode::Smooth => *other = pt.pt + dir * (*other - pt.pt).length(),
+                    HandleMode::Corner => {},
+                    HandleMode::Symmetric => *other = pt.pt * 2.0 - *moved
+                }
+            }
+        }
+        sync_closed_chain(chain, node.idx);
+        points
+    }
+
+}
+
+impl Tool for NodeTool {
+
+    fn mouse_click(&mut self, mouse_pos: Vec2, state: &mut EditorState, _ui: &mut egui::Ui, scene: &mut ScenePanel, gl: &Arc<glow::Context>) {
+        if self.stroke.map(|stroke| state.project.strokes.get(stroke).is_none()).unwrap_or(false) {
+            self.stroke = None;
+            self.selected = None;
+        }
+        let grab_dist = NODE_GRAB_DIST * scene.cam_size;
+
+        // Alt-clicking on the curve adds a point there
+        if scene.modifiers.alt {
+            if let Some((chain, segment, t)) = self.find_curve_point(&state.project, mouse_pos, grab_dist) {
+                let mut new_idx = None;
+                self.edit_points(state, |points| new_idx = insert_point(points, chain, segment, t));
+                self.selected = new_idx.map(|idx| Node { chain, idx });
+            }
+            return;
+        }
+
+        if let Some((node, part)) = self.find_node(&state.project, mouse_pos, grab_dist) {
+            self.selected = Some(node);
+            self.drag = Some((node, part));
+            self.drag_start = mouse_pos;
+            self.orig_points = self.stroke_points(&state.project).unwrap_or_default();
+            return;
+        }
+
+        self.selected = None;
+        self.stroke = scene.sample_pick(mouse_pos, gl).filter(|stroke| state.visible_strokes_in_layer_space(true).contains(stroke));
+    }
+
+    fn mouse_down(&mut self, mouse_pos: Vec2, state: &mut EditorState, _scene: &mut ScenePanel) {
+        let Some(stroke) = self.stroke else { return; };
+        if self.drag.is_none() {
+            return;
+        }
+        if let Some(act) = self.drag_act.take() {
+            act.undo(&mut state.project);
+        }
+        self.drag_act = Stroke::set_points(&mut state.project, stroke, self.dragged_points(mouse_pos));
+    }
+
+    fn mouse_release(&mut self, _mouse_pos: Vec2, state: &mut EditorState, _ui: &mut egui::Ui, _scene: &mut ScenePanel, _gl: &Arc<glow::Context>) {
+        self.reset(state);
+    }
+
+    fn mouse_cursor(&mut self, mouse_pos: Vec2, state: &mut EditorState, scene: &mut ScenePanel, _gl: &Arc<glow::Context>) -> egui::CursorIcon {
+        if self.drag.is_some() || self.find_node(&state.project, mouse_pos, NODE_GRAB_DIST * scene.cam_size).is_some() {
+            egui::CursorIcon::Move
+        } else {
+            egui::CursorIcon::Default
+        }
+    }
+
+    fn draw_overlay(&mut self, overlay: &mut OverlayRenderer, state: &EditorState) {
+        let Some(points) = self.stroke_points(&state.project) else { return; };
+        let node_r = overlay.cam_size * 0.006;
+        let handle_color = vec4(0.0, 1.0, 1.0, 1.0);
+        for (chain_idx, chain) in points.iter().enumerate() {
+            for idx in 0..visible_nodes(chain) {
+                let pt = chain[idx];
+                let selected = self.selected == Some(Node { chain: chain_idx, idx });
+                if selected {
+                    overlay.line(pt.pt, pt.a, handle_color);
+                    overlay.line(pt.pt, pt.b, handle_color);
+                    overlay.circle(pt.a, handle_color, node_r * 0.7);
+                    overlay.circle(pt.b, handle_color, node_r * 0.7);
+                }
+                let color = if selected { vec4(1.0, 0.5, 0.0, 1.0) } else { handle_color };
+                overlay.circle(pt.pt, color, node_r);
+            }
+        }
+    }
+
+    fn has_selection(&self) -> bool {
+        self.selected.is_some()
+    }
+
+    fn delete_selected(&mut self, state: &mut EditorState) {
+        if let Some(node) = self.selected.take() {
+            self.edit_points(state, |points| delete_point(points, node.chain, node.idx));
+        }
+    }
+
+    fn reset(&mut self, state: &mut EditorState) {
+        self.drag = None;
+        if let Some(act) = self.drag_act.take() {
+            state.actions.add(Action::from_list(vec![act]));
+        }
+    }
+
+    fn tool_panel(&mut self, ui: &mut egui::Ui, state: &mut EditorState) {
+        ui.horizontal(|ui| {
+            ui.label("Handles:");
+            for mode in [HandleMode::Smooth, HandleMode::Corner, HandleMode::Symmetric] {
+                ui.selectable_value(&mut self.handle_mode, mode, mode.name());
+            }
+        });
+        if ui.add_enabled(self.selected.is_some(), egui::Button::new("Delete Point")).clicked() {
+            self.delete_selected(state);
+        }
+        ui.label("Alt-click on the curve to add a point.");
+    }
+
+    fn get_icon(&self) -> &str {
+        egui_phosphor::regular::PEN_NIB
+    }
+
+    fn name(&self) -> &str {
+        "Node"
+    }
+
+    fn shortcut(&self, systems: &mut EditorSystems) -> egui::KeyboardShortcut {
+        systems.prefs.get::<NodeToolKeybind>()
+    }
+
+}
+
+keybind!(NodeToolKeybind, "Node", NONE, A);
@@ -1,17 +1,61 @@
 
-use std::{mem, sync::Arc};
+use std::{mem, sync::Arc, time::Instant};
 
-use glam::vec2;
+use glam::{vec2, Vec2, Vec3};
 
-use crate::{editor::{state::EditorState, EditorSystems}, keybind, panels::scene::ScenePanel, project::{action::{Action, ObjAction}, frame::Frame, obj::{child_obj::ChildObj, ObjPtr}, stroke::{Stroke, StrokePoint}}, util::curve};
+use crate::{editor::{state::EditorState, symmetry::SymmetryCopies, EditorSystems}, keybind, panels::scene::ScenePanel, project::{action::{Action, ObjAction}, frame::Frame, obj::{child_obj::ChildObj, ObjPtr}, stroke::{Stroke, StrokePoint}}, util::curve};
 
-use super::{active_frame, Tool};
+use super::{active_frame, stroke_style_panel, symmetry_panel, Tool};
+
+// Drawing faster than this many half screen heights per second gives the thinnest line
+const VELOCITY_PRESSURE_MAX_SPEED: f32 = 4.0;
+const VELOCITY_PRESSURE_MIN: f32 = 0.3;
+
+// At full strength, the string is this many half screen heights long
+const STABILIZER_MAX_STRING_LENGTH: f32 = 0.2;
+const STABILIZER_MAX_WINDOW: f32 = 24.0;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum StabilizerMode {
+    None,
+    // The line eases towards the mouse, covering less of the remaining distance the higher the strength
+    LazyRope,
+    // The line follows the average of the last few mouse positions
+    MovingAverage,
+    // The line only moves once the mouse is farther away than the string's length, and is then pulled along
+    PulledString
+}
+
+impl StabilizerMode {
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StabilizerMode::None => "None",
+            StabilizerMode::LazyRope => "Lazy Rope",
+            StabilizerMode::MovingAverage => "Moving Average",
+            StabilizerMode::PulledString => "Pulled String",
+        }
+    }
+
+}
 
 pub struct Pencil {
-    points: Vec<glam::Vec2>,
+    // Stabilized points, with the pressure as the z coordinate
+    points: Vec<Vec3>,
+    // The latest mouse positions before stabilization
+    raw_points: Vec<Vec3>,
     curr_stroke_frame: Option<(ObjPtr<Stroke>, ObjPtr<Frame>)>,
     frame_creation_acts: Vec<ObjAction>,
-    stroke_act: Option<ObjAction> 
+    stroke_act: Option<ObjAction>,
+    symmetry_copies: SymmetryCopies,
+
+    velocity_pressure: bool,
+    prev_sample_time: Option<Instant>,
+    prev_velocity_pressure: f32,
+
+    stabilizer: StabilizerMode,
+    stabilizer_strength: f32,
+    fit_tolerance: f32
 }
 
 impl Pencil {
@@ -19,9 +63,17 @@ impl Pencil {
     pub fn new() -> Self {
         Self {
             points: Vec::new(),
+            raw_points: Vec::new(),
             curr_stroke_frame: None,
             frame_creation_acts: Vec::new(),
-            stroke_act: None
+            stroke_act: None,
+            symmetry_copies: SymmetryCopies::new(),
+            velocity_pressure: true,
+            prev_sample_time: None,
+            prev_velocity_pressure: 1.0,
+            stabilizer: StabilizerMode::None,
+            stabilizer_strength: 0.5,
+            fit_tolerance: 1.0
         }
     }
 
@@ -29,11 +81,97 @@ impl Pencil {
 
 impl Pencil {
 
+    // Uses the device's pressure when there is one, otherwise slower movements give thicker lines
+    fn sample_pressure(&mut self, mouse_pos: Vec2, scene: &ScenePanel) -> f32 {
+        if let Some(pressure) = scene.pressure {
+            return pressure.clamp(0.05, 1.0);
+        }
+        if !self.velocity_pressure {
+            return 1.0;
+        }
+
+        let now = Instant::now();
+        if let (Some(prev_time), Some(prev_pt)) = (self.prev_sample_time, self.raw_points.last()) {
+            let dt = (now - prev_time).as_secs_f32().max(0.001);
+            let speed = (mouse_pos - prev_pt.truncate()).length() / dt / scene.cam_size;
+            let target = (1.0 - speed / VELOCITY_PRESSURE_MAX_SPEED).max(VELOCITY_PRESSURE_MIN);
+            // Smooth out the jitter in the mouse's speed
+            self.prev_velocity_pressure = self.prev_velocity_pressure * 0.7 + target * 0.3;
+        } else {
+            self.prev_velocity_pressure = 1.0;
+        }
+        self.prev_sample_time = Some(now);
+        self.prev_velocity_pressure
+    }
+
+    fn stabilize(&mut self, raw_pt: Vec3, cam_size: f32) -> Vec3 {
+        let window = match self.stabilizer {
+            StabilizerMode::MovingAverage => 1 + (self.stabilizer_strength * STABILIZER_MAX_WINDOW) as usize,
+            _ => 1
+        };
+        self.raw_points.push(raw_pt);
+        if self.raw_points.len() > window {
+            self.raw_points.drain(0..(self.raw_points.len() - window));
+        }
+
+        let Some(prev_pt) = self.points.last().copied() else {
+            return raw_pt;
+        };
+        match self.stabilizer {
+            StabilizerMode::None => raw_pt,
+            StabilizerMode::LazyRope => {
+                let follow = 1.0 - 0.95 * self.stabilizer_strength;
+                let pos = prev_pt.truncate().lerp(raw_pt.truncate(), follow);
+                pos.extend(raw_pt.z)
+            },
+            StabilizerMode::MovingAverage => {
+                self.raw_points.iter().sum::<Vec3>() / (self.raw_points.len() as f32)
+            },
+            StabilizerMode::PulledString => {
+                let string_length = self.stabilizer_strength * STABILIZER_MAX_STRING_LENGTH * cam_size;
+                let diff = raw_pt.truncate() - prev_pt.truncate();
+                let dist = diff.length();
+                if dist <= string_length {
+                    return prev_pt;
+                }
+                let pos = prev_pt.truncate() + diff * ((dist - string_length) / dist);
+                pos.extend(raw_pt.z)
+            }
+        }
+    }
+
+    fn fit_points(&self, stroke_r: f32) -> Vec<StrokePoint> {
+        // The pressure is fit as the radius, so that the fitting error is in the same units as the position
+        let mut pts = Vec::new();
+        for pt in &self.points {
+            pts.push(pt.x);
+            pts.push(pt.y);
+            pts.push(pt.z * stroke_r);
+        }
+
+        let mut stroke_points = Vec::new();
+        let curve_pts = curve::fit_curve(3, pts.as_slice(), self.fit_tolerance);
+        for i in 0..(curve_pts.len() / (3 * 3)) {
+            let a = glam::vec2(curve_pts[i * 9 + 0], curve_pts[i * 9 + 1]);
+            let p = glam::vec2(curve_pts[i * 9 + 3], curve_pts[i * 9 + 4]);
+            let b = glam::vec2(curve_pts[i * 9 + 6], curve_pts[i * 9 + 7]);
+            let pressure = curve_pts[i * 9 + 5] / stroke_r;
+            stroke_points.push(StrokePoint {
+                pt: p,
+                a,
+                b,
+                pressure: pressure.clamp(0.05, 1.0)
+            });
+        }
+        stroke_points
+    }
+
     fn get_action(&mut self) -> Action {
         let mut action = Action::new();
         let acts = std::mem::replace(&mut self.frame_creation_acts, Vec::new());
         action.add_list(acts);
         action.add(mem::replace(&mut self.stroke_act, None).unwrap());
+        action.add_list(self.symmetry_copies.take_acts());
         action
     }
 
@@ -41,7 +179,7 @@ impl Pencil {
 
 impl Tool for Pencil {
 
-    fn mouse_click(&mut self, mouse_pos: glam::Vec2, state: &mut EditorState, _ui: &mut egui::Ui, _scene: &mut ScenePanel, _gl: &Arc<glow::Context>) {
+    fn mouse_click(&mut self, mouse_pos: glam::Vec2, state: &mut EditorState, _ui: &mut egui::Ui, scene: &mut ScenePanel, _gl: &Arc<glow::Context>) {
         state.pause();
         let active_frame = active_frame(state);
         if active_frame.is_none() {
@@ -51,69 +189,70 @@ impl Tool for Pencil {
 
         self.frame_creation_acts = frame_act;
 
+        let pressure = self.sample_pressure(mouse_pos, scene);
         let offset = vec2(0.001, 0.0);
         let pts = vec![vec![
             StrokePoint {
                 pt: mouse_pos,
                 a: mouse_pos - offset,
                 b: mouse_pos + offset,
+                pressure
             },
             StrokePoint {
                 pt: mouse_pos + offset,
                 a: mouse_pos - offset,
                 b: mouse_pos + offset,
+                pressure
             }
         ]];
 
-        self.points.push(mouse_pos);
+        let pt = self.stabilize(mouse_pos.extend(pressure), scene.cam_size);
+        self.points.push(pt);
 
-        if let Some((stroke, act)) = Stroke::add(&mut state.project, frame, Stroke {
+        let stroke = Stroke {
             frame: frame,
             points: pts,
             color: state.color,
             r: state.stroke_r,
-            filled: state.stroke_filled
-        }) {
+            filled: state.stroke_filled,
+            taper_start: state.stroke_taper_start,
+            taper_end: state.stroke_taper_end,
+            cap: state.stroke_cap
+        };
+        self.symmetry_copies.update(&mut state.project, &state.symmetry, scene.layer_mat, frame, &stroke);
+        if let Some((stroke, act)) = Stroke::add(&mut state.project, frame, stroke) {
             self.curr_stroke_frame = Some((stroke, frame));
             self.stroke_act = Some(act);
         }
 
     }
 
-    fn mouse_down(&mut self, mouse_pos: glam::Vec2, state: &mut EditorState, _scene: &mut ScenePanel) {
+    fn mouse_down(&mut self, mouse_pos: glam::Vec2, state: &mut EditorState, scene: &mut ScenePanel) {
         state.pause();
 
         if let Some((_stroke, frame)) = self.curr_stroke_frame {
-            if self.points.last().map(|prev_pt| (*prev_pt - mouse_pos).length() > 0.001).unwrap_or(true) {
-                self.points.push(mouse_pos);
-
-                let mut pts = Vec::new();
-                for pt in &self.points {
-                    pts.push(pt.x);
-                    pts.push(pt.y);
-                }
-
-                let mut stroke_points = Vec::new();
-                let curve_pts = curve::fit_curve(2, pts.as_slice(), 1.0);
-                for i in 0..(curve_pts.len() / (2 * 3)) {
-                    let a = glam::vec2(curve_pts[i * 6 + 0], curve_pts[i * 6 + 1]);
-                    let p = glam::vec2(curve_pts[i * 6 + 2], curve_pts[i * 6 + 3]);
-                    let b = glam::vec2(curve_pts[i * 6 + 4], curve_pts[i * 6 + 5]);
-                    stroke_points.push(StrokePoint {
-                        pt: p,
-                        a,
-                        b,
-                    });
+            if self.raw_points.last().map(|prev_pt| (prev_pt.truncate() - mouse_pos).length() > 0.001).unwrap_or(true) {
+                let pressure = self.sample_pressure(mouse_pos, scene);
+                let pt = self.stabilize(mouse_pos.extend(pressure), scene.cam_size);
+                if self.points.last().map(|prev_pt| (prev_pt.truncate() - pt.truncate()).length() <= 0.001).unwrap_or(false) {
+                    return;
                 }
+                self.points.push(pt);
+                let stroke_points = self.fit_points(state.stroke_r);
 
                 mem::replace(&mut self.stroke_act, None).unwrap().undo(&mut state.project);
-                if let Some((new_stroke, act)) = Stroke::add(&mut state.project, frame, Stroke {
+                let stroke = Stroke {
                     frame: frame,
                     color: state.color,
                     r: state.stroke_r,
                     filled: state.stroke_filled,
+                    taper_start: state.stroke_taper_start,
+                    taper_end: state.stroke_taper_end,
+                    cap: state.stroke_cap,
                     points: vec![stroke_points]
-                }) {
+                };
+                self.symmetry_copies.update(&mut state.project, &state.symmetry, scene.layer_mat, frame, &stroke);
+                if let Some((new_stroke, act)) = Stroke::add(&mut state.project, frame, stroke) {
                     self.stroke_act = Some(act);
                     self.curr_stroke_frame = Some((new_stroke, frame));
                 }
@@ -131,15 +270,32 @@ impl Tool for Pencil {
     }
 
     fn tool_panel(&mut self, ui: &mut egui::Ui, state: &mut EditorState) {
-        ui.add(egui::Slider::new(&mut state.stroke_r, 1.0..=50.0));
+        stroke_style_panel(ui, state);
         ui.checkbox(&mut state.stroke_filled, "Filled");
+        symmetry_panel(ui, state);
+        ui.checkbox(&mut self.velocity_pressure, "Pressure from speed")
+            .on_hover_text("Vary the width with drawing speed when the device has no pressure sensitivity");
+        ui.horizontal(|ui| {
+            ui.label("Stabilizer:");
+            egui::ComboBox::new("pencil_stabilizer", "")
+                .selected_text(self.stabilizer.name()).show_ui(ui, |ui| {
+                    for mode in [StabilizerMode::None, StabilizerMode::LazyRope, StabilizerMode::MovingAverage, StabilizerMode::PulledString] {
+                        ui.selectable_value(&mut self.stabilizer, mode, mode.name());
+                    }
+            });
+        });
+        ui.add_enabled(self.stabilizer != StabilizerMode::None, egui::Slider::new(&mut self.stabilizer_strength, 0.0..=1.0).text("Strength"));
+        ui.add(egui::Slider::new(&mut self.fit_tolerance, 0.1..=10.0).logarithmic(true).text("Curve fit tolerance"))
+            .on_hover_text("Higher values give smoother lines with fewer points");
     }
 
     fn reset(&mut self, state: &mut EditorState) {
         if let Some(_) = self.curr_stroke_frame {
             state.actions.add(self.get_action());
             self.points.clear();
+            self.raw_points.clear();
             self.curr_stroke_frame = None;
+            self.prev_sample_time = None;
         }
     }
 
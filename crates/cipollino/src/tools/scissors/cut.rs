@@ -3,7 +3,7 @@ use std::sync::Arc;
 
 use glam::Vec2;
 
-use crate::{editor::state::EditorState, panels::scene::{overlay::OverlayRenderer, ScenePanel}, project::{action::{Action, ObjAction}, obj::{child_obj::ChildObj, obj_list::ObjListTrait, ObjPtr}, stroke::{Stroke, StrokePoint}, Project}, tools::state_machine::ToolState, util::geo::LineSegment};
+use crate::{editor::state::EditorState, panels::scene::{overlay::OverlayRenderer, ScenePanel}, project::{action::{Action, ObjAction}, obj::{child_obj::ChildObj, obj_list::ObjListTrait, ObjPtr}, stroke::{pressure_bezier_segments_to_points, Stroke}, Project}, tools::state_machine::ToolState, util::geo::LineSegment};
 
 use super::neutral::Neutral;
 
@@ -28,20 +28,23 @@ impl Cut {
         let frame = stroke.frame;
         let r = stroke.r;
         let color = stroke.color;
-        for bezier in stroke.iter_bezier_segments() {
+        let taper_start = stroke.taper_start;
+        let taper_end = stroke.taper_end;
+        let cap = stroke.cap;
+        for bezier in stroke.iter_pressure_bezier_segments() {
             new_strokes.last_mut().unwrap().push(bezier);
             for line in self.pts.windows(2) {
                 let line = LineSegment::new(line[0], line[1]);
-                let mut intersection_ts = bezier.intersect_segment_ts(&line);
+                let mut intersection_ts = bezier.map(|pt| pt.truncate()).intersect_segment_ts(&line);
                 if intersection_ts.is_empty() {
                     continue;
                 }
                 intersection_ts.sort_by(|a, b| a.total_cmp(b));
 
-                let intersections = intersection_ts.iter().map(|t| bezier.sample(*t));
+                let intersections = intersection_ts.iter().map(|t| bezier.sample(*t).truncate());
                 for intersection in intersections {
                     let curve = new_strokes.last_mut().unwrap().pop().unwrap();
-                    let t = curve.nearest_t(intersection);
+                    let t = curve.map(|pt| pt.truncate()).nearest_t(intersection);
                     let (before, after) = curve.split(t);
                     new_strokes.last_mut().unwrap().push(before);
                     new_strokes.push(vec![after]);
@@ -54,34 +57,18 @@ impl Cut {
                 acts.push(act);
             }
 
-            for new_stroke in new_strokes {
-                let mut pts = Vec::new(); 
-                pts.push(StrokePoint {
-                    a: new_stroke[0].p0 - (new_stroke[0].a1 - new_stroke[0].p0),
-                    pt: new_stroke[0].p0,
-                    b: new_stroke[0].b0
-                });
-                for bz_pair in new_stroke.windows(2) {
-                    let first = bz_pair[0];
-                    let next = bz_pair[1]; 
-                    pts.push(StrokePoint {
-                        a: first.a1,
-                        pt: first.p1,
-                        b: next.b0
-                    });
-                }
-                let last_curve = new_stroke.last().unwrap();
-                pts.push(StrokePoint {
-                    a: last_curve.a1, 
-                    pt: last_curve.p1,
-                    b: last_curve.p1 + (last_curve.p1 - last_curve.a1) 
-                });
-
+            let n_strokes = new_strokes.len();
+            for (i, new_stroke) in new_strokes.into_iter().enumerate() {
+                let pts = pressure_bezier_segments_to_points(&new_stroke);
+                // Only the pieces at the ends of the original stroke keep its tapers
                 if let Some((_, act)) = Stroke::add(project, frame, Stroke {
                     frame,
                     color,
                     r,
                     filled: false,
+                    taper_start: if i == 0 { taper_start } else { 0.0 },
+                    taper_end: if i == n_strokes - 1 { taper_end } else { 0.0 },
+                    cap,
                     points: vec![pts],
                 }) {
                     acts.push(act);
@@ -108,7 +95,7 @@ impl ToolState for Cut {
 
         let mut acts = Vec::new();
 
-        for stroke_ptr in state.visible_strokes(true) {
+        for stroke_ptr in state.visible_strokes_in_layer_space(true) {
             self.cut_unfilled_stroke(&mut state.project, stroke_ptr, &mut acts); 
         }
         
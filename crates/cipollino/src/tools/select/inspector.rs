@@ -0,0 +1,123 @@
+
+use glam::{vec3, Mat4, Quat, Vec2};
+
+use crate::{editor::{state::EditorState, stroke_commands::{align_strokes, distribute_strokes, Align}}, project::action::Action, tools::Tool, util::ui::drag_value};
+
+use super::{Select, SelectState};
+
+pub struct Inspector;
+
+fn changed(old: Vec2, new: Vec2) -> bool {
+    (old - new).length() > 0.00001
+}
+
+impl Inspector {
+
+    pub fn tool_panel(ui: &mut egui::Ui, state: &mut EditorState, select: &mut Select) {
+        let strokes = state.selection.scene_strokes();
+        if strokes.is_empty() || matches!(select.state, SelectState::Lasso) {
+            return;
+        }
+        // Strokes without any segments leave the bounding box empty
+        if !select.bb_min.is_finite() || !select.bb_max.is_finite() {
+            return;
+        }
+
+        let (scl, rot, trans) = select.trans.to_scale_rotation_translation();
+        let bb_size = select.bb_max - select.bb_min;
+        let old_pos = select.transform((select.bb_min + select.bb_max) * 0.5);
+        let old_size = bb_size * scl.truncate().abs();
+        let old_angle = rot.to_euler(glam::EulerRot::XYZ).2.to_degrees();
+        let old_pivot = select.transform(select.pivot);
+        let (mut pos, mut size, mut angle, mut pivot) = (old_pos, old_size, old_angle, old_pivot);
+
+        let mut pos_change = (false, false);
+        let mut size_change = (false, false);
+        let mut angle_change = (false, false);
+        let mut pivot_change = (false, false);
+        ui.label("Position:");
+        ui.horizontal(|ui| {
+            drag_value(ui, "X", &mut pos.x, f32::MIN..=f32::MAX, Some(&mut pos_change));
+            drag_value(ui, "Y", &mut pos.y, f32::MIN..=f32::MAX, Some(&mut pos_change));
+        });
+        ui.label("Size:");
+        ui.horizontal(|ui| {
+            drag_value(ui, "W", &mut size.x, 0.0..=f32::MAX, Some(&mut size_change));
+            drag_value(ui, "H", &mut size.y, 0.0..=f32::MAX, Some(&mut size_change));
+        });
+        drag_value(ui, "Rotation", &mut angle, -360.0..=360.0, Some(&mut angle_change));
+        ui.label("Pivot:");
+        ui.horizontal(|ui| {
+            drag_value(ui, "X", &mut pivot.x, f32::MIN..=f32::MAX, Some(&mut pivot_change));
+            drag_value(ui, "Y", &mut pivot.y, f32::MIN..=f32::MAX, Some(&mut pivot_change));
+        });
+
+        if changed(old_pos, pos) {
+            let delta = pos - old_pos;
+            select.panel_transformation(Mat4::from_translation(vec3(delta.x, delta.y, 0.0)) * select.trans, state);
+        }
+        // A box with no width or height can't be scaled along that side, and neither side can be scaled down to nothing
+        if changed(old_size, size) {
+            let scale_axis = |scl: f32, size: f32, bb_size: f32| if bb_size > 0.0001 && size > 0.0 { scl.signum() * size / bb_size } else { scl };
+            let new_scl = vec3(scale_axis(scl.x, size.x, bb_size.x), scale_axis(scl.y, size.y, bb_size.y), scl.z);
+            let new_trans = select.pivot_matrix(Mat4::from_scale_rotation_translation(new_scl, rot, trans), select.pivot);
+            select.panel_transformation(new_trans, state);
+        }
+        if (old_angle - angle).abs() > 0.00001 {
+            let new_rot = Quat::from_euler(glam::EulerRot::XYZ, 0.0, 0.0, angle.to_radians());
+            let new_trans = select.pivot_matrix(Mat4::from_scale_rotation_translation(scl, new_rot, trans), select.pivot);
+            select.panel_transformation(new_trans, state);
+        }
+        // The pivot belongs to the tool rather than the strokes, so moving it isn't undoable
+        if changed(old_pivot, pivot) {
+            select.pivot = select.untransform(pivot);
+        }
+        if pos_change.1 || size_change.1 || angle_change.1 {
+            select.finish_panel_action(state);
+        }
+
+        ui.horizontal(|ui| {
+            let pivot = select.transform(select.pivot);
+            let around_pivot = |scl: glam::Vec3| {
+                Mat4::from_translation(vec3(pivot.x, pivot.y, 0.0)) * Mat4::from_scale(scl) * Mat4::from_translation(vec3(-pivot.x, -pivot.y, 0.0))
+            };
+            if ui.button("Flip Horizontal").clicked() {
+                select.panel_transformation(around_pivot(vec3(-1.0, 1.0, 1.0)) * select.trans, state);
+                select.finish_panel_action(state);
+            }
+            if ui.button("Flip Vertical").clicked() {
+                select.panel_transformation(around_pivot(vec3(1.0, -1.0, 1.0)) * select.trans, state);
+                select.finish_panel_action(state);
+            }
+        });
+
+        let mut acts = None;
+        ui.add_enabled_ui(strokes.len() > 1, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Align:");
+                for align in [Align::Left, Align::Center, Align::Right, Align::Top, Align::Middle, Align::Bottom] {
+                    if ui.button(align.name()).clicked() {
+                        acts = Some(align_strokes(&mut state.project, &strokes, align));
+                    }
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Distribute:");
+                if ui.add_enabled(strokes.len() > 2, egui::Button::new("Horizontally")).clicked() {
+                    acts = Some(distribute_strokes(&mut state.project, &strokes, false));
+                }
+                if ui.add_enabled(strokes.len() > 2, egui::Button::new("Vertically")).clicked() {
+                    acts = Some(distribute_strokes(&mut state.project, &strokes, true));
+                }
+            });
+        });
+        // The strokes no longer share one transformation, so the bounding box starts over
+        if let Some(acts) = acts {
+            if !acts.is_empty() {
+                state.actions.add(Action::from_list(acts));
+            }
+            select.reset(state);
+        }
+    }
+
+}
@@ -3,7 +3,7 @@ use std::sync::Arc;
 
 use glam::{vec2, vec3, Mat4, Vec2};
 
-use crate::{editor::{selection::Selection, state::EditorState, EditorSystems}, keybind, panels::scene::{overlay::OverlayRenderer, ScenePanel}, project::{action::Action, obj::obj_list::ObjListTrait, stroke::Stroke}};
+use crate::{editor::{selection::Selection, snapping::snap_point, state::EditorState, EditorSystems}, keybind, panels::scene::{overlay::OverlayRenderer, ScenePanel}, project::{action::Action, obj::obj_list::ObjListTrait, stroke::Stroke}};
 
 
 use self::scale::ScalePivot;
@@ -25,6 +25,15 @@ use scale::Scale;
 mod rotate;
 use rotate::Rotate;
 
+mod inspector;
+use inspector::Inspector;
+
+mod symbols;
+use symbols::SymbolPanel;
+
+mod motion_path;
+use motion_path::MotionPathPanel;
+
 enum SelectState {
     Lasso,
     FreeTransform,
@@ -41,7 +50,17 @@ pub struct Select {
     pivot: Vec2,
     trans: glam::Mat4,
     prev_mouse_pos: Vec2,
-    transform_action: Option<Action>
+    transform_action: Option<Action>,
+
+    // Translation is snapped as a whole from where the drag started
+    drag_start: Vec2,
+    drag_start_trans: glam::Mat4,
+    snap_pts: Vec<Vec2>,
+
+    // Edits typed or dragged in the tool panel, added once the edit is finished
+    panel_action: Option<Action>,
+    symbol_panel: SymbolPanel,
+    motion_path_panel: MotionPathPanel
 }
 
 struct FreeTransformPoints {
@@ -67,6 +86,12 @@ impl Select {
             trans: glam::Mat4::IDENTITY,
             prev_mouse_pos: Vec2::ZERO,
             transform_action: None,
+            drag_start: Vec2::ZERO,
+            drag_start_trans: glam::Mat4::IDENTITY,
+            snap_pts: Vec::new(),
+            panel_action: None,
+            symbol_panel: SymbolPanel::new(),
+            motion_path_panel: MotionPathPanel::new()
         }
     }
 
@@ -89,6 +114,24 @@ impl Select {
         }
     }
 
+    // Moves the selection from its current transformation to the new one, adding to the tool panel's action
+    pub fn panel_transformation(&mut self, new_trans: glam::Mat4, state: &mut EditorState) {
+        let delta = new_trans * self.trans.inverse();
+        let action = self.panel_action.get_or_insert_with(Action::new);
+        for stroke in state.selection.scene_strokes() {
+            if let Some(act) = Stroke::transform(&mut state.project, stroke, delta) {
+                action.add(act);
+            }
+        }
+        self.trans = new_trans;
+    }
+
+    pub fn finish_panel_action(&mut self, state: &mut EditorState) {
+        if let Some(action) = self.panel_action.take() {
+            state.actions.add(action);
+        }
+    }
+
     pub fn transform(&self, pt: Vec2) -> Vec2 {
         let pt3 = self.trans.transform_point3(vec3(pt.x, pt.y, 0.0));
         vec2(pt3.x, pt3.y)
@@ -125,6 +168,24 @@ impl Select {
         }
     }
 
+    // The corners and centre of the selection and the ends of its strokes can all be snapped while moving it
+    fn begin_translate(&mut self, mouse_pos: Vec2, state: &EditorState) {
+        self.drag_start = mouse_pos;
+        self.drag_start_trans = self.trans;
+        self.snap_pts = [self.bb_min, vec2(self.bb_min.x, self.bb_max.y), self.bb_max, vec2(self.bb_max.x, self.bb_min.y), (self.bb_min + self.bb_max) * 0.5]
+            .iter().map(|pt| self.transform(*pt)).collect();
+        for stroke in state.selection.scene_strokes() {
+            if let Some(stroke) = state.project.strokes.get(stroke) {
+                for chain in &stroke.points {
+                    if let (Some(first), Some(last)) = (chain.first(), chain.last()) {
+                        self.snap_pts.push(first.pt);
+                        self.snap_pts.push(last.pt);
+                    }
+                }
+            }
+        }
+    }
+
     pub fn pivot_matrix(&self, mat: glam::Mat4, pivot: glam::Vec2) -> Mat4 {
         let new_pivot = mat.transform_point3(vec3(pivot.x, pivot.y, 0.0));
         let new_pivot = vec2(new_pivot.x, new_pivot.y);
@@ -144,13 +205,24 @@ impl Tool for Select {
             SelectState::FreeTransform => FreeTransform::mouse_click(mouse_pos, state, ui, self, scene, gl),
             _ => {}
         }
+        if let SelectState::Translate = self.state {
+            self.begin_translate(mouse_pos, state);
+        }
         self.prev_mouse_pos = mouse_pos;
     }
 
     fn mouse_down(&mut self, mouse_pos: Vec2, state: &mut EditorState, scene: &mut ScenePanel) {
+        // Handles being dragged follow the mouse onto snap targets
+        let mouse_pos = match self.state {
+            SelectState::Scale(_) | SelectState::Rotate => {
+                let selected = state.selection.scene_strokes();
+                snap_point(state, mouse_pos, scene.cam_size, &selected)
+            },
+            _ => mouse_pos
+        };
         match self.state {
             SelectState::Lasso => Lasso::mouse_down(mouse_pos, self, scene),
-            SelectState::Translate => Translate::mouse_down(mouse_pos, state, self),
+            SelectState::Translate => Translate::mouse_down(mouse_pos, state, self, scene),
             SelectState::Scale(pivot) => Scale::mouse_down(mouse_pos, state, self, &scene, pivot),
             SelectState::Rotate => Rotate::mouse_down(mouse_pos, state, self),
             _ => {}
@@ -185,6 +257,7 @@ impl Tool for Select {
     }
 
     fn reset(&mut self, state: &mut EditorState) {
+        self.finish_panel_action(state);
         if let Selection::Scene(strokes) = &state.selection {
             self.state = SelectState::FreeTransform;
             self.bb_min = Vec2::INFINITY;
@@ -209,6 +282,12 @@ impl Tool for Select {
         }
     }
 
+    fn tool_panel(&mut self, ui: &mut egui::Ui, state: &mut EditorState) {
+        Inspector::tool_panel(ui, state, self);
+        self.motion_path_panel.render(ui, state);
+        self.symbol_panel.render(ui, state);
+    }
+
     fn get_icon(&self) -> &str {
         egui_phosphor::regular::CURSOR
     }
@@ -0,0 +1,53 @@
+
+use crate::{editor::{state::EditorState, stroke_commands::{strokes_along_path, MotionPath, MAX_MOTION_PATH_FRAMES}}, project::{action::Action, easing::Easing, obj::{obj_list::ObjListTrait, ObjPtr}, stroke::Stroke}, util::ui::drag_value};
+
+// Copies the selected strokes along a path stroke picked beforehand
+pub struct MotionPathPanel {
+    path: ObjPtr<Stroke>,
+    motion: MotionPath
+}
+
+impl MotionPathPanel {
+
+    pub fn new() -> Self {
+        Self {
+            path: ObjPtr::null(),
+            motion: MotionPath::default()
+        }
+    }
+
+    pub fn render(&mut self, ui: &mut egui::Ui, state: &mut EditorState) {
+        let strokes = state.selection.scene_strokes();
+        if strokes.is_empty() {
+            return;
+        }
+        let has_path = state.project.strokes.get(self.path).is_some();
+
+        ui.separator();
+        ui.label("Motion Path:");
+        ui.horizontal(|ui| {
+            if ui.add_enabled(strokes.len() == 1, egui::Button::new("Use as Path")).clicked() {
+                self.path = strokes[0];
+            }
+            ui.label(if has_path { "Path picked" } else { "No path picked" });
+        });
+        drag_value(ui, "Frames", &mut self.motion.frames, 1..=MAX_MOTION_PATH_FRAMES, None);
+        ui.checkbox(&mut self.motion.orient, "Orient to Path");
+        egui::ComboBox::from_label("Easing")
+            .selected_text(self.motion.easing.name())
+            .show_ui(ui, |ui| {
+                for option in Easing::ALL {
+                    ui.selectable_value(&mut self.motion.easing, option, option.name());
+                }
+            });
+
+        let can_generate = has_path && strokes.iter().any(|stroke| *stroke != self.path);
+        if ui.add_enabled(can_generate, egui::Button::new("Generate Frames")).clicked() {
+            match strokes_along_path(&mut state.project, &strokes, self.path, self.motion) {
+                Some(acts) => state.actions.add(Action::from_list(acts)),
+                None => state.error_messages.push("The motion path needs to be a stroke with some length.".to_owned())
+            }
+        }
+    }
+
+}
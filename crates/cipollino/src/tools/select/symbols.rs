@@ -0,0 +1,108 @@
+
+use glam::{vec3, Mat4, Quat};
+
+use crate::{editor::state::EditorState, project::{action::{Action, ObjAction}, obj::{child_obj::ChildObj, obj_list::ObjListTrait, ObjPtr}, symbol::{SymbolInstance, SymbolLoop}}, util::ui::drag_value};
+
+// Lists the symbols placed in the active layer's current frame
+pub struct SymbolPanel {
+    // Dragged values keep replacing this action until the mouse is released
+    edit_action: Option<ObjAction>
+}
+
+impl SymbolPanel {
+
+    pub fn new() -> Self {
+        Self {
+            edit_action: None
+        }
+    }
+
+    fn edit(&mut self, state: &mut EditorState, set: impl FnOnce(&mut EditorState) -> Option<ObjAction>) {
+        if let Some(act) = self.edit_action.take() {
+            act.undo(&mut state.project);
+        }
+        self.edit_action = set(state);
+    }
+
+    pub fn render(&mut self, ui: &mut egui::Ui, state: &mut EditorState) {
+        let time = state.frame();
+        let symbols: Vec<ObjPtr<SymbolInstance>> = state.project.layers.get(state.active_layer)
+            .and_then(|layer| layer.get_frame_at(&state.project, time))
+            .map(|frame| frame.get(&state.project).symbols.iter().map(|symbol| symbol.make_ptr()).collect())
+            .unwrap_or_default();
+        if symbols.is_empty() {
+            return;
+        }
+
+        ui.separator();
+        ui.label("Symbols:");
+        for symbol_ptr in symbols {
+            let Some(symbol) = state.project.symbol_instances.get(symbol_ptr) else { continue; };
+            let name = state.project.graphics.get(symbol.graphic).map(|gfx| gfx.name.clone()).unwrap_or("Missing graphic".to_owned());
+            let (scl, rot, trans) = symbol.transform.to_scale_rotation_translation();
+            let old_angle = rot.to_euler(glam::EulerRot::XYZ).2.to_degrees();
+            let (mut pos, mut scale, mut angle) = (trans.truncate(), scl.truncate(), old_angle);
+            let (old_pos, old_scale) = (pos, scale);
+            let mut time_offset = symbol.time_offset;
+            let mut loop_mode = symbol.loop_mode;
+            let mut tint = symbol.tint.to_array();
+            let (old_time_offset, old_loop_mode, old_tint) = (time_offset, loop_mode, tint);
+            let mut delete = false;
+
+            egui::CollapsingHeader::new(name).id_source(symbol_ptr.key).show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    drag_value(ui, "X", &mut pos.x, f32::MIN..=f32::MAX, None);
+                    drag_value(ui, "Y", &mut pos.y, f32::MIN..=f32::MAX, None);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Scale:");
+                    ui.add(egui::DragValue::new(&mut scale.x).speed(0.01));
+                    ui.add(egui::DragValue::new(&mut scale.y).speed(0.01));
+                });
+                drag_value(ui, "Rotation", &mut angle, -360.0..=360.0, None);
+                ui.horizontal(|ui| {
+                    ui.label("Playback:");
+                    egui::ComboBox::new(("symbol_loop", symbol_ptr.key), "").selected_text(loop_mode.name()).show_ui(ui, |ui| {
+                        for mode in [SymbolLoop::Loop, SymbolLoop::PlayOnce, SymbolLoop::SingleFrame] {
+                            ui.selectable_value(&mut loop_mode, mode, mode.name());
+                        }
+                    });
+                });
+                drag_value(ui, "Time offset", &mut time_offset, i32::MIN..=i32::MAX, None);
+                ui.horizontal(|ui| {
+                    ui.label("Tint:");
+                    ui.color_edit_button_rgba_unmultiplied(&mut tint);
+                });
+                delete = ui.button("Delete").clicked();
+            });
+
+            if pos != old_pos || scale != old_scale || angle != old_angle {
+                let transform = Mat4::from_scale_rotation_translation(vec3(scale.x, scale.y, 1.0), Quat::from_rotation_z(angle.to_radians()), vec3(pos.x, pos.y, 0.0));
+                self.edit(state, |state| SymbolInstance::set_transform(&mut state.project, symbol_ptr, transform));
+            }
+            if time_offset != old_time_offset {
+                self.edit(state, |state| SymbolInstance::set_time_offset(&mut state.project, symbol_ptr, time_offset));
+            }
+            if tint != old_tint {
+                self.edit(state, |state| SymbolInstance::set_tint(&mut state.project, symbol_ptr, glam::Vec4::from_array(tint)));
+            }
+            if loop_mode != old_loop_mode {
+                if let Some(act) = SymbolInstance::set_loop_mode(&mut state.project, symbol_ptr, loop_mode) {
+                    state.actions.add(Action::from_single(act));
+                }
+            }
+            if delete {
+                if let Some(act) = SymbolInstance::delete(&mut state.project, symbol_ptr) {
+                    state.actions.add(Action::from_single(act));
+                }
+            }
+        }
+
+        if !ui.input(|i| i.pointer.any_down()) {
+            if let Some(act) = self.edit_action.take() {
+                state.actions.add(Action::from_single(act));
+            }
+        }
+    }
+
+}
@@ -1,6 +1,6 @@
 use glam::{Mat4, Vec2};
 
-use crate::editor::state::EditorState;
+use crate::{editor::{snapping::snap_offset, state::EditorState}, panels::scene::ScenePanel};
 
 use super::{Select, SelectState};
 
@@ -9,9 +9,12 @@ pub struct Translate;
 
 impl Translate {
 
-    pub fn mouse_down(mouse_pos: Vec2, state: &mut EditorState, select: &mut Select) {
-        let delta = mouse_pos - select.prev_mouse_pos;
-        let (scl, rot, trans) = select.trans.to_scale_rotation_translation();
+    pub fn mouse_down(mouse_pos: Vec2, state: &mut EditorState, select: &mut Select, scene: &ScenePanel) {
+        let delta = mouse_pos - select.drag_start;
+        let moved_pts = select.snap_pts.iter().map(|pt| *pt + delta).collect();
+        let selected = state.selection.scene_strokes();
+        let delta = delta + snap_offset(state, &moved_pts, scene.cam_size, &selected);
+        let (scl, rot, trans) = select.drag_start_trans.to_scale_rotation_translation();
         select.apply_transformation(Mat4::from_scale_rotation_translation(scl, rot, trans + glam::vec3(delta.x, delta.y, 0.0)), state);
     }
 
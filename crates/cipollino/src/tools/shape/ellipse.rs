@@ -0,0 +1,59 @@
+
+use glam::{vec2, Vec2};
+
+use crate::{editor::EditorSystems, keybind, project::stroke::StrokePoint};
+
+use super::Shape;
+
+// Length of the handles of a quarter circle arc of radius 1
+pub const ARC_HANDLE_LENGTH: f32 = 0.552_284_8;
+
+pub struct Ellipse {
+
+}
+
+impl Ellipse {
+
+    pub fn new() -> Self {
+        Self {
+
+        }
+    }
+
+}
+
+impl Shape for Ellipse {
+
+    fn points(&self, min: Vec2, max: Vec2) -> Vec<StrokePoint> {
+        let center = (min + max) * 0.5;
+        let r = (max - min) * 0.5;
+        let k = r * ARC_HANDLE_LENGTH;
+        let mut pts = Vec::new();
+        for (dir, tangent) in [(vec2(1.0, 0.0), vec2(0.0, 1.0)), (vec2(0.0, 1.0), vec2(-1.0, 0.0)), (vec2(-1.0, 0.0), vec2(0.0, -1.0)), (vec2(0.0, -1.0), vec2(1.0, 0.0))] {
+            let pt = center + dir * r;
+            pts.push(StrokePoint {
+                a: pt - tangent * k,
+                pt,
+                b: pt + tangent * k,
+                pressure: 1.0
+            });
+        }
+        pts.push(pts[0]);
+        pts
+    }
+
+    fn get_icon(&self) -> &str {
+        egui_phosphor::regular::CIRCLE
+    }
+
+    fn name(&self) -> &str {
+        "Ellipse"
+    }
+
+    fn shortcut(&self, systems: &mut EditorSystems) -> egui::KeyboardShortcut {
+        systems.prefs.get::<EllipseToolKeybind>()
+    }
+
+}
+
+keybind!(EllipseToolKeybind, "Ellipse", NONE, O);
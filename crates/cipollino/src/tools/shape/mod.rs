@@ -0,0 +1,177 @@
+
+use std::{mem, sync::Arc};
+
+use glam::{vec2, Vec2};
+
+use crate::{editor::{snapping::snap_point, state::EditorState, symmetry::SymmetryCopies, EditorSystems}, panels::scene::ScenePanel, project::{action::{Action, ObjAction}, frame::Frame, obj::{child_obj::ChildObj, ObjPtr}, stroke::{Stroke, StrokePoint}}};
+
+use super::{active_frame, stroke_style_panel, symmetry_panel, Tool};
+
+pub mod rectangle;
+pub mod ellipse;
+pub mod polygon;
+
+// A shape drawn by dragging out its bounding box
+pub trait Shape {
+
+    fn points(&self, min: Vec2, max: Vec2) -> Vec<StrokePoint>;
+    fn options_panel(&mut self, _ui: &mut egui::Ui) {}
+
+    fn get_icon(&self) -> &str;
+    fn name(&self) -> &str;
+    fn shortcut(&self, systems: &mut EditorSystems) -> egui::KeyboardShortcut;
+
+}
+
+// Closes a polygon made of straight segments, with the handles a third of the way along each side
+pub fn straight_chain(pts: &[Vec2]) -> Vec<StrokePoint> {
+    let mut res = Vec::new();
+    for i in 0..pts.len() {
+        let prev = pts[(i + pts.len() - 1) % pts.len()];
+        let pt = pts[i];
+        let next = pts[(i + 1) % pts.len()];
+        res.push(StrokePoint {
+            a: pt + (prev - pt) / 3.0,
+            pt,
+            b: pt + (next - pt) / 3.0,
+            pressure: 1.0
+        });
+    }
+    if let Some(first) = res.first() {
+        res.push(*first);
+    }
+    res
+}
+
+pub struct ShapeTool<S: Shape> {
+    shape: S,
+    first_point: Vec2,
+    curr_stroke_frame: Option<(ObjPtr<Stroke>, ObjPtr<Frame>)>,
+    frame_creation_acts: Vec<ObjAction>,
+    stroke_act: Option<ObjAction>,
+    symmetry_copies: SymmetryCopies
+}
+
+impl<S: Shape> ShapeTool<S> {
+
+    pub fn new(shape: S) -> Self {
+        Self {
+            shape,
+            first_point: Vec2::ZERO,
+            curr_stroke_frame: None,
+            frame_creation_acts: Vec::new(),
+            stroke_act: None,
+            symmetry_copies: SymmetryCopies::new()
+        }
+    }
+
+    // Shift keeps the width and height equal, Alt drags from the centre
+    fn bounds(&self, mouse_pos: Vec2, modifiers: egui::Modifiers) -> (Vec2, Vec2) {
+        let mut diff = mouse_pos - self.first_point;
+        if modifiers.shift {
+            let size = diff.x.abs().max(diff.y.abs());
+            diff = vec2(size.copysign(diff.x), size.copysign(diff.y));
+        }
+        let other_corner = if modifiers.alt { self.first_point - diff } else { self.first_point };
+        let corner = self.first_point + diff;
+        (corner.min(other_corner), corner.max(other_corner))
+    }
+
+    fn stroke(&self, state: &EditorState, frame: ObjPtr<Frame>, points: Vec<StrokePoint>) -> Stroke {
+        Stroke {
+            frame,
+            color: state.color,
+            r: state.stroke_r,
+            filled: state.stroke_filled,
+            // Tapers would leave a gap where the outline closes
+            taper_start: 0.0,
+            taper_end: 0.0,
+            cap: state.stroke_cap,
+            points: vec![points]
+        }
+    }
+
+    fn get_action(&mut self) -> Action {
+        let mut action = Action::new();
+        let acts = std::mem::replace(&mut self.frame_creation_acts, Vec::new());
+        action.add_list(acts);
+        action.add(mem::replace(&mut self.stroke_act, None).unwrap());
+        action.add_list(self.symmetry_copies.take_acts());
+        action
+    }
+
+}
+
+impl<S: Shape> Tool for ShapeTool<S> {
+
+    fn mouse_click(&mut self, mouse_pos: Vec2, state: &mut EditorState, _ui: &mut egui::Ui, scene: &mut ScenePanel, _gl: &Arc<glow::Context>) {
+        let active_frame = active_frame(state);
+        if active_frame.is_none() {
+            return;
+        }
+        let (frame, frame_act) = active_frame.unwrap();
+        self.frame_creation_acts = frame_act;
+        let mouse_pos = snap_point(state, mouse_pos, scene.cam_size, &Vec::new());
+        self.first_point = mouse_pos;
+
+        let (min, max) = self.bounds(mouse_pos + vec2(0.001, 0.001), scene.modifiers);
+        let stroke = self.stroke(state, frame, self.shape.points(min, max));
+        self.symmetry_copies.update(&mut state.project, &state.symmetry, scene.layer_mat, frame, &stroke);
+        if let Some((stroke, act)) = Stroke::add(&mut state.project, frame, stroke) {
+            self.curr_stroke_frame = Some((stroke, frame));
+            self.stroke_act = Some(act);
+        }
+    }
+
+    fn mouse_down(&mut self, mouse_pos: Vec2, state: &mut EditorState, scene: &mut ScenePanel) {
+        if let Some((stroke, frame)) = self.curr_stroke_frame {
+            let mut drawn_strokes = self.symmetry_copies.strokes();
+            drawn_strokes.push(stroke);
+            let mouse_pos = snap_point(state, mouse_pos, scene.cam_size, &drawn_strokes);
+            let (min, max) = self.bounds(mouse_pos, scene.modifiers);
+            let stroke = self.stroke(state, frame, self.shape.points(min, max));
+            mem::replace(&mut self.stroke_act, None).unwrap().undo(&mut state.project);
+            self.symmetry_copies.update(&mut state.project, &state.symmetry, scene.layer_mat, frame, &stroke);
+            if let Some((new_stroke, act)) = Stroke::add(&mut state.project, frame, stroke) {
+                self.stroke_act = Some(act);
+                self.curr_stroke_frame = Some((new_stroke, frame));
+            }
+        }
+    }
+
+    fn mouse_release(&mut self, _mouse_pos: Vec2, state: &mut EditorState, _ui: &mut egui::Ui, _scene: &mut ScenePanel, _gl: &Arc<glow::Context>) {
+        state.pause();
+        self.reset(state);
+    }
+
+    fn mouse_cursor(&mut self, _mouse_pos: Vec2, _state: &mut EditorState, _scene: &mut ScenePanel, _gl: &Arc<glow::Context>) -> egui::CursorIcon {
+        egui::CursorIcon::Crosshair
+    }
+
+    fn reset(&mut self, state: &mut EditorState) {
+        if let Some(_) = self.curr_stroke_frame {
+            state.actions.add(self.get_action());
+            self.curr_stroke_frame = None;
+        }
+    }
+
+    fn tool_panel(&mut self, ui: &mut egui::Ui, state: &mut EditorState) {
+        stroke_style_panel(ui, state);
+        ui.checkbox(&mut state.stroke_filled, "Filled");
+        self.shape.options_panel(ui);
+        symmetry_panel(ui, state);
+    }
+
+    fn get_icon(&self) -> &str {
+        self.shape.get_icon()
+    }
+
+    fn name(&self) -> &str {
+        self.shape.name()
+    }
+
+    fn shortcut(&self, systems: &mut EditorSystems) -> egui::KeyboardShortcut {
+        self.shape.shortcut(systems)
+    }
+
+}
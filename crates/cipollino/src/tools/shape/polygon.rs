@@ -0,0 +1,68 @@
+
+use glam::{vec2, Vec2};
+
+use crate::{editor::EditorSystems, keybind, project::stroke::StrokePoint};
+
+use super::{straight_chain, Shape};
+
+pub struct Polygon {
+    sides: u32,
+    star: bool,
+    // Radius of the star's inner corners, relative to the outer ones
+    inner_ratio: f32
+}
+
+impl Polygon {
+
+    pub fn new() -> Self {
+        Self {
+            sides: 5,
+            star: false,
+            inner_ratio: 0.5
+        }
+    }
+
+}
+
+impl Shape for Polygon {
+
+    fn points(&self, min: Vec2, max: Vec2) -> Vec<StrokePoint> {
+        let center = (min + max) * 0.5;
+        let r = (max - min) * 0.5;
+        let n_corners = if self.star { self.sides * 2 } else { self.sides };
+        let corners: Vec<Vec2> = (0..n_corners).map(|i| {
+            let angle = std::f32::consts::FRAC_PI_2 + std::f32::consts::TAU * (i as f32) / (n_corners as f32);
+            let scale = if self.star && i % 2 == 1 { self.inner_ratio } else { 1.0 };
+            center + vec2(angle.cos(), angle.sin()) * r * scale
+        }).collect();
+        straight_chain(&corners)
+    }
+
+    fn options_panel(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Sides:");
+            ui.add(egui::DragValue::new(&mut self.sides).clamp_range(3..=32));
+        });
+        ui.checkbox(&mut self.star, "Star");
+        ui.add_enabled(self.star, egui::Slider::new(&mut self.inner_ratio, 0.05..=1.0).text("Inner radius"));
+    }
+
+    fn get_icon(&self) -> &str {
+        if self.star {
+            egui_phosphor::regular::STAR
+        } else {
+            egui_phosphor::regular::POLYGON
+        }
+    }
+
+    fn name(&self) -> &str {
+        "Polygon"
+    }
+
+    fn shortcut(&self, systems: &mut EditorSystems) -> egui::KeyboardShortcut {
+        systems.prefs.get::<PolygonToolKeybind>()
+    }
+
+}
+
+keybind!(PolygonToolKeybind, "Polygon", NONE, P);
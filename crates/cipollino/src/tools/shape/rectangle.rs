@@ -0,0 +1,83 @@
+
+use glam::{vec2, Vec2};
+
+use crate::{editor::EditorSystems, keybind, project::stroke::StrokePoint};
+
+use super::{ellipse::ARC_HANDLE_LENGTH, straight_chain, Shape};
+
+pub struct Rectangle {
+    corner_radius: f32
+}
+
+impl Rectangle {
+
+    pub fn new() -> Self {
+        Self {
+            corner_radius: 0.0
+        }
+    }
+
+}
+
+impl Shape for Rectangle {
+
+    fn points(&self, min: Vec2, max: Vec2) -> Vec<StrokePoint> {
+        let size = max - min;
+        let r = self.corner_radius.min(size.x * 0.5).min(size.y * 0.5);
+        let corners = [min, vec2(max.x, min.y), max, vec2(min.x, max.y)];
+        if r < 0.0001 {
+            return straight_chain(&corners);
+        }
+
+        // Each rounded corner is a quarter circle arc between two anchors
+        let mut pts = Vec::new();
+        for i in 0..4 {
+            let corner = corners[i];
+            let prev = corners[(i + 3) % 4];
+            let next = corners[(i + 1) % 4];
+            let in_len = (corner - prev).length();
+            let out_len = (next - corner).length();
+            let dir_in = (corner - prev) / in_len;
+            let dir_out = (next - corner) / out_len;
+
+            let arc_start = corner - dir_in * r;
+            let arc_end = corner + dir_out * r;
+            pts.push(StrokePoint {
+                a: arc_start - dir_in * (in_len - 2.0 * r) / 3.0,
+                pt: arc_start,
+                b: arc_start + dir_in * r * ARC_HANDLE_LENGTH,
+                pressure: 1.0
+            });
+            pts.push(StrokePoint {
+                a: arc_end - dir_out * r * ARC_HANDLE_LENGTH,
+                pt: arc_end,
+                b: arc_end + dir_out * (out_len - 2.0 * r) / 3.0,
+                pressure: 1.0
+            });
+        }
+        pts.push(pts[0]);
+        pts
+    }
+
+    fn options_panel(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Corner radius:");
+            ui.add(egui::DragValue::new(&mut self.corner_radius).clamp_range(0.0..=1000.0).speed(1.0));
+        });
+    }
+
+    fn get_icon(&self) -> &str {
+        egui_phosphor::regular::RECTANGLE
+    }
+
+    fn name(&self) -> &str {
+        "Rectangle"
+    }
+
+    fn shortcut(&self, systems: &mut EditorSystems) -> egui::KeyboardShortcut {
+        systems.prefs.get::<RectangleToolKeybind>()
+    }
+
+}
+
+keybind!(RectangleToolKeybind, "Rectangle", NONE, R);
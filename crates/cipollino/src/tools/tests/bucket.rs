@@ -0,0 +1,27 @@
+
+use glam::vec2;
+
+use crate::{project::{obj::child_obj::ChildObj, stroke::Stroke}, tools::bucket::gap_closures};
+
+use crate::project::tests::{line_stroke, TestProject};
+
+#[test]
+fn bucket_gap_closures() {
+    let mut test = TestProject::new();
+    let frame = test.frame();
+    let strokes = vec![
+        Stroke::add(&mut test.project, frame, line_stroke(vec2(0.0, 0.0), vec2(10.0, 0.0))).unwrap().0,
+        Stroke::add(&mut test.project, frame, line_stroke(vec2(12.0, 0.0), vec2(12.0, 10.0))).unwrap().0,
+        Stroke::add(&mut test.project, frame, line_stroke(vec2(5.0, 3.0), vec2(5.0, 8.0))).unwrap().0
+    ];
+
+    assert!(gap_closures(&test.project, &strokes, 0.0).is_empty());
+    assert_eq!(gap_closures(&test.project, &strokes, 2.5), vec![(vec2(10.0, 0.0), vec2(12.0, 0.0))]);
+
+    // Ends can also be closed against the middle of another line
+    let closures = gap_closures(&test.project, &strokes, 3.5);
+    assert_eq!(closures.len(), 2);
+    let (end, nearest) = closures[1];
+    assert_eq!(end, vec2(5.0, 3.0));
+    assert!((nearest - vec2(5.0, 0.0)).length() < 0.2);
+}
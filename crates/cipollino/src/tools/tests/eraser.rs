@@ -0,0 +1,54 @@
+
+use glam::vec2;
+
+use crate::{project::{obj::{child_obj::ChildObj, obj_list::ObjListTrait}, stroke::{Stroke, StrokePoint}}, tools::eraser::erase_stroke};
+
+use crate::project::tests::TestProject;
+
+#[test]
+fn erase_splits_unfilled_strokes() {
+    let mut test = TestProject::new();
+    let frame = test.frame();
+    let dot = vec![StrokePoint { a: vec2(20.0, 0.0), pt: vec2(20.0, 0.0), b: vec2(20.0, 0.0), pressure: 1.0 }];
+    let (stroke, _) = Stroke::add(&mut test.project, frame, Stroke {
+        taper_start: 3.0,
+        taper_end: 4.0,
+        points: vec![vec![
+            StrokePoint { a: vec2(-1.0, 0.0), pt: vec2(0.0, 0.0), b: vec2(1.0, 0.0), pressure: 1.0 },
+            StrokePoint { a: vec2(9.0, 0.0), pt: vec2(10.0, 0.0), b: vec2(11.0, 0.0), pressure: 1.0 }
+        ], dot],
+        ..Stroke::default()
+    }).unwrap();
+    let (above, _) = Stroke::add(&mut test.project, frame, Stroke::default()).unwrap();
+
+    // The pieces stay chains of the same stroke, under the strokes drawn after it
+    let mut acts = Vec::new();
+    erase_stroke(&mut test.project, stroke, vec2(5.0, -3.0), vec2(5.0, 3.0), 1.0, &mut acts).unwrap();
+    test.check_undo_redo(acts, |project, applied| {
+        let strokes: Vec<_> = project.frames.get(frame).unwrap().strokes.iter().map(|stroke| stroke.make_ptr()).collect();
+        assert_eq!(strokes, vec![stroke, above]);
+        let points = &project.strokes.get(stroke).unwrap().points;
+        if !applied {
+            assert_eq!(points.len(), 2);
+            return;
+        }
+        assert_eq!(points.len(), 3);
+        assert_eq!(points[0][0].pt, vec2(0.0, 0.0));
+        assert!((points[0].last().unwrap().pt - vec2(4.0, 0.0)).length() < 0.01);
+        assert!((points[1][0].pt - vec2(6.0, 0.0)).length() < 0.01);
+        assert_eq!(points[1].last().unwrap().pt, vec2(10.0, 0.0));
+        assert_eq!(points[2][0].pt, vec2(20.0, 0.0));
+    });
+
+    // Dots are erased whole
+    let mut acts = Vec::new();
+    erase_stroke(&mut test.project, stroke, vec2(20.0, -3.0), vec2(20.0, 3.0), 1.0, &mut acts).unwrap();
+    test.check_undo_redo(acts, |project, applied| {
+        assert_eq!(project.strokes.get(stroke).unwrap().points.len(), if applied { 2 } else { 3 });
+    });
+
+    // Erasing away from the stroke does nothing
+    let mut acts = Vec::new();
+    assert!(erase_stroke(&mut test.project, stroke, vec2(0.0, 5.0), vec2(10.0, 5.0), 1.0, &mut acts).is_none());
+    assert!(acts.is_empty());
+}
@@ -0,0 +1,5 @@
+
+mod eraser;
+mod shape;
+mod node;
+mod bucket;
@@ -0,0 +1,68 @@
+
+use glam::{vec2, Vec2};
+
+use crate::{project::{obj::{child_obj::ChildObj, obj_list::ObjListTrait}, stroke::{iter_bezier_segments, Stroke, StrokePoint}}, tools::node::{delete_point, insert_point}};
+
+use crate::project::tests::TestProject;
+
+#[test]
+fn node_insert_and_delete() {
+    let mut test = TestProject::new();
+    let frame = test.frame();
+    let (stroke, _) = Stroke::add(&mut test.project, frame, Stroke {
+        points: vec![vec![
+            StrokePoint { a: vec2(-1.0, 0.0), pt: vec2(0.0, 0.0), b: vec2(0.0, 5.0), pressure: 1.0 },
+            StrokePoint { a: vec2(10.0, 5.0), pt: vec2(10.0, 0.0), b: vec2(11.0, 0.0), pressure: 0.5 }
+        ]],
+        ..Stroke::default()
+    }).unwrap();
+
+    // Inserting a point keeps the curve's shape
+    let orig = test.project.strokes.get(stroke).unwrap().points.clone();
+    let mut points = orig.clone();
+    assert_eq!(insert_point(&mut points, 0, 0, 0.5), Some(1));
+    assert_eq!(points[0].len(), 3);
+    let orig_segment = iter_bezier_segments(&orig[0]).next().unwrap();
+    assert!((points[0][1].pt - orig_segment.sample(0.5)).length() < 0.0001);
+    assert!((points[0][1].pressure - 0.75).abs() < 0.0001);
+    let halves: Vec<_> = iter_bezier_segments(&points[0]).collect();
+    assert!((halves[1].sample(0.5) - orig_segment.sample(0.75)).length() < 0.0001);
+
+    let act = Stroke::set_points(&mut test.project, stroke, points.clone()).unwrap();
+    test.check_undo_redo(vec![act], |project, applied| {
+        assert_eq!(project.strokes.get(stroke).unwrap().points[0].len(), if applied { 3 } else { 2 });
+    });
+
+    delete_point(&mut points, 0, 1);
+    assert_eq!(points[0].len(), 2);
+    delete_point(&mut points, 0, 0);
+    assert!(points.is_empty());
+}
+
+#[test]
+fn node_edits_keep_closed_chains_closed() {
+    let pt = |pt: Vec2, a: Vec2, b: Vec2| StrokePoint { a, pt, b, pressure: 1.0 };
+    let orig = vec![vec![
+        pt(vec2(0.0, 0.0), vec2(-1.0, 0.0), vec2(1.0, 0.0)),
+        pt(vec2(4.0, 0.0), vec2(3.0, 0.0), vec2(4.0, 1.0)),
+        pt(vec2(2.0, 4.0), vec2(3.0, 3.0), vec2(1.0, 3.0)),
+        pt(vec2(0.0, 0.0), vec2(0.0, 1.0), vec2(0.0, -1.0))
+    ]];
+
+    // Splitting the first segment changes the first point's outgoing handle, not the last point's incoming one
+    let mut points = orig.clone();
+    insert_point(&mut points, 0, 0, 0.5);
+    let last = points[0].last().unwrap();
+    assert_eq!((last.pt, last.a), (vec2(0.0, 0.0), vec2(0.0, 1.0)));
+
+    // Splitting the closing segment changes the shared point's incoming handle, which the first point shows
+    let mut points = orig.clone();
+    insert_point(&mut points, 0, 2, 0.5);
+    assert_eq!(points[0][0].a, points[0].last().unwrap().a);
+    assert_eq!(points[0][0].b, vec2(1.0, 0.0));
+
+    let mut points = orig.clone();
+    delete_point(&mut points, 0, 0);
+    assert_eq!(points[0].len(), 3);
+    assert_eq!(points[0][0].pt, points[0].last().unwrap().pt);
+}
@@ -0,0 +1,24 @@
+
+use glam::vec2;
+
+use crate::{project::stroke::iter_bezier_segments, tools::shape::{ellipse::Ellipse, rectangle::Rectangle, Shape}};
+
+#[test]
+fn shapes_are_closed_curves() {
+    let circle = Ellipse::new().points(vec2(-1.0, -1.0), vec2(1.0, 1.0));
+    assert_eq!(circle.first().unwrap().pt, circle.last().unwrap().pt);
+    for segment in iter_bezier_segments(&circle) {
+        for pt in segment.to_discrete::<10>() {
+            assert!((pt.length() - 1.0).abs() < 0.001);
+        }
+    }
+
+    let rect = Rectangle::new().points(vec2(0.0, 0.0), vec2(4.0, 2.0));
+    assert_eq!(rect.len(), 5);
+    assert_eq!(rect.first().unwrap().pt, rect.last().unwrap().pt);
+    for segment in iter_bezier_segments(&rect) {
+        // Straight sides stay on the rectangle's edges
+        let mid = segment.sample(0.5);
+        assert!(mid.x.abs() < 0.0001 || (mid.x - 4.0).abs() < 0.0001 || mid.y.abs() < 0.0001 || (mid.y - 2.0).abs() < 0.0001);
+    }
+}
@@ -34,6 +34,23 @@ impl LineSegment {
 
     }
 
+    pub fn distance_to_point(&self, pt: Vec2) -> f32 {
+        let dir = self.p1 - self.p0;
+        let len_sq = dir.length_squared();
+        let t = if len_sq > 0.0 { ((pt - self.p0).dot(dir) / len_sq).clamp(0.0, 1.0) } else { 0.0 };
+        (self.p0 + dir * t - pt).length()
+    }
+
+    pub fn distance(&self, other: &LineSegment) -> f32 {
+        if self.intersect(LineSegment::new(other.p0, other.p1)).is_some() {
+            return 0.0;
+        }
+        self.distance_to_point(other.p0)
+            .min(self.distance_to_point(other.p1))
+            .min(other.distance_to_point(self.p0))
+            .min(other.distance_to_point(self.p1))
+    }
+
     // Returns the vector q and value d such that for all points p on the line, q dot p = d.
     pub fn get_characteristic(&self) -> (Vec2, f32) {
         // y = mx + b
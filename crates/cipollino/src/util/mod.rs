@@ -6,6 +6,7 @@ pub mod color;
 pub mod fs;
 pub mod bson;
 pub mod ffmpeg;
+pub mod polygon;
 
 pub fn next_unique_name<'a, T>(name: &String, names: T) -> String where T: Iterator<Item = &'a str> + Clone {
     if names.clone().position(|other_name| other_name.to_lowercase() == name.to_lowercase()).is_none() {
@@ -0,0 +1,280 @@
+
+use std::collections::HashMap;
+
+use glam::{vec2, Vec2};
+
+// A region bounded by closed rings, filled using the even-odd rule just like filled strokes
+pub type Polygon = Vec<Vec<Vec2>>;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BooleanOp {
+    Union,
+    Difference,
+    Intersection,
+    // Regions covered by exactly one of the polygons
+    Exclude
+}
+
+impl BooleanOp {
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BooleanOp::Union => "Union",
+            BooleanOp::Difference => "Difference",
+            BooleanOp::Intersection => "Intersection",
+            BooleanOp::Exclude => "Exclude",
+        }
+    }
+
+}
+
+pub fn point_in_polygon(polygon: &Polygon, pt: Vec2) -> bool {
+    let mut inside = false;
+    for ring in polygon {
+        for i in 0..ring.len() {
+            let a = ring[i];
+            let b = ring[(i + 1) % ring.len()];
+            if (a.y > pt.y) != (b.y > pt.y) {
+                let x = a.x + (pt.y - a.y) / (b.y - a.y) * (b.x - a.x);
+                if x > pt.x {
+                    inside = !inside;
+                }
+            }
+        }
+    }
+    inside
+}
+
+pub fn polygon_bounds(polygon: &Polygon) -> Option<(Vec2, Vec2)> {
+    let mut pts = polygon.iter().flatten();
+    let first = *pts.next()?;
+    Some(pts.fold((first, first), |(min, max), pt| (min.min(*pt), max.max(*pt))))
+}
+
+// The region swept by a circle of radius r moving from p0 to p1
+pub fn capsule_polygon(p0: Vec2, p1: Vec2, r: f32) -> Vec<Vec2> {
+    let dir = (p1 - p0).try_normalize().unwrap_or(vec2(1.0, 0.0));
+    let norm = vec2(-dir.y, dir.x);
+    let n = 16;
+    let mut res = Vec::new();
+    for (center, start) in [(p1, -norm), (p0, norm)] {
+        let end = vec2(-start.y, start.x);
+        for i in 0..=n {
+            let a = std::f32::consts::PI * (i as f32) / (n as f32);
+            res.push(center + r * (start * a.cos() - end * a.sin()));
+        }
+    }
+    res
+}
+
+// Returns the parameters along both segments at which they cross
+fn intersect_segments(a0: Vec2, a1: Vec2, b0: Vec2, b1: Vec2) -> Option<(f32, f32)> {
+    let da = a1 - a0;
+    let db = b1 - b0;
+    let denom = da.perp_dot(db);
+    if denom.abs() < 1e-12 {
+        return None;
+    }
+    let diff = b0 - a0;
+    let t = diff.perp_dot(db) / denom;
+    let u = diff.perp_dot(da) / denom;
+    if t < 0.0 || t > 1.0 || u < 0.0 || u > 1.0 {
+        return None;
+    }
+    Some((t, u))
+}
+
+fn add_polygon_edges(polygon: &Polygon, verts: &mut Vec<Vec2>) -> Vec<(usize, usize)> {
+    let mut edges = Vec::new();
+    for ring in polygon {
+        if ring.len() < 3 {
+            continue;
+        }
+        let first = verts.len();
+        verts.extend_from_slice(ring);
+        for i in 0..ring.len() {
+            edges.push((first + i, first + (i + 1) % ring.len()));
+        }
+    }
+    edges
+}
+
+fn split_edges(edges: &Vec<(usize, usize)>, mut splits: Vec<Vec<(f32, usize)>>) -> Vec<(usize, usize)> {
+    let mut res = Vec::new();
+    for (edge, edge_splits) in edges.iter().zip(splits.iter_mut()) {
+        edge_splits.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut prev = edge.0;
+        for (_, vert) in edge_splits.iter().chain(std::iter::once(&(1.0, edge.1))) {
+            if *vert != prev {
+                res.push((prev, *vert));
+            }
+            prev = *vert;
+        }
+    }
+    res
+}
+
+/*
+    Boolean operation between two even-odd polygons.
+    Both boundaries are split where they cross, then the pieces of each boundary lying inside or outside the other
+    polygon are kept depending on the operation and joined back into rings. Since the result is also filled with
+    the even-odd rule, the orientation of the rings does not matter.
+*/
+pub fn polygon_boolean(a: &Polygon, b: &Polygon, op: BooleanOp) -> Polygon {
+    // With the even-odd rule, overlapping regions already cancel out
+    if op == BooleanOp::Exclude {
+        return a.iter().chain(b.iter()).filter(|ring| ring.len() >= 3).cloned().collect();
+    }
+
+    let mut verts = Vec::new();
+    let a_edges = add_polygon_edges(a, &mut verts);
+    let b_edges = add_polygon_edges(b, &mut verts);
+    // Vertices found to be at the same position as another one
+    let mut merged: HashMap<usize, usize> = HashMap::new();
+
+    let eps = 1e-5;
+    let mut a_splits = vec![Vec::new(); a_edges.len()];
+    let mut b_splits = vec![Vec::new(); b_edges.len()];
+    for (i, a_edge) in a_edges.iter().enumerate() {
+        let (a0, a1) = (verts[a_edge.0], verts[a_edge.1]);
+        for (j, b_edge) in b_edges.iter().enumerate() {
+            let (b0, b1) = (verts[b_edge.0], verts[b_edge.1]);
+            if let Some((t, u)) = intersect_segments(a0, a1, b0, b1) {
+                let a_vert = if t < eps { Some(a_edge.0) } else if t > 1.0 - eps { Some(a_edge.1) } else { None };
+                let b_vert = if u < eps { Some(b_edge.0) } else if u > 1.0 - eps { Some(b_edge.1) } else { None };
+                let vert = match (a_vert, b_vert) {
+                    (Some(a_vert), Some(b_vert)) => {
+                        merged.insert(b_vert, a_vert);
+                        a_vert
+                    },
+                    (Some(vert), None) | (None, Some(vert)) => vert,
+                    (None, None) => {
+                        verts.push(a0 + (a1 - a0) * t);
+                        verts.len() - 1
+                    }
+                };
+                a_splits[i].push((t, vert));
+                b_splits[j].push((u, vert));
+            }
+        }
+    }
+
+    let resolve = |vert: usize| *merged.get(&vert).unwrap_or(&vert);
+    let a_pieces = split_edges(&a_edges, a_splits);
+    let b_pieces = split_edges(&b_edges, b_splits);
+
+    let keep_inside_b = op == BooleanOp::Intersection;
+    let keep_inside_a = op != BooleanOp::Union;
+    let mut kept = Vec::new();
+    for (pieces, other, keep_inside) in [(&a_pieces, b, keep_inside_b), (&b_pieces, a, keep_inside_a)] {
+        for (from, to) in pieces {
+            let (from, to) = (resolve(*from), resolve(*to));
+            if from == to {
+                continue;
+            }
+            let mid = (verts[from] + verts[to]) * 0.5;
+            if point_in_polygon(other, mid) == keep_inside {
+                kept.push((from, to));
+            }
+        }
+    }
+
+    join_pieces(&verts, &kept)
+}
+
+// Joins boundary pieces sharing vertices back into rings
+fn join_pieces(verts: &Vec<Vec2>, kept: &Vec<(usize, usize)>) -> Polygon {
+    let mut pieces_at_vert: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (idx, (from, to)) in kept.iter().enumerate() {
+        pieces_at_vert.entry(*from).or_default().push(idx);
+        pieces_at_vert.entry(*to).or_default().push(idx);
+    }
+    let mut used = vec![false; kept.len()];
+    let mut res = Vec::new();
+    for start in 0..kept.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+        let (first, mut curr) = kept[start];
+        let mut ring = vec![verts[first]];
+        while curr != first {
+            ring.push(verts[curr]);
+            let next = pieces_at_vert.get(&curr).and_then(|pieces| pieces.iter().find(|piece| !used[**piece]).copied());
+            if let Some(next) = next {
+                used[next] = true;
+                let (from, to) = kept[next];
+                curr = if from == curr { to } else { from };
+            } else {
+                break;
+            }
+        }
+        if curr == first && ring.len() >= 3 {
+            res.push(ring);
+        }
+    }
+    res
+}
+
+// How many times the rings wind around the point, counting their direction
+fn winding_number(polygon: &Polygon, pt: Vec2) -> i32 {
+    let mut winding = 0;
+    for ring in polygon {
+        for i in 0..ring.len() {
+            let a = ring[i];
+            let b = ring[(i + 1) % ring.len()];
+            let side = (b - a).perp_dot(pt - a);
+            if a.y <= pt.y && b.y > pt.y && side > 0.0 {
+                winding += 1;
+            } else if a.y > pt.y && b.y <= pt.y && side < 0.0 {
+                winding -= 1;
+            }
+        }
+    }
+    winding
+}
+
+/*
+    Turns rings filled with the nonzero rule into an even-odd polygon covering the same region.
+    The rings are split where they cross each other or themselves, and only the pieces with a filled
+    region on one side and an empty one on the other are kept.
+*/
+pub fn polygon_nonzero(polygon: &Polygon) -> Polygon {
+    let mut verts = Vec::new();
+    let edges = add_polygon_edges(polygon, &mut verts);
+
+    let eps = 1e-5;
+    let mut splits = vec![Vec::new(); edges.len()];
+    for i in 0..edges.len() {
+        for j in (i + 1)..edges.len() {
+            let (a0, a1) = (verts[edges[i].0], verts[edges[i].1]);
+            let (b0, b1) = (verts[edges[j].0], verts[edges[j].1]);
+            let Some((t, u)) = intersect_segments(a0, a1, b0, b1) else { continue; };
+            let a_inner = t > eps && t < 1.0 - eps;
+            let b_inner = u > eps && u < 1.0 - eps;
+            match (a_inner, b_inner) {
+                (true, true) => {
+                    verts.push(a0 + (a1 - a0) * t);
+                    splits[i].push((t, verts.len() - 1));
+                    splits[j].push((u, verts.len() - 1));
+                },
+                // One edge ends on the other one
+                (true, false) => splits[i].push((t, if u < 0.5 { edges[j].0 } else { edges[j].1 })),
+                (false, true) => splits[j].push((u, if t < 0.5 { edges[i].0 } else { edges[i].1 })),
+                (false, false) => {}
+            }
+        }
+    }
+
+    let mut kept = Vec::new();
+    for (from, to) in split_edges(&edges, splits) {
+        let (p0, p1) = (verts[from], verts[to]);
+        let Some(dir) = (p1 - p0).try_normalize() else { continue; };
+        let mid = (p0 + p1) * 0.5;
+        let side = vec2(-dir.y, dir.x) * 0.001;
+        if (winding_number(polygon, mid + side) != 0) != (winding_number(polygon, mid - side) != 0) {
+            kept.push((from, to));
+        }
+    }
+    join_pieces(&verts, &kept)
+}
@@ -1,6 +1,13 @@
 
+// Modifiers have to match exactly, so that a shortcut like Alt+A doesn't also trigger one bound to just A
 pub fn consume_shortcut(ui: &mut egui::Ui, shortcut: &egui::KeyboardShortcut) -> bool {
-    let res = ui.input_mut(|i| i.consume_shortcut(shortcut)) && !ui.memory(|mem| mem.focus().is_some());
+    let res = ui.input_mut(|i| {
+        let n_events = i.events.len();
+        i.events.retain(|event| !matches!(event,
+            egui::Event::Key { key, modifiers, pressed: true, .. } if *key == shortcut.logical_key && modifiers.matches_exact(shortcut.modifiers)
+        ));
+        i.events.len() != n_events
+    }) && !ui.memory(|mem| mem.focus().is_some());
     if res {
         ui.ctx().request_repaint();
     }
@@ -1,17 +1,13 @@
 
 use convert_case::Casing;
 use proc_macro::TokenStream;
-use syn::{parse_macro_input, Attribute, Data, DeriveInput, Field, Ident};
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Field, Fields, Ident};
 use quote::{format_ident, quote, ToTokens, TokenStreamExt};
 
-fn attr_list_contains(attrs: &Vec<Attribute>, attr: &str) -> bool {
-    let attr = format!("#[{}]", attr);
-    for other_attr in attrs {
-        if other_attr.to_token_stream().to_string() == attr {
-            return true;
-        } 
-    }
-    false
+mod obj_serialize;
+
+fn attr_list_contains(attrs: &[Attribute], attr: &str) -> bool {
+    attrs.iter().any(|other_attr| other_attr.path().is_ident(attr))
 }
 
 fn field_has_attr(field: &Field, attr: &str) -> bool {
@@ -39,9 +35,10 @@ pub fn object(input: TokenStream) -> TokenStream {
 
         if field_has_attr(&field, "field") {
             let setter_name = format_ident!("set_{}", field_name.clone().to_token_stream().to_string());
+            let field_name_str = field_name.clone().to_token_stream().to_string();
             field_setters.append_all(quote! {
                 pub fn #setter_name(project: &mut Project, ptr: ObjPtr<Self>, #field_name: #ty) -> Option<ObjAction> {
-                    project.#list_name.get_then_mut(ptr, |obj| {
+                    let act = project.#list_name.get_then_mut(ptr, |obj| {
                         let init_val = obj.#field_name.clone();
                         obj.#field_name = #field_name.clone();
                         ObjAction::new(move |proj| {
@@ -49,7 +46,9 @@ pub fn object(input: TokenStream) -> TokenStream {
                         }, move |proj| {
                             #name::#setter_name(proj, ptr, init_val.clone());
                         })
-                    })
+                    })?;
+                    project.#list_name.push_event(crate::project::obj::obj_event::ObjEvent::Modified(ptr, #field_name_str));
+                    Some(act)
                 } 
             });
         }
@@ -92,136 +91,68 @@ pub fn object(input: TokenStream) -> TokenStream {
 #[proc_macro_derive(ObjClone, attributes(parent))]
 pub fn obj_clone(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
-    let fields = if let Data::Struct(data) = ast.data {
-        data.fields
-    } else {
-        panic!("object must be a struct!");
-    };
     let name = ast.ident;
 
-    let mut obj_clone_impl = quote!{};
-    for field in fields {
-        let field_name = field.ident.clone();
-        obj_clone_impl.append_all(quote! {
-            #field_name: self.#field_name.obj_clone(project),
-        });
-    }
-
-    quote! {
-
-        impl ObjClone for #name {
-
-            fn obj_clone(&self, project: &mut Project) -> Self {
+    let obj_clone_impl = match ast.data {
+        Data::Struct(data) => {
+            let mut fields_impl = quote!{};
+            for field in data.fields {
+                let field_name = field.ident.clone();
+                fields_impl.append_all(quote! {
+                    #field_name: self.#field_name.obj_clone(project),
+                });
+            }
+            quote! {
                 Self {
-                    #obj_clone_impl
+                    #fields_impl
                 }
             }
-
-        }
-
-    }.into()
-}
-
-#[proc_macro_derive(ObjSerialize, attributes(parent))]
-pub fn obj_serialize(input: TokenStream) -> TokenStream {
-    let ast = parse_macro_input!(input as DeriveInput);
-    let fields = if let Data::Struct(data) = ast.data {
-        data.fields
-    } else {
-        panic!("object must be a struct!");
-    };
-    let name = ast.ident;
-
-    let raw_data_name = format_ident!("{}RawData", name);
-
-    let mut serialize_impl = quote!{};
-    let mut serialize_full_impl = quote!{};
-    let mut deserialize_impl = quote!{};
-    let mut raw_data_struct_fields = quote!{};
-    let mut to_raw_data_impl = quote!{};
-    let mut from_raw_data_impl = quote!{};
-    for field in fields {
-        let field_name = field.ident.clone();
-        let ty = field.ty.to_token_stream();
-
-        if !field_has_attr(&field, "parent") {
-            let field_name_str = field_name.to_token_stream().to_string();
-            serialize_impl.append_all(quote! {
-                #field_name_str: self.#field_name.obj_serialize(project, asset_file),
-            });
-
-            serialize_full_impl.append_all(quote! {
-                #field_name_str: self.#field_name.obj_serialize_full(project, asset_file),
-            });
-
-            deserialize_impl.append_all(quote! {
-                if let Some(field) = crate::util::bson::bson_get(data, #field_name_str) {
-                    if let Some(val) = <#ty>::obj_deserialize(project, field, parent, asset_file, metadata) {
-                        res.#field_name = val;
+        },
+        Data::Enum(data) => {
+            let mut variants_impl = quote!{};
+            for variant in data.variants {
+                let variant_name = variant.ident;
+                let bindings = (0..variant.fields.len()).map(|idx| format_ident!("__field{}", idx)).collect::<Vec<_>>();
+                variants_impl.append_all(match variant.fields {
+                    Fields::Named(fields) => {
+                        let names = fields.named.iter().map(|field| field.ident.clone()).collect::<Vec<_>>();
+                        quote! {
+                            Self::#variant_name { #(#names: #bindings),* } => Self::#variant_name { #(#names: #bindings.obj_clone(project)),* },
+                        }
+                    },
+                    Fields::Unnamed(_) => quote! {
+                        Self::#variant_name(#(#bindings),*) => Self::#variant_name(#(#bindings.obj_clone(project)),*),
+                    },
+                    Fields::Unit => quote! {
+                        Self::#variant_name => Self::#variant_name,
                     }
+                });
+            }
+            quote! {
+                match self {
+                    #variants_impl
                 }
-            });
-        } 
-
-        raw_data_struct_fields.append_all(quote! {
-            #field_name: <#ty as crate::project::obj::ToRawData>::RawData,
-        });
-
-        to_raw_data_impl.append_all(quote! {
-            #field_name: self.#field_name.to_raw_data(project),
-        });
-
-        from_raw_data_impl.append_all(quote! {
-            #field_name: <#ty as crate::project::obj::ToRawData>::from_raw_data(project, &data.#field_name),
-        });
-    }
+            }
+        },
+        Data::Union(_) => panic!("object must be a struct or an enum!")
+    };
 
     quote! {
 
-        
-        impl ObjSerialize for #name {
-
-            fn obj_serialize(&self, project: &Project, asset_file: &mut crate::project::saveload::asset_file::AssetFile) -> bson::Bson {
-                bson::bson! {{
-                    #serialize_impl
-                }}
-            }
-
-            fn obj_serialize_full(&self, project: &Project, asset_file: &mut crate::project::saveload::asset_file::AssetFile) -> bson::Bson {
-                bson::bson! {{
-                    #serialize_full_impl
-                }}
-            }
+        impl ObjClone for #name {
 
-            fn obj_deserialize(project: &mut Project, data: &bson::Bson, parent: crate::project::obj::DynObjPtr, asset_file: &mut crate::project::saveload::asset_file::AssetFile, metadata: &mut crate::project::saveload::load::LoadingMetadata) -> Option<Self> {
-                let mut res = Self::default();
-                #deserialize_impl 
-                Some(res)
+            #[allow(unused_variables)]
+            fn obj_clone(&self, project: &mut Project) -> Self {
+                #obj_clone_impl
             }
 
         }
 
-        pub struct #raw_data_name {
-            #raw_data_struct_fields
-        }
-
-        impl crate::project::obj::ToRawData for #name {
-
-            type RawData = #raw_data_name;
-
-            fn to_raw_data(&self, project: &Project) -> Self::RawData {
-                Self::RawData {
-                    #to_raw_data_impl
-                }
-            }
-
-            fn from_raw_data(project: &mut Project, data: &Self::RawData) -> Self {
-                Self {
-                    #from_raw_data_impl
-                }
-            }
-
-        }
     }.into()
+}
 
+#[proc_macro_derive(ObjSerialize, attributes(parent, field, serialize))]
+pub fn obj_serialize(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    obj_serialize::derive(ast).into()
 }
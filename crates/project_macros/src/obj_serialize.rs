@@ -0,0 +1,506 @@
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens, TokenStreamExt};
+use syn::{Attribute, Data, DataEnum, DeriveInput, Expr, Fields, Ident, LitStr, Meta};
+
+use crate::attr_list_contains;
+
+// Options set with #[serialize(...)], modeled after serde's field attributes, along with the default set with #[field(default)] or #[field(default = ...)]
+#[derive(Default)]
+struct SerializeAttrs {
+    rename: Option<String>,
+    aliases: Vec<String>,
+    default: Option<TokenStream>,
+    skip: bool,
+    // Enum variants written as just their data, without the variant name
+    untagged: bool
+}
+
+fn serialize_attrs(attrs: &[Attribute]) -> syn::Result<SerializeAttrs> {
+    let mut res = SerializeAttrs::default();
+    for attr in attrs {
+        if attr.path().is_ident("field") {
+            if let Meta::List(_) = attr.meta {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("default") {
+                        res.default = Some(if meta.input.peek(syn::Token![=]) {
+                            let default = meta.value()?.parse::<Expr>()?;
+                            quote! { #default }
+                        } else {
+                            quote! { Default::default() }
+                        });
+                        Ok(())
+                    } else {
+                        Err(meta.error("unknown field attribute"))
+                    }
+                })?;
+            }
+            continue;
+        }
+        if !attr.path().is_ident("serialize") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                res.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("alias") {
+                res.aliases.push(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("skip") {
+                res.skip = true;
+            } else if meta.path.is_ident("untagged") {
+                res.untagged = true;
+            } else {
+                return Err(meta.error("unknown serialize attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(res)
+}
+
+impl SerializeAttrs {
+
+    fn key(&self, name: String) -> String {
+        self.rename.clone().unwrap_or(name)
+    }
+
+    // Looks the field up under its key, then under each of its aliases
+    fn lookup(&self, name: String, data: TokenStream) -> TokenStream {
+        let key = self.key(name);
+        let mut res = quote! { crate::util::bson::bson_get(#data, #key) };
+        for alias in &self.aliases {
+            res.append_all(quote! { .or_else(|| crate::util::bson::bson_get(#data, #alias)) });
+        }
+        res
+    }
+
+}
+
+fn serialize_call(val: TokenStream, full: bool) -> TokenStream {
+    if full {
+        quote! { crate::project::obj::ObjSerialize::obj_serialize_full(#val, project, asset_file) }
+    } else {
+        quote! { crate::project::obj::ObjSerialize::obj_serialize(#val, project, asset_file) }
+    }
+}
+
+fn deserialize_call(ty: &syn::Type, val: TokenStream) -> TokenStream {
+    quote! { <#ty as crate::project::obj::ObjSerialize>::obj_deserialize(project, #val, parent, asset_file, metadata) }
+}
+
+// Local variables the fields are bound to, named so they can't shadow the function's arguments
+fn field_bindings(fields: &Fields) -> Vec<Ident> {
+    (0..fields.len()).map(|idx| format_ident!("__field{}", idx)).collect()
+}
+
+// Pattern binding each field to its local variable, also used to construct the value back from them
+fn destructure(fields: &Fields, bindings: &[Ident]) -> TokenStream {
+    match fields {
+        Fields::Named(_) => {
+            let names = fields.iter().map(|field| field.ident.clone().unwrap());
+            quote! { { #(#names: #bindings),* } }
+        },
+        Fields::Unnamed(_) => quote! { ( #(#bindings),* ) },
+        Fields::Unit => quote! {}
+    }
+}
+
+fn construct(fields: &Fields, bindings: &[Ident]) -> TokenStream {
+    destructure(fields, bindings)
+}
+
+fn field_name(field: &syn::Field, idx: usize) -> String {
+    field.ident.as_ref().map(|ident| ident.to_string()).unwrap_or(idx.to_string())
+}
+
+/*
+    Serializes a list of fields, already bound to local variables.
+    Named fields become a document, unnamed fields an array, and a single unnamed field is written as-is.
+*/
+fn serialize_fields(fields: &Fields, bindings: &[Ident], full: bool) -> syn::Result<TokenStream> {
+    let mut entries = quote! {};
+    let mut newtype = None;
+    for (field, binding) in fields.iter().zip(bindings.iter()) {
+        let attrs = serialize_attrs(&field.attrs)?;
+        if attrs.skip || attr_list_contains(&field.attrs, "parent") {
+            continue;
+        }
+        let val = serialize_call(binding.to_token_stream(), full);
+        match field.ident.as_ref() {
+            Some(ident) => {
+                let key = attrs.key(ident.to_string());
+                entries.append_all(quote! { #key: #val, });
+            },
+            None => {
+                if fields.len() == 1 {
+                    newtype = Some(val.clone());
+                }
+                entries.append_all(quote! { #val, });
+            }
+        }
+    }
+    Ok(match (fields, newtype) {
+        (Fields::Unnamed(_), Some(val)) => val,
+        (Fields::Named(_) | Fields::Unit, _) => quote! { bson::bson!({ #entries }) },
+        (Fields::Unnamed(_), None) => quote! { bson::bson!([ #entries ]) }
+    })
+}
+
+/*
+    Deserializes a list of fields from `data` into local variables.
+    Fields missing from the data fall back to the default given in their attributes. Struct fields without one use their type's Default,
+    while enum variant fields without one fail, so that untagged variants don't match data meant for another variant.
+    Fields with a default that can't be read are also replaced by it, without reporting an error.
+*/
+fn deserialize_fields(fields: &Fields, bindings: &[Ident], data: TokenStream, type_name: &str, type_defaults: bool) -> syn::Result<TokenStream> {
+    let mut res = quote! {};
+    let mut array_idx = 0usize;
+    for (idx, (field, binding)) in fields.iter().zip(bindings.iter()).enumerate() {
+        let attrs = serialize_attrs(&field.attrs)?;
+        let ty = &field.ty;
+        let field_name = field_name(field, idx);
+        let default = attrs.default.clone().or_else(|| type_defaults.then(|| quote! { <#ty as Default>::default() }));
+        let missing = match &default {
+            Some(default) => quote! {{
+                log::debug!("{} field '{}' missing, using default.", #type_name, #field_name);
+                #default
+            }},
+            None => quote! {{
+                metadata.deserialization_error(format!("{} field '{}' missing.", #type_name, #field_name), parent.key);
+                return None;
+            }}
+        };
+
+        // The parent isn't saved, and is set once the object is added to the one it's loaded into
+        if attrs.skip || attr_list_contains(&field.attrs, "parent") {
+            let default = default.unwrap_or(quote! { Default::default() });
+            res.append_all(quote! { let #binding: #ty = #default; });
+            continue;
+        }
+
+        let lookup = match field.ident.as_ref() {
+            Some(ident) => attrs.lookup(ident.to_string(), data.clone()),
+            None if fields.len() == 1 => quote! { Some(#data) },
+            None => {
+                array_idx += 1;
+                let idx = array_idx - 1;
+                quote! { #data.as_array().and_then(|arr| arr.get(#idx)) }
+            }
+        };
+
+        let deserialize = deserialize_call(ty, quote! { field });
+        let bad_value = match &default {
+            Some(default) => quote! {{
+                metadata.errors.truncate(n_errors);
+                log::debug!("{} field '{}' could not be read, using default.", #type_name, #field_name);
+                #default
+            }},
+            None => quote! { return None }
+        };
+        res.append_all(quote! {
+            let #binding: #ty = match #lookup {
+                Some(field) => {
+                    let n_errors = metadata.errors.len();
+                    match #deserialize {
+                        Some(val) => val,
+                        None => #bad_value
+                    }
+                },
+                None => #missing
+            };
+        });
+    }
+    Ok(res)
+}
+
+fn raw_data_fields(fields: &Fields) -> TokenStream {
+    let types = fields.iter().map(|field| {
+        let ty = &field.ty;
+        let raw_ty = quote! { <#ty as crate::project::obj::ToRawData>::RawData };
+        match &field.ident {
+            Some(ident) => quote! { #ident: #raw_ty },
+            None => raw_ty
+        }
+    });
+    match fields {
+        Fields::Named(_) => quote! { { #(#types),* } },
+        Fields::Unnamed(_) => quote! { ( #(#types),* ) },
+        Fields::Unit => quote! {}
+    }
+}
+
+fn to_raw_data_fields(fields: &Fields, bindings: &[Ident]) -> TokenStream {
+    let vals = fields.iter().zip(bindings.iter()).map(|(field, binding)| {
+        let val = quote! { crate::project::obj::ToRawData::to_raw_data(#binding, project) };
+        match &field.ident {
+            Some(ident) => quote! { #ident: #val },
+            None => val
+        }
+    });
+    match fields {
+        Fields::Named(_) => quote! { { #(#vals),* } },
+        Fields::Unnamed(_) => quote! { ( #(#vals),* ) },
+        Fields::Unit => quote! {}
+    }
+}
+
+fn from_raw_data_fields(fields: &Fields, bindings: &[Ident]) -> TokenStream {
+    let vals = fields.iter().zip(bindings.iter()).map(|(field, binding)| {
+        let ty = &field.ty;
+        let val = quote! { <#ty as crate::project::obj::ToRawData>::from_raw_data(project, #binding) };
+        match &field.ident {
+            Some(ident) => quote! { #ident: #val },
+            None => val
+        }
+    });
+    match fields {
+        Fields::Named(_) => quote! { { #(#vals),* } },
+        Fields::Unnamed(_) => quote! { ( #(#vals),* ) },
+        Fields::Unit => quote! {}
+    }
+}
+
+struct SerializeImpl {
+    serialize: TokenStream,
+    serialize_full: TokenStream,
+    deserialize: TokenStream,
+    raw_data: TokenStream,
+    to_raw_data: TokenStream,
+    from_raw_data: TokenStream
+}
+
+fn struct_impl(name: &Ident, raw_data_name: &Ident, fields: &Fields) -> syn::Result<SerializeImpl> {
+    let type_name = name.to_string();
+    let bindings = field_bindings(fields);
+    let pattern = destructure(fields, &bindings);
+    let construct = construct(fields, &bindings);
+    let serialize = serialize_fields(fields, &bindings, false)?;
+    let serialize_full = serialize_fields(fields, &bindings, true)?;
+    let deserialize = deserialize_fields(fields, &bindings, quote! { data }, &type_name, true)?;
+    let raw_data = raw_data_fields(fields);
+    let to_raw_data = to_raw_data_fields(fields, &bindings);
+    let from_raw_data = from_raw_data_fields(fields, &bindings);
+    let semicolon = if let Fields::Named(_) = fields { quote! {} } else { quote! { ; } };
+
+    Ok(SerializeImpl {
+        serialize: quote! {
+            let Self #pattern = self;
+            #serialize
+        },
+        serialize_full: quote! {
+            let Self #pattern = self;
+            #serialize_full
+        },
+        deserialize: quote! {
+            #deserialize
+            Some(Self #construct)
+        },
+        raw_data: quote! { struct #raw_data_name #raw_data #semicolon },
+        to_raw_data: quote! {
+            let Self #pattern = self;
+            #raw_data_name #to_raw_data
+        },
+        from_raw_data: quote! {
+            let #raw_data_name #pattern = data;
+            Self #from_raw_data
+        }
+    })
+}
+
+/*
+    Enums use serde's externally tagged layout: unit variants are written as their name,
+    and variants with data as a single entry document mapping the name to the fields.
+    Untagged variants are written as just their fields, and are tried in order when the data isn't tagged.
+*/
+fn enum_impl(name: &Ident, raw_data_name: &Ident, data: &DataEnum) -> syn::Result<SerializeImpl> {
+    let type_name = name.to_string();
+
+    let mut serialize = quote! {};
+    let mut serialize_full = quote! {};
+    let mut deserialize = quote! {};
+    let mut deserialize_untagged = quote! {};
+    let mut raw_data = quote! {};
+    let mut to_raw_data = quote! {};
+    let mut from_raw_data = quote! {};
+    for variant in &data.variants {
+        let variant_name = &variant.ident;
+        let attrs = serialize_attrs(&variant.attrs)?;
+        let key = attrs.key(variant_name.to_string());
+        let mut keys = vec![key.clone()];
+        keys.extend(attrs.aliases.iter().cloned());
+
+        let fields = &variant.fields;
+        let bindings = field_bindings(fields);
+        let pattern = destructure(fields, &bindings);
+        let construct = construct(fields, &bindings);
+
+        if let Fields::Unit = fields {
+            if attrs.untagged {
+                return Err(syn::Error::new_spanned(variant, "unit variants can't be untagged"));
+            }
+            serialize.append_all(quote! { Self::#variant_name => bson::Bson::String(#key.to_owned()), });
+            serialize_full.append_all(quote! { Self::#variant_name => bson::Bson::String(#key.to_owned()), });
+            deserialize.append_all(quote! { #(#keys)|* => return Some(Self::#variant_name), });
+        } else if attrs.untagged {
+            let variant_serialize = serialize_fields(fields, &bindings, false)?;
+            let variant_serialize_full = serialize_fields(fields, &bindings, true)?;
+            let variant_type_name = format!("{}::{}", type_name, variant_name);
+            let variant_deserialize = deserialize_fields(fields, &bindings, quote! { data }, &variant_type_name, false)?;
+            serialize.append_all(quote! { Self::#variant_name #pattern => #variant_serialize, });
+            serialize_full.append_all(quote! { Self::#variant_name #pattern => #variant_serialize_full, });
+            // Data that doesn't match this variant isn't an error unless no other variant matches either
+            deserialize_untagged.append_all(quote! {{
+                let n_errors = metadata.errors.len();
+                let res = (|| {
+                    #variant_deserialize
+                    Some(Self::#variant_name #construct)
+                })();
+                if res.is_some() {
+                    return res;
+                }
+                metadata.errors.truncate(n_errors);
+            }});
+        } else {
+            let variant_serialize = serialize_fields(fields, &bindings, false)?;
+            let variant_serialize_full = serialize_fields(fields, &bindings, true)?;
+            let variant_type_name = format!("{}::{}", type_name, variant_name);
+            let variant_deserialize = deserialize_fields(fields, &bindings, quote! { value }, &variant_type_name, false)?;
+            serialize.append_all(quote! { Self::#variant_name #pattern => bson::bson!({ #key: #variant_serialize }), });
+            serialize_full.append_all(quote! { Self::#variant_name #pattern => bson::bson!({ #key: #variant_serialize_full }), });
+            deserialize.append_all(quote! {
+                #(#keys)|* => {
+                    let value = if let Some(value) = value {
+                        value
+                    } else {
+                        metadata.deserialization_error(format!("{} is missing its data.", #variant_type_name), parent.key);
+                        return None;
+                    };
+                    #variant_deserialize
+                    return Some(Self::#variant_name #construct);
+                },
+            });
+        }
+
+        let variant_raw_data = raw_data_fields(fields);
+        let variant_to_raw_data = to_raw_data_fields(fields, &bindings);
+        let variant_from_raw_data = from_raw_data_fields(fields, &bindings);
+        raw_data.append_all(quote! { #variant_name #variant_raw_data, });
+        to_raw_data.append_all(quote! { Self::#variant_name #pattern => #raw_data_name::#variant_name #variant_to_raw_data, });
+        from_raw_data.append_all(quote! { #raw_data_name::#variant_name #pattern => Self::#variant_name #variant_from_raw_data, });
+    }
+
+    Ok(SerializeImpl {
+        serialize: quote! {
+            match self {
+                #serialize
+            }
+        },
+        serialize_full: quote! {
+            match self {
+                #serialize_full
+            }
+        },
+        deserialize: quote! {
+            let tagged = match data {
+                bson::Bson::String(variant) => Some((variant.as_str(), None)),
+                bson::Bson::Document(doc) if doc.len() == 1 => {
+                    let (variant, value) = doc.iter().next().unwrap();
+                    Some((variant.as_str(), Some(value)))
+                },
+                _ => None
+            };
+            if let Some((variant, value)) = tagged {
+                match variant {
+                    #deserialize
+                    _ => {}
+                }
+            }
+            #deserialize_untagged
+            match tagged {
+                Some((variant, _)) => metadata.deserialization_error(format!("Unknown {} variant '{}'.", #type_name, variant), parent.key),
+                None => metadata.deserialization_error(format!("{} should be a variant name or a single entry document.", #type_name), parent.key)
+            }
+            None
+        },
+        raw_data: quote! {
+            enum #raw_data_name {
+                #raw_data
+            }
+        },
+        to_raw_data: quote! {
+            match self {
+                #to_raw_data
+            }
+        },
+        from_raw_data: quote! {
+            match data {
+                #from_raw_data
+            }
+        }
+    })
+}
+
+pub fn derive(ast: DeriveInput) -> TokenStream {
+    let name = &ast.ident;
+    let vis = &ast.vis;
+    let raw_data_name = format_ident!("{}RawData", name);
+
+    let serialize_impl = match &ast.data {
+        Data::Struct(data) => struct_impl(name, &raw_data_name, &data.fields),
+        Data::Enum(data) => enum_impl(name, &raw_data_name, data),
+        Data::Union(_) => Err(syn::Error::new_spanned(name, "ObjSerialize cannot be derived for unions"))
+    };
+    // Mistakes in the attributes are reported as errors pointing at the attribute, instead of panicking
+    let SerializeImpl {
+        serialize,
+        serialize_full,
+        deserialize,
+        raw_data,
+        to_raw_data,
+        from_raw_data
+    } = match serialize_impl {
+        Ok(serialize_impl) => serialize_impl,
+        Err(err) => return err.to_compile_error()
+    };
+
+    quote! {
+
+        impl crate::project::obj::ObjSerialize for #name {
+
+            #[allow(unused_variables)]
+            fn obj_serialize(&self, project: &crate::project::Project, asset_file: &mut crate::project::saveload::asset_file::AssetFile) -> bson::Bson {
+                #serialize
+            }
+
+            #[allow(unused_variables)]
+            fn obj_serialize_full(&self, project: &crate::project::Project, asset_file: &mut crate::project::saveload::asset_file::AssetFile) -> bson::Bson {
+                #serialize_full
+            }
+
+            #[allow(unused_variables)]
+            fn obj_deserialize(project: &mut crate::project::Project, data: &bson::Bson, parent: crate::project::obj::DynObjPtr, asset_file: &mut crate::project::saveload::asset_file::AssetFile, metadata: &mut crate::project::saveload::load::LoadingMetadata) -> Option<Self> {
+                #deserialize
+            }
+
+        }
+
+        #vis #raw_data
+
+        impl crate::project::obj::ToRawData for #name {
+
+            type RawData = #raw_data_name;
+
+            #[allow(unused_variables)]
+            fn to_raw_data(&self, project: &crate::project::Project) -> Self::RawData {
+                #to_raw_data
+            }
+
+            #[allow(unused_variables)]
+            fn from_raw_data(project: &mut crate::project::Project, data: &Self::RawData) -> Self {
+                #from_raw_data
+            }
+
+        }
+
+    }
+}